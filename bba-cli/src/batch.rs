@@ -4,21 +4,90 @@
 //! and writes rich PBN output matching BBA.exe format.
 
 use anyhow::{Context, Result};
-use bridge_parsers::pbn::reader::read_pbn_file as bp_read_pbn;
 use bridge_parsers::{Board, Deal, Direction};
+use crate::csv_export;
+use crate::focus;
+use crate::formats::lin::LinBoard;
+use crate::pbn::{self, InputFile, PbnDate, PbnHeader, RawGame, TagFilter};
 use epbot_core::bba_hash::{self, HandSuits};
 use epbot_core::score::{self, Strain};
-use epbot_core::{generate_auction_with_options, ConventionCard, Scoring};
-use log::{debug, error, info};
+use epbot_core::{generate_auction_with, AuctionOptions, ConventionCard, Scoring};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
 use std::io::{BufWriter, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
 
 /// Statistics from batch processing
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ProcessingStats {
     pub deals_processed: usize,
     pub auctions_generated: usize,
     pub errors: usize,
+    /// Games copied through unchanged (`--since`, an auction that disagrees
+    /// with its Dealer tag, or an input with no deal to bid).
+    pub deals_skipped: usize,
+    /// Boards dropped by `--par-disagreements` (on par, or no DD data).
+    pub deals_filtered: usize,
+    /// Auctions of four passes, however `--passout-handling` treated them.
+    pub passouts: usize,
+    /// Auctions scored against double-dummy par (`--double-dummy`,
+    /// `--par-disagreements`), and how many of them reached it.
+    pub par_checked: usize,
+    pub par_reached: usize,
+}
+
+impl ProcessingStats {
+    /// Fold another file's counts into these (multi-file runs).
+    pub fn add(&mut self, other: &ProcessingStats) {
+        self.deals_processed += other.deals_processed;
+        self.auctions_generated += other.auctions_generated;
+        self.errors += other.errors;
+        self.deals_skipped += other.deals_skipped;
+        self.deals_filtered += other.deals_filtered;
+        self.passouts += other.passouts;
+        self.par_checked += other.par_checked;
+        self.par_reached += other.par_reached;
+    }
+}
+
+/// One auction generated for a board.
+///
+/// A board normally has a single run. With `--rotate-dealers` and/or
+/// `--swap-sides` it has several; each run is written as its own game,
+/// labeled with a `[Room]` tag, so re-reading the output never merges them.
+pub struct BoardRun {
+    /// `[Room]` label; only set when the board has more than one run.
+    pub label: Option<String>,
+    pub dealer: Direction,
+    /// NS and EW convention cards (and system names) exchanged.
+    pub swapped: bool,
+    pub result: epbot_core::AuctionResult,
+    /// Double-dummy par, computed under `--par-disagreements` or
+    /// `--double-dummy` and written as `[OptimumScore]`, `[ParScore]` and
+    /// `[ParContract]`.
+    pub par: Option<score::Par>,
+    /// Tricks each declarer makes in each strain, solved under
+    /// `--double-dummy` and written as `[DoubleDummyTricks]` and
+    /// `[OptimumResultTable]`.
+    pub dd_tricks: Option<[[u8; 5]; 4]>,
+    /// NS score of the bid contract played double dummy, whenever par is
+    /// computed and an auction was generated.
+    pub dd_score: Option<i32>,
+    /// Distinct auctions and how often each came up under `--repeat`, most
+    /// frequent first; empty without `--repeat`.
+    pub variants: Vec<(String, usize)>,
+}
+
+/// What gets written for one input board.
+pub enum BoardOutput {
+    /// Generated auctions, one game per run.
+    Bid(Vec<BoardRun>),
+    /// The input game's text, copied through untouched (`--since`).
+    Unchanged(String),
 }
 
 /// Configuration for PBN output formatting
@@ -28,11 +97,128 @@ pub struct OutputConfig {
     pub ew_system_name: String,
     pub ns_conventions_path: String,
     pub ew_conventions_path: String,
+    /// Cards for single seats, indexed N, E, S, W, as (content, source)
+    /// (`--north-conventions` etc.). Each replaces its side's card for that
+    /// seat's own bidding only.
+    pub seat_conventions: [Option<(String, String)>; 4],
     pub scoring: Scoring,
     pub single_dummy: bool,
+    /// Truncate each auction once a bid reaches this level (`--stop-at-level`).
+    pub stop_at_level: Option<u8>,
+    /// Partnership forced to pass throughout (0 = NS, 1 = EW) for
+    /// uncontested auctions (`--no-opponents`).
+    pub passing_side: Option<u8>,
+    /// Engine bidding-style setting switched on for both sides (`--style`).
+    pub style: Option<String>,
+    /// Conventions set on top of the cards (`--set-convention`).
+    pub conventions: Vec<epbot_core::ConventionOverride>,
+    /// Abort on any Deal tag that doesn't parse (`--strict-parse`).
+    pub strict_parse: bool,
+    /// Fail deals with no Dealer / Vulnerable tag that their board number
+    /// can't supply, instead of defaulting to North / None
+    /// (`--require-dealer`, `--require-vulnerability`).
+    pub require_dealer: bool,
+    pub require_vulnerability: bool,
+    /// Number the boards from this one on, in input order
+    /// (`--renumber-from`).
+    pub renumber_from: Option<u32>,
+    /// Rewrite the input without bidding (`--passthrough`).
+    pub passthrough: bool,
+    /// Also bid each deal with every other seat as dealer (`--rotate-dealers`).
+    pub rotate_dealers: bool,
+    /// Also bid each deal with the NS/EW conventions exchanged (`--swap-sides`).
+    pub swap_sides: bool,
+    /// Start each auction from the calls already in the game's Auction
+    /// section (`--continue-auctions`).
+    pub continue_auctions: bool,
+    /// Only bid games whose `since_tag` date is on or after this date
+    /// (`--since`); older games are copied through unchanged.
+    pub since: Option<PbnDate>,
+    /// Tag holding each game's date (`--since-tag`, default `Date`).
+    pub since_tag: String,
+    /// Also skip games whose date is missing or unparseable (`--since-strict`).
+    pub since_strict: bool,
+    /// Keep only auctions whose double-dummy result is at least this many
+    /// IMPs off par (`--par-disagreements` / `--par-threshold`).
+    pub par_threshold: Option<u32>,
+    /// Solve each deal double dummy instead of reading `[DoubleDummyTricks]`
+    /// (`--double-dummy`).
+    pub double_dummy: bool,
+    /// Tag names written for the generated contract, declarer, and auction
+    /// (`--contract-tag`, `--declarer-tag`, `--auction-tag`).
+    pub contract_tag: String,
+    pub declarer_tag: String,
+    pub auction_tag: String,
+    /// Fill the last auction line out to four columns with `-`
+    /// (`--pad-auction-grid`)
+    pub pad_auction_grid: bool,
+    /// What to do with passed-out auctions (`--passout-handling`).
+    pub passout_handling: PassoutHandling,
+    /// PBN or CSV output (`--output-format`).
+    pub output_format: OutputFormat,
+    /// How the calls of generated auctions are laid out (`--auction-layout`).
+    pub auction_layout: AuctionLayout,
+    /// Fixed width of each grid column (`--auction-column-width`); None
+    /// keeps the default spacing.
+    pub auction_column_width: Option<usize>,
+    /// How the Vulnerable tag of generated games is spelled
+    /// (`--vulnerability-style`); copied games keep theirs.
+    pub vulnerability_style: VulnerabilityStyle,
+    /// In a `--threads` worker, (k, n): bid only the k-th of n equal shares
+    /// of the boards and write nothing for the rest.
+    pub chunk: Option<(usize, usize)>,
+    /// Write `[BBAIndex "N"]`, the game's 1-based position in the input
+    /// (matching "Game N" in log messages), into each generated game
+    /// (`--annotate-index`).
+    pub annotate_index: bool,
+    /// Note each generated call with its bidder's HCP and suit lengths
+    /// (`--annotate-strength`).
+    pub annotate_strength: bool,
+    /// Bid each run this many times and keep the most common auction
+    /// (`--repeat`); 1 bids once.
+    pub repeat: u32,
+    /// Abort after this many failed deals in a row
+    /// (`--max-consecutive-errors`).
+    pub max_consecutive_errors: Option<usize>,
+    /// NS and EW tag names for `--stamp-conventions`, which records each
+    /// game's convention file names; None leaves them out.
+    pub stamp_tags: Option<(String, String)>,
+    /// Write a provenance block (bba-cli and EPBot versions, conventions,
+    /// UTC time) into the leading `%` header (`--stamp-metadata`).
+    pub stamp_metadata: bool,
+    /// Flush the output after every this many games (`--flush-every`)
+    pub flush_every: usize,
+    /// Sync the output to disk at every flush (`--fsync`)
+    pub fsync: bool,
+    /// Print `Board N: contract by declarer` per auction (`--print-contracts`)
+    pub print_contracts: bool,
+    /// Directory for per-auction engine input/output dumps (`--dump-engine-io`)
+    pub dump_engine_io: Option<PathBuf>,
+    /// Seat (0..4 for N, E, S, W) whose calls go to the `--focus-seat` report
+    pub focus_seat: Option<u8>,
+    /// Where the focus report goes; None for stdout
+    pub focus_report: Option<PathBuf>,
+    /// Re-read the written file and check its games and auctions
+    pub verify_output: bool,
+    /// How input bytes are decoded (`--encoding`)
+    pub encoding: pbn::InputEncoding,
+    /// Replace `x` spot cards in Deal tags with real cards (`--fill-spots`)
+    pub fill_spots: bool,
+    /// Rewrite Deal tags of games copied from the input to start from North
+    /// (`--canonical-first-seat`). Generated games always start from North.
+    pub canonical_first_seat: bool,
+    /// Add `"?"` Event, Site, Date, Board and player tags to games copied
+    /// from the input that lack them (on unless `--no-placeholder-tags`).
+    /// `--passthrough` leaves games as read.
+    pub placeholder_tags: bool,
+    /// Tags written back for games copied from the input (`--passthrough`,
+    /// `--since`, inconsistent auctions). Generated games are unaffected.
+    pub tag_filter: TagFilter,
+    /// Wall-clock limit per auction (`--timeout`); None waits indefinitely.
+    pub timeout: Option<Duration>,
 }
 
-fn direction_to_int(dir: Direction) -> i32 {
+pub(crate) fn direction_to_int(dir: Direction) -> i32 {
     match dir {
         Direction::North => 0,
         Direction::East => 1,
@@ -41,7 +227,7 @@ fn direction_to_int(dir: Direction) -> i32 {
     }
 }
 
-fn direction_char(dir: Direction) -> &'static str {
+pub(crate) fn direction_char(dir: Direction) -> &'static str {
     match dir {
         Direction::North => "N",
         Direction::East => "E",
@@ -50,6 +236,15 @@ fn direction_char(dir: Direction) -> &'static str {
     }
 }
 
+fn int_to_direction(pos: i32) -> Direction {
+    match pos.rem_euclid(4) {
+        0 => Direction::North,
+        1 => Direction::East,
+        2 => Direction::South,
+        _ => Direction::West,
+    }
+}
+
 fn int_to_direction_char(pos: i32) -> &'static str {
     match pos % 4 {
         0 => "N",
@@ -71,6 +266,69 @@ fn vulnerability_to_epbot(vul: &bridge_parsers::Vulnerability) -> i32 {
     }
 }
 
+/// Layout of the calls in generated auctions (`--auction-layout`). Both
+/// are read back by `RawGame::auction_calls`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuctionLayout {
+    /// Four calls to a line, starting with the dealer
+    #[default]
+    Grid,
+    /// One call per line, after the caller's seat: `N 1H`
+    Vertical,
+}
+
+/// What the output file holds (`--output-format`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A PBN file, one game per auction
+    #[default]
+    Pbn,
+    /// A CSV table, one row per auction (see `csv_export`)
+    Csv,
+    /// BBO hand-viewer movies, one line per auction (see `formats::lin`)
+    Lin,
+}
+
+/// What happens to an auction the engine passes out
+/// (`--passout-handling`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PassoutHandling {
+    /// Written like any other auction, with `[Contract "Pass"]`
+    #[default]
+    Record,
+    /// Left out of the output
+    Skip,
+    /// Written as a failed auction and counted as an error
+    Error,
+}
+
+/// Spelling of the Vulnerable tag in generated games
+/// (`--vulnerability-style`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VulnerabilityStyle {
+    /// None, NS, EW, All
+    #[default]
+    Short,
+    /// None, N/S, E/W, All
+    Hyphen,
+    /// Love All, N/S, E/W, Game All
+    British,
+}
+
+impl VulnerabilityStyle {
+    fn tag_value(self, vul: i32) -> &'static str {
+        match (self, vul) {
+            (VulnerabilityStyle::Short, _) => vulnerability_to_pbn(vul),
+            (VulnerabilityStyle::British, 0) => "Love All",
+            (VulnerabilityStyle::British, 3) => "Game All",
+            (_, 1) => "E/W",
+            (_, 2) => "N/S",
+            (_, 3) => "All",
+            _ => "None",
+        }
+    }
+}
+
 fn vulnerability_to_pbn(vul: i32) -> &'static str {
     match vul {
         0 => "None",
@@ -157,13 +415,7 @@ fn format_deal_pbn(deal: &Deal) -> String {
                 bridge_parsers::Suit::Clubs,
             ]
             .iter()
-            .map(|&suit| {
-                let cards = hand.cards_in_suit(suit);
-                cards
-                    .iter()
-                    .map(|c| format!("{}", c.rank))
-                    .collect::<String>()
-            })
+            .map(|&suit| suit_string_with_t(&hand, suit))
             .collect();
             suits.join(".")
         })
@@ -173,86 +425,1405 @@ fn format_deal_pbn(deal: &Deal) -> String {
 
 /// Process a PBN file, generating auctions for each deal.
 ///
+/// `ns_conventions` / `ew_conventions` are the `.bbsa` file contents, already
+/// loaded from disk or a system pack; None bids with EPBot's defaults.
+///
 /// `auction_prefix`, if provided, forces the first N bids of every auction
 /// before EPBot resumes normal bidding. Mirrors the bba-server `auctionPrefix`
 /// field so the CLI and server stay interchangeable for A/B testing.
 pub fn process_pbn_file(
     input_path: &Path,
     output_path: &Path,
-    ns_conventions: &Path,
-    ew_conventions: &Path,
+    ns_conventions: Option<&str>,
+    ew_conventions: Option<&str>,
     dry_run: bool,
     config: &OutputConfig,
     auction_prefix: Option<&[String]>,
 ) -> Result<ProcessingStats> {
+    process_pbn_file_with_results(
+        input_path,
+        output_path,
+        ns_conventions,
+        ew_conventions,
+        dry_run,
+        config,
+        auction_prefix,
+    )
+    .map(|(stats, _)| stats)
+}
+
+/// `process_pbn_file`, also returning what was produced for each game as
+/// (game index, result), so embedders can use the auctions without
+/// re-reading the output. Indexes are 1-based, as in "Game N" log messages
+/// and `[BBAIndex]`. `--passthrough` bids nothing and returns no results.
+pub fn process_pbn_file_with_results(
+    input_path: &Path,
+    output_path: &Path,
+    ns_conventions: Option<&str>,
+    ew_conventions: Option<&str>,
+    dry_run: bool,
+    config: &OutputConfig,
+    auction_prefix: Option<&[String]>,
+) -> Result<(ProcessingStats, Vec<(usize, BoardOutput)>)> {
     let mut stats = ProcessingStats::default();
 
     info!("Reading PBN file: {:?}", input_path);
-    let boards = bp_read_pbn(input_path).context("Failed to parse PBN file")?;
+    let mut input = InputFile::read(input_path, config.encoding)?;
+    for e in pbn::RawFile::parse(&input.text).malformed {
+        warn!("{:?}: not a tag, {}", input_path, e);
+    }
+
+    if config.passthrough {
+        let stats = passthrough_pbn_file(&input.text, output_path, dry_run, config)?;
+        return Ok((stats, Vec::new()));
+    }
+    let (tidied, filled) = tidy_deals(&mut input, config);
+    if tidied > 0 {
+        debug!("Normalized whitespace in {} Deal tags", tidied);
+    }
+    if filled > 0 {
+        info!("Filled x spot cards in {} deals", filled);
+    }
+
+    // Nothing to bid (an empty file, or only games without a Deal tag such
+    // as a standalone header block): don't start EPBot at all.
+    if deal_games(&input.text).is_empty() {
+        info!("Input has no Deal tags; copying it unchanged");
+        return copy_without_bidding(&input.text, output_path, dry_run, config);
+    }
+
+    let mut boards = input.boards()?;
     info!("Found {} games in input file", boards.len());
+    let header = PbnHeader::parse(&input.text);
+    if config.strict_parse {
+        check_deals_strict(&input.text, boards.len(), 0)?;
+    }
+    if header.is_strict() {
+        info!("Input declares % EXPORT: games without a Dealer tag will be rejected");
+    }
+
+    if boards.is_empty() {
+        info!("No deal in the input parses; copying its games unchanged");
+        return copy_without_bidding(&input.text, output_path, dry_run, config);
+    }
 
-    // Load convention cards
-    let ns_content = std::fs::read_to_string(ns_conventions)
-        .context("Failed to read NS conventions file")?;
-    let ew_content = std::fs::read_to_string(ew_conventions)
-        .context("Failed to read EW conventions file")?;
-    let ns_card = ConventionCard::from_content(&ns_content);
-    let ew_card = ConventionCard::from_content(&ew_content);
+    let cards = RunCards::new(ns_conventions, ew_conventions, config);
+
+    // Raw game text lines up with bridge-parsers' boards only when every
+    // Deal tag parsed; features that need per-game tags require that.
+    let raw_games = deal_games(&input.text);
+    let games_aligned = raw_games.len() == boards.len();
+    if !games_aligned {
+        if config.since.is_some()
+            || config.continue_auctions
+            || config.par_threshold.is_some()
+            || config.require_dealer
+            || config.require_vulnerability
+            || config.renumber_from.is_some()
+        {
+            anyhow::bail!(
+                "{} Deal tags in input but {} boards parsed; cannot match games to their tags",
+                raw_games.len(),
+                boards.len()
+            );
+        }
+        debug!("Deal tags and parsed boards differ; skipping auction consistency checks and board tags");
+    }
+    let missing_tags = if games_aligned {
+        fill_board_tags(&mut boards, &raw_games, config, 0, header.is_strict())
+    } else {
+        vec![Vec::new(); boards.len()]
+    };
+
+    if let Some(ref dir) = config.dump_engine_io {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
 
     // Process each deal
-    let mut results = Vec::new();
+    let mut results: Vec<BoardOutput> = Vec::new();
+    let mut bidder = GameBidder::new(config, cards.options(config, auction_prefix), header.is_strict());
+
+    // Games are written as soon as their board is done, so an interrupted
+    // run leaves a valid prefix of the output.
+    let mut out = if dry_run {
+        None
+    } else {
+        info!("Writing output to {:?}", output_path);
+        Some(PbnOutput::create(output_path, &header, config)?)
+    };
+
+    let share = config
+        .chunk
+        .map(|(k, n)| boards.len() * k / n..boards.len() * (k + 1) / n);
 
     for (idx, board) in boards.iter().enumerate() {
+        if let Some(ref mut out) = out {
+            out.write_pending(&boards, &results, config)?;
+        }
+        if share.as_ref().is_some_and(|share| !share.contains(&idx)) {
+            results.push(BoardOutput::Bid(Vec::new()));
+            continue;
+        }
+        let game = games_aligned.then(|| &raw_games[idx]);
+        results.push(bidder.bid(idx, board, game, &missing_tags[idx], &mut stats)?);
+    }
+
+    if let Some(mut out) = out {
+        out.write_pending(&boards, &results, config)?;
+        out.finish()?;
+        if config.verify_output {
+            verify_output(output_path, expected_games(&results, config))?;
+        }
+    }
+
+    if let Some(seat) = config.focus_seat {
+        write_focus_report(&boards, &results, seat, config.focus_report.as_deref())?;
+    }
+
+    if config.print_contracts {
+        print_contracts(&boards, &results, 0);
+    }
+
+    let results = results.into_iter().enumerate().map(|(idx, r)| (idx + 1, r)).collect();
+    Ok((stats, results))
+}
+
+/// `--stream`: bid `input_path` a game at a time as it is read, so the
+/// input is never held in memory whole. Each game is decoded as
+/// `--encoding` says, tidied, parsed, bid and written before the next one
+/// is read, and the output is what a normal run writes. Stats are those of
+/// the whole file; there are no per-game results.
+pub fn process_pbn_file_streaming(
+    input_path: &Path,
+    output_path: &Path,
+    ns_conventions: Option<&str>,
+    ew_conventions: Option<&str>,
+    dry_run: bool,
+    config: &OutputConfig,
+    auction_prefix: Option<&[String]>,
+) -> Result<ProcessingStats> {
+    let input: Box<dyn std::io::BufRead> = if pbn::is_stdio(input_path) {
+        Box::new(std::io::stdin().lock())
+    } else {
+        let file = File::open(input_path).with_context(|| format!("Failed to open {:?}", input_path))?;
+        Box::new(std::io::BufReader::new(file))
+    };
+    info!("Reading PBN file: {:?}", input_path);
+    let mut reader = pbn::PbnReader::with_encoding(input, config.encoding);
+    let read_error = || format!("Failed to read {:?}", input_path);
+
+    // Games without a Deal tag are dropped, as in a normal run, unless the
+    // file has no deal at all and is copied unchanged; so they are kept
+    // until the first deal turns up.
+    let mut dealless = String::new();
+    let first = loop {
+        match reader.next().transpose().with_context(read_error)? {
+            Some(game) if game.tag("Deal").is_some() => break game,
+            Some(game) => dealless.push_str(&game.text),
+            None => {
+                info!("Input has no Deal tags; copying it unchanged");
+                let text = format!("{}{}", reader.preamble(), dealless);
+                return copy_without_bidding(&text, output_path, dry_run, config).map(|(stats, _)| stats);
+            }
+        }
+    };
+    drop(dealless);
+
+    let header = PbnHeader::parse(reader.preamble());
+    if header.is_strict() {
+        info!("Input declares % EXPORT: games without a Dealer tag will be rejected");
+    }
+    if let Some(ref dir) = config.dump_engine_io {
+        std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+
+    let cards = RunCards::new(ns_conventions, ew_conventions, config);
+    let mut bidder = GameBidder::new(config, cards.options(config, auction_prefix), header.is_strict());
+    let mut out = if dry_run {
+        None
+    } else {
+        info!("Writing output to {:?}", output_path);
+        Some(PbnOutput::create(output_path, &header, config)?)
+    };
+
+    let mut stats = ProcessingStats::default();
+    let mut expected = Vec::new();
+    let (mut tidied, mut filled) = (0, 0);
+    let mut idx = 0;
+    for game in std::iter::once(Ok(first)).chain(reader.by_ref()) {
+        let game = game.with_context(read_error)?;
+        if game.tag("Deal").is_none() {
+            continue;
+        }
+        let mut input = InputFile::from_game(input_path, game);
+        let counts = tidy_deals(&mut input, config);
+        tidied += counts.0;
+        filled += counts.1;
+
+        let mut boards = input.boards()?;
+        if config.strict_parse {
+            check_deals_strict(&input.text, boards.len(), idx)?;
+        }
+        // The game's tags are read again, as tidying may have changed them.
+        let games = deal_games(&input.text);
+        if boards.len() != games.len() {
+            warn!("Game {}: Deal tag doesn't parse; dropped", idx + 1);
+            continue;
+        }
+        let missing_tags = fill_board_tags(&mut boards, &games, config, idx, header.is_strict());
+        let output = bidder.bid(idx, &boards[0], games.first(), &missing_tags[0], &mut stats)?;
+
+        if let Some(ref mut out) = out {
+            out.write_board(idx, &boards[0], &output, config)?;
+            if config.verify_output {
+                expected.extend(expected_games(std::slice::from_ref(&output), config));
+            }
+        }
+        if config.print_contracts {
+            print_contracts(&boards, std::slice::from_ref(&output), idx);
+        }
+        idx += 1;
+    }
+    info!("Bid {} games from {:?}", idx, input_path);
+    if tidied > 0 {
+        debug!("Normalized whitespace in {} Deal tags", tidied);
+    }
+    if filled > 0 {
+        info!("Filled x spot cards in {} deals", filled);
+    }
+
+    if let Some(out) = out {
+        out.finish()?;
+        if config.verify_output {
+            verify_output(output_path, expected)?;
+        }
+    }
+    Ok(stats)
+}
+
+/// Tidy Deal tags with stray spaces, unless `--strict-parse` asks for them
+/// to be rejected, and `--fill-spots`. Returns how many tags were tidied and
+/// how many deals filled.
+fn tidy_deals(input: &mut InputFile, config: &OutputConfig) -> (usize, usize) {
+    let tidied = if config.strict_parse { 0 } else { input.normalize_deal_spacing() };
+    let filled = if config.fill_spots { input.fill_spots() } else { 0 };
+    (tidied, filled)
+}
+
+/// The convention cards of a run, parsed once and lent to every auction.
+struct RunCards {
+    ns: Option<ConventionCard>,
+    ew: Option<ConventionCard>,
+    seats: [Option<ConventionCard>; 4],
+}
+
+impl RunCards {
+    fn new(ns_conventions: Option<&str>, ew_conventions: Option<&str>, config: &OutputConfig) -> Self {
+        RunCards {
+            ns: ns_conventions.map(ConventionCard::from_content),
+            ew: ew_conventions.map(ConventionCard::from_content),
+            seats: config
+                .seat_conventions
+                .each_ref()
+                .map(|card| card.as_ref().map(|(content, _)| ConventionCard::from_content(content))),
+        }
+    }
+
+    fn options<'a>(&'a self, config: &'a OutputConfig, auction_prefix: Option<&'a [String]>) -> AuctionOptions<'a> {
+        AuctionOptions {
+            scoring: config.scoring,
+            ns_card: self.ns.as_ref(),
+            ew_card: self.ew.as_ref(),
+            seat_cards: self.seats.each_ref().map(Option::as_ref),
+            auction_prefix,
+            single_dummy: config.single_dummy,
+            stop_at_level: config.stop_at_level,
+            passing_side: config.passing_side,
+            style: config.style.as_deref(),
+            conventions: &config.conventions,
+        }
+    }
+}
+
+/// Bids a run's boards one at a time: checks each game, bids it as
+/// `plan_runs` says and scores the auctions. The `--timeout` worker and the
+/// `--max-consecutive-errors` streak carry over from board to board.
+struct GameBidder<'a> {
+    config: &'a OutputConfig,
+    options: AuctionOptions<'a>,
+    swapped_options: AuctionOptions<'a>,
+    /// The input declares `% EXPORT`, so every game needs a Dealer tag.
+    export: bool,
+    worker: Option<TimeoutWorker>,
+    breaker: ErrorBreaker,
+}
+
+impl<'a> GameBidder<'a> {
+    fn new(config: &'a OutputConfig, options: AuctionOptions<'a>, export: bool) -> Self {
+        GameBidder {
+            config,
+            options,
+            swapped_options: AuctionOptions {
+                ns_card: options.ew_card,
+                ew_card: options.ns_card,
+                ..options
+            },
+            export,
+            worker: None,
+            breaker: ErrorBreaker::new(config.max_consecutive_errors),
+        }
+    }
+
+    /// Bid `board`, game `idx` (0-based) of the input. `game` is its raw
+    /// text and tags, None if they can't be matched to the board (only
+    /// allowed when no option needs them); `missing_tags` are the required
+    /// tags it lacks (see `fill_board_tags`).
+    fn bid(
+        &mut self,
+        idx: usize,
+        board: &Board,
+        game: Option<&RawGame>,
+        missing_tags: &[&str],
+        stats: &mut ProcessingStats,
+    ) -> Result<BoardOutput> {
+        let config = self.config;
+        let game_id = game_id(idx, board);
+
+        if let (Some(since), Some(game)) = (config.since, game) {
+            if !is_since(game, since, config) {
+                stats.deals_skipped += 1;
+                debug!("{}: older than {}, copied unchanged", game_id, since);
+                return Ok(BoardOutput::Unchanged(copied_game_text(&game.text, config)));
+            }
+        }
+
+        if let Some(game) = game {
+            if let Err(e) = check_auction_consistency(game) {
+                if config.strict_parse {
+                    anyhow::bail!("{}: {}", game_id, e);
+                }
+                warn!("{}: {}; copied unchanged", game_id, e);
+                stats.deals_skipped += 1;
+                return Ok(BoardOutput::Unchanged(copied_game_text(&game.text, config)));
+            }
+        }
+
         let dealer = board.dealer.unwrap_or(Direction::North);
         let vul = vulnerability_to_epbot(&board.vulnerable);
         let deal_str = format_deal_pbn(&board.deal);
 
         stats.deals_processed += 1;
+        let errors_before = stats.errors;
 
-        let result = generate_auction_with_options(
-            &deal_str,
-            direction_to_int(dealer),
-            vul,
-            config.scoring,
-            Some(&ns_card),
-            Some(&ew_card),
-            auction_prefix,
-            config.single_dummy,
-        );
+        if self.export && board.dealer.is_none() {
+            stats.errors += 1;
+            error!("{}: missing Dealer tag (required in export format)", game_id);
+            self.breaker.record(true, idx)?;
+            return Ok(failed_board(dealer, "missing Dealer tag"));
+        }
 
-        if result.success {
-            stats.auctions_generated += 1;
-        } else {
+        if !missing_tags.is_empty() {
+            let msg = format!("missing {} tag", missing_tags.join(" and "));
+            stats.errors += 1;
+            error!("{}: {}", game_id, msg);
+            self.breaker.record(true, idx)?;
+            return Ok(failed_board(dealer, &msg));
+        }
+
+        // Never hand EPBot a deal string of the wrong shape, whatever
+        // bridge-parsers gave us.
+        if let Err(e) = pbn::check_deal(&deal_str) {
             stats.errors += 1;
-            if let Some(ref err) = result.error {
-                error!("Game {}: {}", idx + 1, err);
+            warn!("{}: deal rendered as '{}' is malformed ({}); not bid", game_id, deal_str, e);
+            self.breaker.record(true, idx)?;
+            return Ok(failed_board(dealer, &format!("malformed deal: {}", e)));
+        }
+
+        let dd_tricks = match config.par_threshold {
+            _ if config.double_dummy => match epbot_core::dd::solve(&deal_str) {
+                Ok(tricks) => Some(tricks),
+                Err(e) => {
+                    stats.errors += 1;
+                    warn!("{}: double-dummy analysis failed ({}); not bid", game_id, e);
+                    self.breaker.record(true, idx)?;
+                    return Ok(failed_board(dealer, &format!("double-dummy analysis failed: {}", e)));
+                }
+            },
+            Some(_) => {
+                let tricks = game
+                    .and_then(|game| game.tag("DoubleDummyTricks"))
+                    .and_then(pbn::parse_dd_tricks);
+                if tricks.is_none() {
+                    warn!("{}: no usable DoubleDummyTricks tag, dropped", game_id);
+                    stats.deals_filtered += 1;
+                    return Ok(BoardOutput::Bid(Vec::new()));
+                }
+                tricks
             }
+            None => None,
+        };
+
+        // --continue-auctions: the calls already recorded are forced and
+        // EPBot bids on from there.
+        let existing = match game {
+            Some(game) if config.continue_auctions => game.auction_calls(),
+            _ => Vec::new(),
+        };
+        let prefix = (!existing.is_empty()).then_some(existing.as_slice());
+        if let Some(calls) = prefix {
+            debug!("{}: continuing from {} recorded calls", game_id, calls.len());
         }
 
-        results.push(result);
+        let plan = plan_runs(dealer, config);
+        let multiple = plan.len() > 1;
+        let mut runs = Vec::with_capacity(plan.len());
+        for (run, (run_dealer, swapped)) in plan.into_iter().enumerate() {
+            let base = if swapped { &self.swapped_options } else { &self.options };
+            let opts = &AuctionOptions {
+                auction_prefix: prefix.or(base.auction_prefix),
+                ..*base
+            };
+            let (options, worker) = (&self.options, &mut self.worker);
+            let mut bid_once = || match config.timeout {
+                Some(timeout) => {
+                    let job = DealJob {
+                        deal: deal_str.clone(),
+                        dealer: direction_to_int(run_dealer),
+                        vul,
+                        swapped,
+                        prefix: prefix.map(<[String]>::to_vec),
+                    };
+                    generate_with_timeout(worker, options, job, timeout)
+                }
+                None => generate_auction_with(&deal_str, direction_to_int(run_dealer), vul, opts),
+            };
+            let (mut result, variants) = if config.repeat > 1 {
+                repeat_auction(config.repeat, bid_once)
+            } else {
+                (bid_once(), Vec::new())
+            };
+
+            if let Some(ref dir) = config.dump_engine_io {
+                let name = if multiple {
+                    format!("game-{:04}-run{}.json", idx + 1, run + 1)
+                } else {
+                    format!("game-{:04}.json", idx + 1)
+                };
+                let request = EngineRequest {
+                    game: idx + 1,
+                    board: board.number,
+                    deal: &deal_str,
+                    dealer: run_dealer,
+                    vul,
+                    swapped,
+                    options: opts,
+                    config,
+                };
+                if let Err(e) = dump_engine_io(&dir.join(name), &request, &result) {
+                    warn!("{}: {:#}", game_id, e);
+                }
+            }
+
+            if result.success && is_passout(&result.bids) {
+                stats.passouts += 1;
+                match config.passout_handling {
+                    PassoutHandling::Record => {}
+                    PassoutHandling::Skip => {
+                        debug!("{}: passed out, dropped", game_id);
+                        continue;
+                    }
+                    PassoutHandling::Error => {
+                        result.success = false;
+                        result.error = Some("passed out".to_string());
+                    }
+                }
+            }
+
+            if result.success {
+                stats.auctions_generated += 1;
+            } else {
+                stats.errors += 1;
+                if let Some(ref err) = result.error {
+                    error!("{}: {}", game_id, err);
+                }
+            }
+
+            let label = multiple.then(|| {
+                let sides = if swapped { ", sides swapped" } else { "" };
+                format!("Dealer {}{}", direction_char(run_dealer), sides)
+            });
+            runs.push(BoardRun {
+                label,
+                dealer: run_dealer,
+                swapped,
+                result,
+                par: None,
+                dd_tricks: None,
+                dd_score: None,
+                variants,
+            });
+        }
+
+        if let Some(tricks) = dd_tricks {
+            for run in &mut runs {
+                let par = score::par_ns(&tricks, vul as u8, direction_to_int(run.dealer) as u8);
+                run.dd_score = dd_result_ns(&run.result, &tricks, run.dealer, vul);
+                if let Some(bid) = run.dd_score {
+                    stats.par_checked += 1;
+                    if bid == par.score {
+                        stats.par_reached += 1;
+                    }
+                }
+                if config.double_dummy {
+                    run.dd_tricks = Some(tricks);
+                    let below = run.dd_score.and_then(|bid| below_par(&run.result, run.dealer, bid, par.score));
+                    if let Some((contract, shortfall)) = below {
+                        warn!("{}: {} is {} points below par double dummy", game_id, contract, shortfall);
+                    }
+                }
+                run.par = Some(par);
+            }
+        }
+
+        if let Some(threshold) = config.par_threshold {
+            runs.retain(|run| match (run.dd_score, &run.par) {
+                (Some(bid), Some(par)) => score::imps(bid - par.score).unsigned_abs() >= threshold,
+                _ => false,
+            });
+            if runs.is_empty() {
+                debug!("{}: on par, dropped", game_id);
+                stats.deals_filtered += 1;
+            }
+        }
+        self.breaker.record(stats.errors > errors_before, idx)?;
+        Ok(BoardOutput::Bid(runs))
+    }
+}
+
+/// Fill each board's missing Dealer or Vulnerable tag from its board number
+/// (see `parse_board_number`), and return, per board, the tags that are
+/// still missing and required (`--require-dealer` /
+/// `--require-vulnerability`). A board without a number keeps the North /
+/// None defaults unless they are required. An `export` input must give
+/// every Dealer tag, so its dealers are only filled when required.
+///
+/// `--renumber-from` first gives the boards new numbers, counting on from
+/// the `offset` games before, and the tags are filled from those.
+fn fill_board_tags(
+    boards: &mut [Board],
+    raw_games: &[RawGame],
+    config: &OutputConfig,
+    offset: usize,
+    export: bool,
+) -> Vec<Vec<&'static str>> {
+    boards
+        .iter_mut()
+        .enumerate()
+        .map(|(idx, board)| {
+            let mut missing = Vec::new();
+            if let Some(start) = config.renumber_from {
+                board.number = Some(start + (offset + idx) as u32);
+            }
+            // Board 0 and malformed numbers count as no number at all.
+            let number = || match config.renumber_from {
+                Some(_) => board.number,
+                None => raw_games[idx].tag("Board").and_then(pbn::parse_board_number),
+            };
+            if board.dealer.is_none() && (config.require_dealer || !export) {
+                board.dealer = number().and_then(pbn::board_dealer);
+                if config.require_dealer && board.dealer.is_none() {
+                    missing.push("Dealer");
+                }
+            }
+            // bridge-parsers reads a missing Vulnerable tag as None, so ask
+            // the raw game whether there was one.
+            if raw_games[idx].tag("Vulnerable").is_none() {
+                match number().and_then(pbn::board_vulnerability) {
+                    Some(vul) => board.vulnerable = vul,
+                    None if config.require_vulnerability => missing.push("Vulnerable"),
+                    None => {}
+                }
+            }
+            missing
+        })
+        .collect()
+}
+
+/// The (dealer, swapped) auctions to run for a board. The board's own dealer
+/// with unswapped conventions always comes first.
+fn plan_runs(dealer: Direction, config: &OutputConfig) -> Vec<(Direction, bool)> {
+    let dealers: Vec<Direction> = if config.rotate_dealers {
+        let first = direction_to_int(dealer);
+        (0..4).map(|i| int_to_direction((first + i) % 4)).collect()
+    } else {
+        vec![dealer]
+    };
+    let sides: &[bool] = if config.swap_sides { &[false, true] } else { &[false] };
+    dealers
+        .iter()
+        .flat_map(|&d| sides.iter().map(move |&swapped| (d, swapped)))
+        .collect()
+}
+
+/// Text written for a game copied from the input: missing roster tags
+/// filled in (see `OutputConfig::placeholder_tags`), `--keep-tags` /
+/// `--drop-tags` applied and, with `--canonical-first-seat`, the Deal tag
+/// rotated to start from North.
+fn copied_game_text(text: &str, config: &OutputConfig) -> String {
+    let text = if config.placeholder_tags && !config.passthrough {
+        config.tag_filter.apply(&pbn::complete_roster_tags(text))
+    } else {
+        config.tag_filter.apply(text)
+    };
+    if config.canonical_first_seat {
+        pbn::canonicalize_deal_tags(&text)
+    } else {
+        text
+    }
+}
+
+/// `--passthrough`: read the input into games and write them back unchanged.
+/// No bidding happens, so unless tags are filtered or Deal tags rotated the
+/// output is byte-for-byte identical to the input.
+fn passthrough_pbn_file(
+    text: &str,
+    output_path: &Path,
+    dry_run: bool,
+    config: &OutputConfig,
+) -> Result<ProcessingStats> {
+    let mut file = pbn::RawFile::parse(text);
+    info!("Found {} games in input file (passthrough, no bidding)", file.games.len());
+
+    for game in &mut file.games {
+        game.text = copied_game_text(&game.text, config);
     }
 
     if !dry_run {
         info!("Writing output to {:?}", output_path);
-        write_rich_pbn(output_path, &boards, &results, config)?;
+        write_output(output_path, &file.to_text()).context("Failed to write output PBN file")?;
+        if config.verify_output {
+            let expected = file
+                .games
+                .iter()
+                .map(|game| ("Auction", game.auction_calls()))
+                .collect();
+            verify_output(output_path, expected)?;
+        }
     }
 
-    Ok(stats)
+    Ok(ProcessingStats {
+        deals_processed: file.games.len(),
+        ..Default::default()
+    })
 }
 
-/// Write PBN output matching BBA.exe format
-fn write_rich_pbn(
-    path: &Path,
-    boards: &[Board],
-    results: &[epbot_core::AuctionResult],
+/// Write a whole output at once, to a file or to stdout for `-`.
+fn write_output(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut out = pbn::OutputFile::create(path)?;
+    out.write_all(text.as_bytes())?;
+    out.flush()
+}
+
+/// Copy every game of an input with nothing to bid, as `--passthrough`
+/// would, or as CSV rows or LIN lines for those with a Deal tag. No deal counts as
+/// processed; every game counts as skipped.
+fn copy_without_bidding(
+    text: &str,
+    output_path: &Path,
+    dry_run: bool,
     config: &OutputConfig,
+) -> Result<(ProcessingStats, Vec<(usize, BoardOutput)>)> {
+    // Every --threads worker gets here; only the first one copies.
+    if config.chunk.is_some_and(|(k, _)| k > 0) {
+        if !dry_run {
+            File::create(output_path).context("Failed to create output PBN file")?;
+        }
+        return Ok((ProcessingStats::default(), Vec::new()));
+    }
+    if config.output_format != OutputFormat::Pbn {
+        let games = pbn::split_games(text);
+        if !dry_run {
+            let mut out = String::new();
+            if config.output_format == OutputFormat::Csv {
+                out = format!("{}\n", csv_export::HEADER);
+            }
+            for game in games.iter().filter(|g| g.tag("Deal").is_some()) {
+                let line = match config.output_format {
+                    OutputFormat::Lin => match LinBoard::from_game(game) {
+                        Some(board) => board.to_lin(),
+                        None => continue,
+                    },
+                    _ => csv_export::game_line(game),
+                };
+                out.push_str(&line);
+                out.push('\n');
+            }
+            write_output(output_path, &out).context("Failed to write output file")?;
+        }
+        let stats = ProcessingStats {
+            deals_skipped: games.len(),
+            ..Default::default()
+        };
+        return Ok((stats, Vec::new()));
+    }
+    let copied = passthrough_pbn_file(text, output_path, dry_run, config)?;
+    let stats = ProcessingStats {
+        deals_skipped: copied.deals_processed,
+        ..Default::default()
+    };
+    Ok((stats, Vec::new()))
+}
+
+/// `--strict-parse`: fail on the first Deal tag that doesn't parse, instead
+/// of letting the game through with no auction. Also catches deals that
+/// bridge-parsers dropped without reporting.
+fn check_deals_strict(text: &str, parsed_boards: usize, offset: usize) -> Result<()> {
+    let games = pbn::split_games(text);
+    let mut deals = 0;
+    for (idx, game) in games.iter().enumerate() {
+        if let Some(deal) = game.tag("Deal") {
+            pbn::check_deal(deal)
+                .map_err(|e| anyhow::anyhow!("Game {}: failed to parse Deal tag: {}", offset + idx + 1, e))?;
+            deals += 1;
+        }
+    }
+    if deals != parsed_boards {
+        anyhow::bail!(
+            "{} Deal tags in input but only {} boards parsed",
+            deals,
+            parsed_boards
+        );
+    }
+    Ok(())
+}
+
+/// The raw text of every game that carries a Deal tag, in file order. When
+/// every deal parsed, game N lines up with bridge-parsers' board N.
+pub(crate) fn deal_games(text: &str) -> Vec<RawGame> {
+    pbn::split_games(text)
+        .into_iter()
+        .filter(|g| g.tag("Deal").is_some())
+        .collect()
+}
+
+/// Check that an existing auction in the input agrees with the Dealer tag:
+/// the `[Auction]` seat must be the dealer, and replaying the calls from
+/// that dealer must give the `[Declarer]` the file records. Catches files
+/// where the Dealer tag was edited without the auction.
+fn check_auction_consistency(game: &RawGame) -> std::result::Result<(), String> {
+    let (Some(dealer), Some(first_seat)) = (game.tag("Dealer"), game.tag("Auction")) else {
+        return Ok(());
+    };
+    if !dealer.eq_ignore_ascii_case(first_seat) {
+        return Err(format!(
+            "auction starts with {} but Dealer is {}",
+            first_seat, dealer
+        ));
+    }
+    let recorded = match game.tag("Declarer") {
+        Some(d) if matches!(d, "N" | "E" | "S" | "W") => d,
+        _ => return Ok(()),
+    };
+    let calls = game.auction_calls();
+    let calls: Vec<&str> = calls.iter().map(String::as_str).collect();
+    let (contract, declarer) = derive_contract_declarer(&calls, direction_str_to_int(dealer));
+    if contract != "Pass" && declarer != recorded {
+        return Err(format!(
+            "{} calls from dealer {} give declarer {}, but Declarer is {}",
+            calls.len(),
+            dealer,
+            declarer,
+            recorded
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `game` should be bid under `--since`: its date is on or after
+/// `since`. Games without a usable date are bid unless `--since-strict`.
+fn is_since(game: &RawGame, since: PbnDate, config: &OutputConfig) -> bool {
+    match game.tag(&config.since_tag).and_then(PbnDate::parse) {
+        Some(date) => date >= since,
+        None => !config.since_strict,
+    }
+}
+
+/// `--max-consecutive-errors`: a circuit breaker that aborts the run once
+/// `limit` deals in a row have failed, so a systemic problem (bad
+/// conventions, a crashing engine) doesn't grind through the whole file.
+struct ErrorBreaker {
+    limit: Option<usize>,
+    streak: usize,
+    succeeded: usize,
+}
+
+impl ErrorBreaker {
+    fn new(limit: Option<usize>) -> Self {
+        ErrorBreaker {
+            limit,
+            streak: 0,
+            succeeded: 0,
+        }
+    }
+
+    /// Record whether game `idx` (0-based) failed; errors once the streak
+    /// reaches the limit.
+    fn record(&mut self, failed: bool, idx: usize) -> Result<()> {
+        if !failed {
+            self.streak = 0;
+            self.succeeded += 1;
+            return Ok(());
+        }
+        self.streak += 1;
+        match self.limit {
+            Some(limit) if self.streak >= limit => anyhow::bail!(
+                "{} consecutive deals failed (games {}-{}); {} deals succeeded before stopping",
+                self.streak,
+                idx + 2 - self.streak,
+                idx + 1,
+                self.succeeded
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `--repeat`: bid the same deal `times` times. Returns the most common
+/// outcome (the earliest one on ties) and every distinct auction with its
+/// count, most frequent first. Failures are grouped by error message.
+fn repeat_auction(
+    times: u32,
+    mut bid: impl FnMut() -> epbot_core::AuctionResult,
+) -> (epbot_core::AuctionResult, Vec<(String, usize)>) {
+    let mut outcomes: Vec<(String, usize, epbot_core::AuctionResult)> = Vec::new();
+    for _ in 0..times {
+        let result = bid();
+        let key = if result.success {
+            result.bids.iter().map(|b| b.bid.as_str()).collect::<Vec<_>>().join(" ")
+        } else {
+            format!("error: {}", result.error.as_deref().unwrap_or("unknown"))
+        };
+        match outcomes.iter_mut().find(|(k, _, _)| *k == key) {
+            Some(outcome) => outcome.1 += 1,
+            None => outcomes.push((key, 1, result)),
+        }
+    }
+
+    // Stable sort: equal counts keep first-seen order.
+    outcomes.sort_by_key(|&(_, n, _)| std::cmp::Reverse(n));
+    let variants = outcomes.iter().map(|(k, n, _)| (k.clone(), *n)).collect();
+    let modal = outcomes
+        .into_iter()
+        .next()
+        .map(|(_, _, result)| result)
+        .unwrap_or_else(|| error_result("no auctions run"));
+    (modal, variants)
+}
+
+/// One deal for a `TimeoutWorker`. `swapped` picks the side-swapped cards;
+/// `prefix` replaces the worker's auction prefix for this deal only.
+struct DealJob {
+    deal: String,
+    dealer: i32,
+    vul: i32,
+    swapped: bool,
+    prefix: Option<Vec<String>>,
+}
+
+/// `--timeout` worker: a long-lived thread holding its own copy of the
+/// convention cards and, through epbot-core's per-thread engine cache, its
+/// own EPBot instances. The cards are copied and the engine created once per
+/// worker rather than once per deal; only a worker abandoned on a timed-out
+/// deal is replaced.
+struct TimeoutWorker {
+    jobs: mpsc::Sender<DealJob>,
+    results: mpsc::Receiver<epbot_core::AuctionResult>,
+}
+
+impl TimeoutWorker {
+    /// Start a worker bidding with `options` (unswapped orientation).
+    fn spawn(options: &AuctionOptions) -> std::io::Result<Self> {
+        // The worker outlives any one call, so it gets owned copies of
+        // everything the options borrow.
+        let ns_card = options.ns_card.cloned();
+        let ew_card = options.ew_card.cloned();
+        let seat_cards = options.seat_cards.map(|card| card.cloned());
+        let prefix = options.auction_prefix.map(<[String]>::to_vec);
+        let style = options.style.map(str::to_string);
+        let conventions = options.conventions.to_vec();
+        let base = AuctionOptions {
+            ns_card: None,
+            ew_card: None,
+            seat_cards: [None; 4],
+            conventions: &[],
+            auction_prefix: None,
+            style: None,
+            ..*options
+        };
+
+        let (job_tx, job_rx) = mpsc::channel::<DealJob>();
+        let (result_tx, result_rx) = mpsc::channel();
+        thread::Builder::new()
+            .name("epbot-worker".to_string())
+            .spawn(move || {
+                let options = AuctionOptions {
+                    ns_card: ns_card.as_ref(),
+                    ew_card: ew_card.as_ref(),
+                    seat_cards: seat_cards.each_ref().map(Option::as_ref),
+                    auction_prefix: prefix.as_deref(),
+                    style: style.as_deref(),
+                    conventions: &conventions,
+                    ..base
+                };
+                let swapped = AuctionOptions {
+                    ns_card: options.ew_card,
+                    ew_card: options.ns_card,
+                    ..options
+                };
+                for job in job_rx {
+                    let opts = if job.swapped { &swapped } else { &options };
+                    let opts = AuctionOptions {
+                        auction_prefix: job.prefix.as_deref().or(opts.auction_prefix),
+                        ..*opts
+                    };
+                    let result = generate_auction_with(&job.deal, job.dealer, job.vul, &opts);
+                    // The receiver is gone once this worker was abandoned.
+                    if result_tx.send(result).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(TimeoutWorker {
+            jobs: job_tx,
+            results: result_rx,
+        })
+    }
+}
+
+/// `--timeout`: bid one deal on the worker, starting one if needed, and give
+/// up after `timeout`, returning a failed result.
+///
+/// An FFI call can't be interrupted, so a timed-out worker is abandoned: it
+/// keeps running detached, and its EPBot instances are destroyed only if the
+/// call eventually returns (otherwise they leak until exit). The next deal
+/// starts a fresh worker, so it never touches an abandoned engine.
+fn generate_with_timeout(
+    worker: &mut Option<TimeoutWorker>,
+    options: &AuctionOptions,
+    job: DealJob,
+    timeout: Duration,
+) -> epbot_core::AuctionResult {
+    if worker.is_none() {
+        match TimeoutWorker::spawn(options) {
+            Ok(started) => *worker = Some(started),
+            Err(e) => return error_result(&format!("failed to start worker thread: {}", e)),
+        }
+    }
+    let current = worker.as_ref().expect("worker was just started");
+
+    let received = match current.jobs.send(job) {
+        Ok(()) => current.results.recv_timeout(timeout),
+        Err(_) => Err(RecvTimeoutError::Disconnected),
+    };
+    match received {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            *worker = None;
+            error_result(&format!("timed out after {}s; deal abandoned", timeout.as_secs_f64()))
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            *worker = None;
+            error_result("auction worker panicked")
+        }
+    }
+}
+
+/// NS score of the bid contract played double-dummy, or None if no auction
+/// was generated. A passed-out board scores 0.
+fn dd_result_ns(
+    result: &epbot_core::AuctionResult,
+    tricks: &[[u8; 5]; 4],
+    dealer: Direction,
+    vul: i32,
+) -> Option<i32> {
+    if !result.success || result.truncated {
+        return None;
+    }
+    let bids: Vec<&str> = result.bids.iter().map(|b| b.bid.as_str()).collect();
+    let (contract, declarer) = derive_contract_declarer(&bids, direction_to_int(dealer));
+    score::dd_score_ns(&contract, direction_str_to_int(&declarer) as u8, vul as u8, tricks)
+}
+
+/// The bid contract ("4S by N", or "Pass") and how many points its
+/// declaring side's double-dummy score `bid` falls below par `par`; None if
+/// it reaches par. A passed-out board is below par whenever par isn't zero.
+fn below_par(result: &epbot_core::AuctionResult, dealer: Direction, bid: i32, par: i32) -> Option<(String, i32)> {
+    let bids: Vec<&str> = result.bids.iter().map(|b| b.bid.as_str()).collect();
+    let (contract, declarer) = derive_contract_declarer(&bids, direction_to_int(dealer));
+    let shortfall = match contract.as_str() {
+        "Pass" => par.abs(),
+        _ if direction_str_to_int(&declarer) % 2 == 0 => par - bid,
+        _ => bid - par,
+    };
+    let contract = if contract == "Pass" { contract } else { format!("{} by {}", contract, declarer) };
+    (shortfall > 0).then_some((contract, shortfall))
+}
+
+/// A board rejected before reaching EPBot: one failed run at its dealer.
+fn failed_board(dealer: Direction, message: &str) -> BoardOutput {
+    BoardOutput::Bid(vec![BoardRun {
+        label: None,
+        dealer,
+        swapped: false,
+        result: error_result(message),
+        par: None,
+        dd_tricks: None,
+        dd_score: None,
+        variants: Vec::new(),
+    }])
+}
+
+/// Four passes and nothing else.
+fn is_passout(bids: &[epbot_core::BidInfo]) -> bool {
+    !bids.is_empty() && bids.iter().all(|b| matches!(b.bid.as_str(), "Pass" | "P"))
+}
+
+/// A failed result for a deal rejected before reaching EPBot.
+fn error_result(message: &str) -> epbot_core::AuctionResult {
+    epbot_core::AuctionResult {
+        bids: Vec::new(),
+        success: false,
+        error: Some(message.to_string()),
+        failure: None,
+        analysis: None,
+        truncated: false,
+    }
+}
+
+/// "Game N" for log messages (N is the 1-based position in the input, as
+/// in `[BBAIndex]`), plus the board number when that differs, so a failure
+/// can be found in the input without counting games.
+fn game_id(idx: usize, board: &Board) -> String {
+    match board.number {
+        Some(number) if number as usize != idx + 1 => format!("Game {} (board {})", idx + 1, number),
+        _ => format!("Game {}", idx + 1),
+    }
+}
+
+/// Everything that went into one auction, for `--dump-engine-io`.
+struct EngineRequest<'a> {
+    /// 1-based position in the input, and the board's number
+    game: usize,
+    board: Option<u32>,
+    deal: &'a str,
+    dealer: Direction,
+    vul: i32,
+    swapped: bool,
+    options: &'a AuctionOptions<'a>,
+    config: &'a OutputConfig,
+}
+
+/// `--dump-engine-io`: write the exact inputs handed to EPBot for one
+/// auction and what came back, so a bad auction can be reproduced (or
+/// reported) without re-running the whole file. Convention cards are named
+/// by source rather than inlined.
+fn dump_engine_io(path: &Path, request: &EngineRequest, result: &epbot_core::AuctionResult) -> Result<()> {
+    let (ns_card, ew_card) = if request.swapped {
+        (&request.config.ew_conventions_path, &request.config.ns_conventions_path)
+    } else {
+        (&request.config.ns_conventions_path, &request.config.ew_conventions_path)
+    };
+    let opts = request.options;
+    let bids: Vec<serde_json::Value> = result
+        .bids
+        .iter()
+        .map(|b| {
+            serde_json::json!({
+                "bid": b.bid,
+                "code": b.code,
+                "position": b.position,
+                "meaning": b.meaning,
+            })
+        })
+        .collect();
+    let dump = serde_json::json!({
+        "game": request.game,
+        "board": request.board,
+        "request": {
+            "deal": request.deal,
+            "dealer": direction_char(request.dealer),
+            "vulnerability": vulnerability_to_pbn(request.vul),
+            "scoring": scoring_tag(opts.scoring),
+            "ns_conventions": (!ns_card.is_empty()).then_some(ns_card),
+            "ew_conventions": (!ew_card.is_empty()).then_some(ew_card),
+            "auction_prefix": opts.auction_prefix,
+            "stop_at_level": opts.stop_at_level,
+            "passing_side": opts.passing_side.map(|side| if side == 0 { "NS" } else { "EW" }),
+            "single_dummy": opts.single_dummy,
+            "style": opts.style,
+        },
+        "response": {
+            "success": result.success,
+            "error": result.error,
+            "error_code": result.error_code(),
+            "truncated": result.truncated,
+            "bids": bids,
+        },
+    });
+    let text = serde_json::to_string_pretty(&dump)?;
+    std::fs::write(path, text + "\n").with_context(|| format!("Failed to write {:?}", path))
+}
+
+/// `--focus-seat`: one line per generated auction, to `path` or stdout.
+/// Games copied unchanged are left out.
+fn write_focus_report(
+    boards: &[Board],
+    results: &[BoardOutput],
+    seat: u8,
+    path: Option<&Path>,
 ) -> Result<()> {
-    let file = std::fs::File::create(path).context("Failed to create output PBN file")?;
-    let mut writer = BufWriter::new(file);
+    let mut report = format!("Focus seat: {}\n", focus::seat_name(seat));
+    for (idx, (board, output)) in boards.iter().zip(results).enumerate() {
+        let BoardOutput::Bid(runs) = output else {
+            continue;
+        };
+        let board_num = board.number.unwrap_or((idx + 1) as u32);
+        for run in runs {
+            let calls: Vec<&str> = if run.result.success {
+                run.result.bids.iter().map(|b| b.bid.as_str()).collect()
+            } else {
+                Vec::new()
+            };
+            let dealer = direction_to_int(run.dealer) as u8;
+            report.push_str(&focus::focus_line(board_num, run.label.as_deref(), dealer, &calls, seat));
+            report.push('\n');
+        }
+    }
+
+    match path {
+        Some(path) => {
+            std::fs::write(path, report).with_context(|| format!("Failed to write {:?}", path))?;
+            info!("Focus report written to {:?}", path);
+        }
+        None => print!("{}", report),
+    }
+    Ok(())
+}
+
+/// `--print-contracts`: one `Board N: 4S by E` line per auction on stdout,
+/// for previewing a run (usually with `--dry-run`).
+fn print_contracts(boards: &[Board], results: &[BoardOutput], offset: usize) {
+    for (idx, (board, output)) in boards.iter().zip(results).enumerate() {
+        let BoardOutput::Bid(runs) = output else {
+            continue;
+        };
+        let board_num = board.number.unwrap_or((offset + idx + 1) as u32);
+        for run in runs {
+            let label = run.label.as_ref().map(|l| format!(" ({})", l)).unwrap_or_default();
+            println!("Board {}{}: {}", board_num, label, run_outcome(run));
+        }
+    }
+}
+
+/// A run's result in words: `4S by E`, `passed out`, or `error: ...`.
+pub(crate) fn run_outcome(run: &BoardRun) -> String {
+    if !run.result.success {
+        return format!("error: {}", run.result.error.as_deref().unwrap_or("unknown"));
+    }
+    let bids: Vec<&str> = run.result.bids.iter().map(|b| b.bid.as_str()).collect();
+    match derive_contract_declarer(&bids, direction_to_int(run.dealer)) {
+        (contract, _) if contract == "Pass" => "passed out".to_string(),
+        (contract, declarer) => format!("{} by {}", contract, declarer),
+    }
+}
+
+/// The games `PbnOutput` writes for `results`, as (auction tag name,
+/// calls) in file order.
+fn expected_games<'a>(results: &[BoardOutput], config: &'a OutputConfig) -> Vec<(&'a str, Vec<String>)> {
+    let mut games = Vec::new();
+    for output in results {
+        match output {
+            BoardOutput::Unchanged(text) => {
+                games.extend(pbn::split_games(text).iter().map(|g| ("Auction", g.auction_calls())));
+            }
+            BoardOutput::Bid(runs) => {
+                for run in runs {
+                    let calls = if run.result.success {
+                        run.result.bids.iter().filter_map(|b| pbn::normalize_call(&b.bid)).collect()
+                    } else {
+                        Vec::new()
+                    };
+                    games.push((config.auction_tag.as_str(), calls));
+                }
+            }
+        }
+    }
+    games
+}
+
+/// `--verify-output`: re-read the file just written and check that it
+/// splits into the expected games, each carrying the expected auction.
+/// Catches formatting bugs (a stray blank line, a mangled auction grid)
+/// before anyone relies on the file. The file is read back a game at a
+/// time, so a large output isn't held in memory twice.
+fn verify_output(path: &Path, expected: Vec<(&str, Vec<String>)>) -> Result<()> {
+    let file = File::open(path).context("Failed to re-read output PBN file")?;
+    let mut games = 0;
+    for (n, game) in pbn::PbnReader::new(std::io::BufReader::new(file)).enumerate() {
+        let game = game.with_context(|| format!("Output verification failed: game {} of {:?}", n + 1, path))?;
+        games += 1;
+        let Some((tag, calls)) = expected.get(n) else {
+            continue;
+        };
+        let found = game.auction_calls_under(tag);
+        if found != *calls {
+            anyhow::bail!(
+                "Output verification failed: game {} of {:?} reads back with auction '{}', expected '{}'",
+                n + 1,
+                path,
+                found.join(" "),
+                calls.join(" ")
+            );
+        }
+    }
+    if games != expected.len() {
+        anyhow::bail!(
+            "Output verification failed: wrote {} games but {:?} reads back as {}",
+            expected.len(),
+            path,
+            games
+        );
+    }
+    info!("Verified {} games in {:?}", games, path);
+    Ok(())
+}
+
+/// PBN output matching BBA.exe format, written board by board.
+///
+/// The buffer is flushed every `--flush-every` games (and synced to disk
+/// with `--fsync`), so a crash loses at most the games since the last flush
+/// and the file always holds whole games.
+struct PbnOutput {
+    writer: BufWriter<pbn::OutputFile>,
+    today: String,
+    /// Boards written so far; `results[..written]` are on their way to disk.
+    written: usize,
+    first_game: bool,
+    /// PBN games, or rows of `--output-format csv` or lines of `lin`.
+    format: OutputFormat,
+    unflushed_games: usize,
+    flush_every: usize,
+    fsync: bool,
+}
+
+impl PbnOutput {
+    /// Create the file and write the `%` header block, or the CSV header
+    /// row. A LIN file has no header.
+    fn create(path: &Path, header: &PbnHeader, config: &OutputConfig) -> Result<Self> {
+        let file = pbn::OutputFile::create(path).context("Failed to create output PBN file")?;
+        let mut writer = BufWriter::new(file);
+        let format = config.output_format;
+
+        match format {
+            OutputFormat::Pbn => write_pbn_header(&mut writer, header, config)?,
+            OutputFormat::Csv => writeln!(writer, "{}", csv_export::HEADER)?,
+            OutputFormat::Lin => {}
+        }
+
+        Ok(PbnOutput {
+            writer,
+            today: chrono_date(),
+            written: 0,
+            first_game: true,
+            format,
+            unflushed_games: 0,
+            flush_every: config.flush_every.max(1),
+            fsync: config.fsync,
+        })
+    }
+
+    /// Write every board in `results` not written yet.
+    fn write_pending(&mut self, boards: &[Board], results: &[BoardOutput], config: &OutputConfig) -> Result<()> {
+        for idx in self.written..results.len() {
+            self.write_board(idx, &boards[idx], &results[idx], config)?;
+            self.written = idx + 1;
+        }
+        Ok(())
+    }
+
+    fn write_board(&mut self, idx: usize, board: &Board, output: &BoardOutput, config: &OutputConfig) -> Result<()> {
+        let runs = match output {
+            BoardOutput::Bid(runs) => runs,
+            BoardOutput::Unchanged(text) => {
+                self.start_game()?;
+                match self.format {
+                    OutputFormat::Pbn => writeln!(self.writer, "{}", text.trim_end())?,
+                    OutputFormat::Csv => {
+                        for game in pbn::split_games(text) {
+                            writeln!(self.writer, "{}", csv_export::game_line(&game))?;
+                        }
+                    }
+                    OutputFormat::Lin => {
+                        for board in pbn::split_games(text).iter().filter_map(LinBoard::from_game) {
+                            writeln!(self.writer, "{}", board.to_lin())?;
+                        }
+                    }
+                }
+                debug!("Game {}: copied unchanged", idx + 1);
+                return self.game_done();
+            }
+        };
+        // Boards with several runs are written as consecutive games.
+        for run in runs {
+            self.start_game()?;
+            match self.format {
+                OutputFormat::Pbn => write_game(&mut self.writer, idx, board, run, config, &self.today)?,
+                OutputFormat::Csv => write_csv_row(&mut self.writer, idx, board, run, config)?,
+                OutputFormat::Lin => write_lin_line(&mut self.writer, idx, board, run)?,
+            }
+            debug!("Game {}: written", idx + 1);
+            self.game_done()?;
+        }
+        Ok(())
+    }
+
+    /// Games are separated by a blank line; CSV rows and LIN lines are not.
+    fn start_game(&mut self) -> Result<()> {
+        if !self.first_game && self.format == OutputFormat::Pbn {
+            writeln!(self.writer)?;
+        }
+        self.first_game = false;
+        Ok(())
+    }
+
+    fn game_done(&mut self) -> Result<()> {
+        self.unflushed_games += 1;
+        if self.unflushed_games >= self.flush_every {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.writer.flush().context("Failed to write output PBN file")?;
+        if self.fsync {
+            self.writer.get_ref().sync_data().context("Failed to sync output PBN file")?;
+        }
+        self.unflushed_games = 0;
+        Ok(())
+    }
 
-    let today = chrono_date();
+    fn finish(mut self) -> Result<()> {
+        self.flush()
+    }
+}
 
-    writeln!(writer, "% PBN 2.1")?;
+/// The `%` header block that opens a PBN output.
+fn write_pbn_header(writer: &mut impl Write, header: &PbnHeader, config: &OutputConfig) -> Result<()> {
+    writeln!(writer, "% PBN {}", header.output_version())?;
+    if header.export {
+        writeln!(writer, "% EXPORT")?;
+    }
     writeln!(writer, "% Generated by bba-cli")?;
     if !config.ns_conventions_path.is_empty() {
         writeln!(writer, "% CC1 - {}", config.ns_conventions_path)?;
@@ -260,60 +1831,109 @@ fn write_rich_pbn(
     if !config.ew_conventions_path.is_empty() {
         writeln!(writer, "% CC2 - {}", config.ew_conventions_path)?;
     }
-
-    for (idx, (board, result)) in boards.iter().zip(results.iter()).enumerate() {
-        if idx > 0 {
-            writeln!(writer)?;
+    for (seat, card) in ["North", "East", "South", "West"].iter().zip(&config.seat_conventions) {
+        if let Some((_, source)) = card {
+            writeln!(writer, "% CC {} - {}", seat, source)?;
         }
+    }
+    if config.stamp_metadata {
+        write_metadata(writer, config)?;
+    }
+    for line in &header.passthrough {
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
 
-        let dealer = board.dealer.unwrap_or(Direction::North);
-        let vul = vulnerability_to_epbot(&board.vulnerable);
-        let has_auction = result.success && !result.bids.is_empty();
-        let board_num = board.number.unwrap_or((idx + 1) as u32);
-        let deal_str = format_deal_pbn(&board.deal);
+/// Write one generated game (tags, hand analysis, auction).
+fn write_game(
+    writer: &mut impl Write,
+    idx: usize,
+    board: &Board,
+    run: &BoardRun,
+    config: &OutputConfig,
+    today: &str,
+) -> Result<()> {
+    let result = &run.result;
+    let dealer = run.dealer;
+    let vul = vulnerability_to_epbot(&board.vulnerable);
+    let has_auction = result.success && !result.bids.is_empty();
+    let board_num = board.number.unwrap_or((idx + 1) as u32);
+    let deal_str = format_deal_pbn(&board.deal);
 
-        writeln!(writer, "[Event \"{}\"]", config.event)?;
-        writeln!(writer, "[Site \"\"]")?;
-        writeln!(writer, "[Date \"{}\"]", today)?;
-        writeln!(writer, "[Board \"{}\"]", board_num)?;
-
-        // BBA-style 28-hex board fingerprint, only with --single-dummy.
-        if config.single_dummy {
-            let hands_for_hash = hands_for_bba_hash(&board.deal);
-            let hash = bba_hash::encode(
-                &hands_for_hash,
-                direction_to_int(dealer) as u8,
-                vul as u8,
-                bba_hash::board_extension_for(board_num),
-            );
-            writeln!(writer, "% {}", hash)?;
-        }
+    writeln!(writer, "[Event \"{}\"]", config.event)?;
+    writeln!(writer, "[Site \"\"]")?;
+    writeln!(writer, "[Date \"{}\"]", today)?;
+    writeln!(writer, "[Board \"{}\"]", board_num)?;
+    if let Some(ref label) = run.label {
+        writeln!(writer, "[Room \"{}\"]", label)?;
+    }
+    if config.annotate_index {
+        writeln!(writer, "[BBAIndex \"{}\"]", idx + 1)?;
+    }
 
-        writeln!(writer, "[North \"EPBot\"]")?;
-        writeln!(writer, "[East \"EPBot\"]")?;
-        writeln!(writer, "[South \"EPBot\"]")?;
-        writeln!(writer, "[West \"EPBot\"]")?;
-        writeln!(writer, "[Dealer \"{}\"]", direction_char(dealer))?;
-        writeln!(writer, "[Vulnerable \"{}\"]", vulnerability_to_pbn(vul))?;
-        writeln!(writer, "[Deal \"{}\"]", deal_str)?;
+    // BBA-style 28-hex board fingerprint, only with --single-dummy.
+    if config.single_dummy {
+        let hands_for_hash = hands_for_bba_hash(&board.deal);
+        let hash = bba_hash::encode(
+            &hands_for_hash,
+            direction_to_int(dealer) as u8,
+            vul as u8,
+            bba_hash::board_extension_for(board_num),
+        );
+        writeln!(writer, "% {}", hash)?;
+    }
+
+    writeln!(writer, "[North \"EPBot\"]")?;
+    writeln!(writer, "[East \"EPBot\"]")?;
+    writeln!(writer, "[South \"EPBot\"]")?;
+    writeln!(writer, "[West \"EPBot\"]")?;
+    writeln!(writer, "[Dealer \"{}\"]", direction_char(dealer))?;
+    writeln!(writer, "[Vulnerable \"{}\"]", config.vulnerability_style.tag_value(vul))?;
+    writeln!(writer, "[Deal \"{}\"]", deal_str)?;
 
-        // Hand analysis
-        write_hand_analysis(&mut writer, &board.deal)?;
+    // Hand analysis
+    write_hand_analysis(writer, &board.deal)?;
 
-        if has_auction {
+    if let Some(tricks) = run.dd_tricks.as_ref() {
+        writeln!(writer, "[DoubleDummyTricks \"{}\"]", pbn::format_dd_tricks(tricks))?;
+    }
+    if let Some(par) = run.par.as_ref() {
+        writeln!(writer, "[OptimumScore \"NS {}\"]", par.score)?;
+        writeln!(writer, "[ParScore \"NS {}\"]", par.score)?;
+        writeln!(writer, "[ParContract \"{}\"]", par)?;
+    }
+    if let Some(tricks) = run.dd_tricks.as_ref() {
+        writeln!(writer, "[OptimumResultTable \"Declarer;Denomination\\2R;Result\\2R\"]")?;
+        for seat in [0, 2, 1, 3] {
+            for (strain, name) in [(4, "NT"), (3, "S"), (2, "H"), (1, "D"), (0, "C")] {
+                writeln!(writer, "{} {} {}", b"NESW"[seat] as char, name, tricks[seat][strain])?;
+            }
+        }
+    }
+
+    if has_auction {
+        // A truncated auction (--stop-at-level) has no final contract, so
+        // it gets no contract or score tags, only the `*`-ended auction.
+        if !result.truncated {
             let bid_strs: Vec<&str> = result.bids.iter().map(|b| b.bid.as_str()).collect();
             let (contract, declarer) =
                 derive_contract_declarer(&bid_strs, direction_to_int(dealer));
-            writeln!(writer, "[Declarer \"{}\"]", declarer)?;
-            writeln!(writer, "[Contract \"{}\"]", contract)?;
-
-            // [Result], [Score], [Scoring] only with --single-dummy.
-            if config.single_dummy {
-                if let Some(analysis) = result.analysis.as_ref() {
-                    if let Some((level, strain, doubled)) = score::parse_contract(&contract) {
-                        let strain_idx = strain_index(strain);
-                        let tricks = analysis.tricks[strain_idx];
-                        let declarer_pos = direction_str_to_int(&declarer);
+            writeln!(writer, "[{} \"{}\"]", config.declarer_tag, declarer)?;
+            writeln!(writer, "[{} \"{}\"]", config.contract_tag, contract)?;
+
+            // [Result], [Score], [Scoring] only with --single-dummy (the
+            // engine's estimate) or --double-dummy (solved tricks, preferred).
+            if config.single_dummy || run.dd_tricks.is_some() {
+                if let Some((level, strain, doubled)) = score::parse_contract(&contract) {
+                    let strain_idx = strain_index(strain);
+                    let declarer_pos = direction_str_to_int(&declarer);
+                    let taken = match (run.dd_tricks.as_ref(), result.analysis.as_ref()) {
+                        (Some(dd), _) => Some(dd[declarer_pos as usize][strain_idx]),
+                        (None, Some(analysis)) => Some(analysis.tricks[strain_idx]),
+                        (None, None) => None,
+                    };
+                    if let Some(tricks) = taken {
                         let ns_score = score::score_for_ns(
                             level,
                             strain,
@@ -328,21 +1948,112 @@ fn write_rich_pbn(
                 }
                 writeln!(writer, "[Scoring \"{}\"]", scoring_tag(config.scoring))?;
             }
+        }
 
-            writeln!(writer, "[Auction \"{}\"]", direction_char(dealer))?;
-            write_annotated_auction(&mut writer, &result.bids)?;
+        writeln!(writer, "[{} \"{}\"]", config.auction_tag, direction_char(dealer))?;
+        write_annotated_auction(
+            writer,
+            &result.bids,
+            config.annotate_strength.then_some((&board.deal, dealer)),
+            result.truncated,
+            config.auction_layout,
+            config.pad_auction_grid,
+            config.auction_column_width,
+        )?;
+    }
+
+    if !run.variants.is_empty() {
+        let total: usize = run.variants.iter().map(|(_, n)| n).sum();
+        if run.variants.len() == 1 {
+            writeln!(writer, "{{Bid {} times: all identical}}", total)?;
+        } else {
+            writeln!(writer, "{{Bid {} times, {} distinct auctions:", total, run.variants.len())?;
+            let lines: Vec<String> =
+                run.variants.iter().map(|(auction, n)| format!("{}x {}", n, auction)).collect();
+            writeln!(writer, "{}}}", lines.join("\n"))?;
         }
+    }
 
-        writeln!(writer, "[BidSystemEW \"{}\"]", config.ew_system_name)?;
-        writeln!(writer, "[BidSystemNS \"{}\"]", config.ns_system_name)?;
+    let (ns_system, ew_system) = if run.swapped {
+        (&config.ew_system_name, &config.ns_system_name)
+    } else {
+        (&config.ns_system_name, &config.ew_system_name)
+    };
+    writeln!(writer, "[BidSystemEW \"{}\"]", ew_system)?;
+    writeln!(writer, "[BidSystemNS \"{}\"]", ns_system)?;
 
-        debug!("Game {}: written", idx + 1);
+    if let Some((ns_tag, ew_tag)) = &config.stamp_tags {
+        let (ns_source, ew_source) = if run.swapped {
+            (&config.ew_conventions_path, &config.ns_conventions_path)
+        } else {
+            (&config.ns_conventions_path, &config.ew_conventions_path)
+        };
+        writeln!(writer, "[{} \"{}\"]", ns_tag, convention_file_name(ns_source))?;
+        writeln!(writer, "[{} \"{}\"]", ew_tag, convention_file_name(ew_source))?;
     }
+    Ok(())
+}
+
+/// Write one generated game as a row of `--output-format csv`. A failed
+/// auction leaves the auction, contract and declarer empty.
+fn write_csv_row(writer: &mut impl Write, idx: usize, board: &Board, run: &BoardRun, config: &OutputConfig) -> Result<()> {
+    let result = &run.result;
+    let bids: Vec<&str> = if result.success {
+        result.bids.iter().map(|b| b.bid.as_str()).collect()
+    } else {
+        Vec::new()
+    };
+    let (contract, declarer) = if bids.is_empty() {
+        (String::new(), String::new())
+    } else {
+        derive_contract_declarer(&bids, direction_to_int(run.dealer))
+    };
+    let row = csv_export::Row {
+        board: board.number.unwrap_or((idx + 1) as u32).to_string(),
+        dealer: direction_char(run.dealer),
+        vulnerable: config.vulnerability_style.tag_value(vulnerability_to_epbot(&board.vulnerable)),
+        deal: &format_deal_pbn(&board.deal),
+        auction: bids.join(" "),
+        contract: &contract,
+        declarer: &declarer,
+    };
+    writeln!(writer, "{}", row.to_line())?;
+    Ok(())
+}
 
-    writer.flush()?;
+/// Write one generated game as a line of `--output-format lin`, with each
+/// alerted call's meaning as its explanation. A failed auction has no calls.
+fn write_lin_line(writer: &mut impl Write, idx: usize, board: &Board, run: &BoardRun) -> Result<()> {
+    let bids: &[epbot_core::BidInfo] = if run.result.success { &run.result.bids } else { &[] };
+    let lin = LinBoard {
+        number: board.number.unwrap_or((idx + 1) as u32),
+        players: ["EPBot"; 4].map(String::from),
+        dealer: direction_char(run.dealer).chars().next().unwrap_or('N'),
+        vulnerable: vulnerability_to_pbn(vulnerability_to_epbot(&board.vulnerable)),
+        deal: format_deal_pbn(&board.deal),
+        calls: bids.iter().map(|b| b.bid.clone()).collect(),
+        alerts: bids
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.is_alert)
+            .map(|(i, b)| (i, b.meaning.clone().unwrap_or_default()))
+            .collect(),
+    };
+    writeln!(writer, "{}", lin.to_lin())?;
     Ok(())
 }
 
+/// File name of a convention source for `--stamp-conventions`: the last
+/// component of a path, or of the entry in a system pack
+/// ("pack.zip:cards/sayc.bbsa" gives "sayc.bbsa"). An empty source means
+/// EPBot's defaults.
+fn convention_file_name(source: &str) -> &str {
+    if source.is_empty() {
+        return "EPBot default";
+    }
+    source.rsplit(['/', '\\', ':']).next().unwrap_or(source)
+}
+
 /// Write {Shape}, {HCP}, {Losers} comments
 fn write_hand_analysis(writer: &mut impl Write, deal: &Deal) -> Result<()> {
     let dirs = [
@@ -383,7 +2094,7 @@ fn write_hand_analysis(writer: &mut impl Write, deal: &Deal) -> Result<()> {
 }
 
 /// Derive contract and declarer from auction bids
-fn derive_contract_declarer(bids: &[&str], dealer: i32) -> (String, String) {
+pub(crate) fn derive_contract_declarer(bids: &[&str], dealer: i32) -> (String, String) {
     let mut last_contract_bid = None;
     let mut last_contract_idx = 0;
     let mut doubled = false;
@@ -442,37 +2153,77 @@ fn derive_contract_declarer(bids: &[&str], dealer: i32) -> (String, String) {
     (contract, int_to_direction_char(declarer).to_string())
 }
 
-/// Write auction with column alignment and =N= annotations
+/// Write auction with column alignment and =N= annotations.
+///
+/// A `truncated` auction (from `--stop-at-level`) ends with the PBN `*`
+/// marker for an incomplete auction instead of the closing passes.
 fn write_annotated_auction(
     writer: &mut impl Write,
     bids: &[epbot_core::BidInfo],
+    strength: Option<(&Deal, Direction)>,
+    truncated: bool,
+    layout: AuctionLayout,
+    pad_grid: bool,
+    column_width: Option<usize>,
 ) -> Result<()> {
     let mut notes: Vec<(usize, String)> = Vec::new();
     let mut entries: Vec<String> = Vec::new();
 
-    for bid in bids {
-        let meaning = bid.meaning.as_deref().unwrap_or("");
+    for (i, bid) in bids.iter().enumerate() {
+        let mut meaning = bid.meaning.clone().unwrap_or_default();
+        if let Some((deal, dealer)) = strength {
+            let seat = (direction_to_int(dealer) + i as i32) % 4;
+            if !meaning.is_empty() {
+                meaning.push_str("; ");
+            }
+            meaning.push_str(&hand_strength(deal, seat));
+        }
         if !meaning.is_empty() {
             let note_num = notes.len() + 1;
-            notes.push((note_num, meaning.to_string()));
+            notes.push((note_num, meaning));
             entries.push(format!("{} ={}=", bid.bid, note_num));
         } else {
             entries.push(bid.bid.clone());
         }
     }
+    if truncated {
+        entries.push("*".to_string());
+    }
 
-    for chunk in entries.chunks(4) {
-        let mut line = String::new();
-        for (j, entry) in chunk.iter().enumerate() {
-            let is_last = j == chunk.len() - 1;
-            if is_last {
-                line.push_str(entry);
-            } else {
-                let width = if entry.len() <= 4 { 6 } else { entry.len() + 4 };
-                line.push_str(&format!("{:<width$}", entry, width = width));
+    match layout {
+        AuctionLayout::Vertical => {
+            for (bid, entry) in bids.iter().zip(&entries) {
+                writeln!(writer, "{} {}", int_to_direction_char(bid.position), entry)?;
+            }
+            if truncated {
+                writeln!(writer, "*")?;
+            }
+        }
+        AuctionLayout::Grid => {
+            if pad_grid {
+                while !entries.len().is_multiple_of(4) {
+                    entries.push("-".to_string());
+                }
+            }
+            for chunk in entries.chunks(4) {
+                let mut line = String::new();
+                for (j, entry) in chunk.iter().enumerate() {
+                    let is_last = j == chunk.len() - 1;
+                    if is_last {
+                        line.push_str(entry);
+                    } else {
+                        // A call too wide for its column still gets a space.
+                        let width = match column_width {
+                            Some(width) => width.max(entry.len() + 1),
+                            None if entry.len() <= 4 => 6,
+                            None => entry.len() + 4,
+                        };
+                        line.push_str(&format!("{:<width$}", entry, width = width));
+                    }
+                }
+                writeln!(writer, "{}", line.trim_end())?;
             }
         }
-        writeln!(writer, "{}", line.trim_end())?;
     }
 
     for (num, meaning) in &notes {
@@ -482,12 +2233,66 @@ fn write_annotated_auction(
     Ok(())
 }
 
+/// `--annotate-strength` note text for `seat`'s hand: `S: 15 HCP, 5-3-3-2`,
+/// with the suit lengths in spade, heart, diamond, club order.
+fn hand_strength(deal: &Deal, seat: i32) -> String {
+    let hand = deal.hand(int_to_direction(seat));
+    let l = hand.suit_lengths();
+    format!(
+        "{}: {} HCP, {}-{}-{}-{}",
+        int_to_direction_char(seat),
+        hand.hcp(),
+        l[0],
+        l[1],
+        l[2],
+        l[3]
+    )
+}
+
+/// `--stamp-metadata`: how this file was produced, as `%` lines of the
+/// leading header, so strict parsers read them before the first game.
+fn write_metadata(writer: &mut impl Write, config: &OutputConfig) -> Result<()> {
+    let engine = epbot_core::version().map_or_else(|_| "unknown".to_string(), |v| v.to_string());
+    let conventions = |path: &str| if path.is_empty() { "EPBot default".to_string() } else { path.to_string() };
+    writeln!(writer, "% Generated-by: bba-cli v{}", env!("CARGO_PKG_VERSION"))?;
+    writeln!(writer, "% Engine: EPBot {}", engine)?;
+    writeln!(
+        writer,
+        "% Conventions: ns={}, ew={}",
+        conventions(&config.ns_conventions_path),
+        conventions(&config.ew_conventions_path)
+    )?;
+    writeln!(writer, "% Generated-at: {}", utc_timestamp())?;
+    Ok(())
+}
+
 fn chrono_date() -> String {
-    let now = std::time::SystemTime::now()
+    date_of(unix_now())
+}
+
+/// `--stamp-metadata` timestamp: date and time of day in UTC.
+fn utc_timestamp() -> String {
+    let now = unix_now();
+    let secs = now % 86400;
+    format!(
+        "{} {:02}:{:02}:{:02} UTC",
+        date_of(now),
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default()
-        .as_secs();
-    let days = now / 86400;
+        .as_secs()
+}
+
+/// PBN date (YYYY.MM.DD, UTC) of a Unix time.
+fn date_of(unix_secs: u64) -> String {
+    let days = unix_secs / 86400;
     let mut year = 1970i64;
     let mut remaining_days = days as i64;
 
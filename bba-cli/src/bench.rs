@@ -0,0 +1,129 @@
+//! Engine benchmark: `bba-cli bench`.
+//!
+//! Bids a fixed corpus of deals compiled into the binary, so timings from
+//! different machines or EPBot library versions are measured on the same
+//! work. Reports total time, throughput, and per-deal latency percentiles.
+
+use anyhow::{Context, Result};
+use epbot_core::{AuctionOptions, ConventionCard, Engine};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Arguments for `bba-cli bench`.
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// Convention file (.bbsa) for both sides; EPBot's defaults if omitted
+    #[arg(long, value_name = "FILE")]
+    pub conventions: Option<PathBuf>,
+
+    /// Times to bid the whole corpus
+    #[arg(long, value_name = "N", default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub rounds: u32,
+}
+
+/// (dealer, vulnerability, deal), with EPBot's seat and vulnerability codes
+/// (dealer 0..4 for N, E, S, W; vulnerability 0=None, 1=EW, 2=NS, 3=Both).
+/// Mixes part-scores, games, slams, competitive auctions and a void or two.
+const CORPUS: [(i32, i32, &str); 16] = [
+    (0, 0, "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT"),
+    (0, 2, "N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984"),
+    (0, 1, "N:J.J76.A7642.AQ98 972.KT92.KQ5.642 KQT863.AQ4.8.KJ5 A54.853.JT93.T73"),
+    (0, 3, "N:AKQT.T2.T97.AT86 8742.Q83.65.QJ32 J3.A9764.AKQ8.97 965.KJ5.J432.K54"),
+    (0, 2, "N:AJ64.T72.A3.KJ98 Q95.J86.J64.T542 K32.AQ953.KQ72.7 T87.K4.T985.AQ63"),
+    (1, 0, "N:KJT72.KQ2.A6432. 43.J7.K8.KJT8642 AQ65.AT964.JT.AQ 98.853.Q975.9753"),
+    (2, 0, "N:T82.A843.43.K952 Q974.75.J9865.J6 A63.QJ92.AT2.A83 KJ5.KT6.KQ7.QT74"),
+    (3, 0, "N:43.T862.Q75.Q642 T65.KJ5.9863.AJ8 A.Q943.AKJT42.KT KQJ9872.A7..9753"),
+    (2, 2, "N:AKJ642.742.Q5.J7 T.A953.T942.K953 98.KJ6.AK87.AQ62 Q753.QT8.J63.T84"),
+    (2, 1, "N:965.8.T764.T9842 Q72.AKJT4.Q95.KJ KT3.Q92.AKJ3.Q73 AJ84.7653.82.A65"),
+    (2, 3, "N:973.KJT.KT7.JT52 K64.85.QJ43.AK93 AQ8.A632.A962.Q6 JT52.Q974.85.874"),
+    (2, 0, "N:KQ852.2.K6532.JT JT.KT876543..KQ3 A74.AQ.AQ87.8542 963.J9.JT94.A976"),
+    (0, 2, "N:AQ96.AT32.Q.A643 7543.9765.J3.JT2 KJ.K.AKT7642.987 T82.QJ84.985.KQ5"),
+    (0, 1, "N:AKT3.76.Q94.KJ73 QJ97.K85.65.A652 8.AQJT942.KJ2.Q4 6542.3.AT873.T98"),
+    (0, 3, "N:Q.KT9.AKT3.AQ965 AT53.63.J8542.72 K987.AQJ72..KJT4 J642.854.Q976.83"),
+    (0, 0, "N:KT9.A.K9654.A963 J432.J4.QT732.Q5 Q76.KT9763.AJ.K8 A85.Q852.8.JT742"),
+];
+
+/// The nearest-rank `pct`th percentile of `sorted` (ascending); zero when
+/// empty.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+pub fn run(args: &BenchArgs) -> Result<()> {
+    let card = args
+        .conventions
+        .as_ref()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read conventions file {:?}", path))
+                .map(|content| ConventionCard::from_content(&content))
+        })
+        .transpose()?;
+    let options = AuctionOptions {
+        ns_card: card.as_ref(),
+        ew_card: card.as_ref(),
+        ..Default::default()
+    };
+    let mut engine = Engine::new().context("Failed to create EPBot engine")?;
+
+    let mut timings = Vec::with_capacity(CORPUS.len() * args.rounds as usize);
+    let mut failures = 0;
+    let start = Instant::now();
+    for _ in 0..args.rounds {
+        for &(dealer, vul, deal) in &CORPUS {
+            let deal_start = Instant::now();
+            let result = engine.generate(deal, dealer, vul, &options);
+            timings.push(deal_start.elapsed());
+            if !result.success {
+                failures += 1;
+            }
+        }
+    }
+    let total = start.elapsed();
+    timings.sort_unstable();
+
+    let conventions = match args.conventions {
+        Some(ref path) => path.display().to_string(),
+        None => "EPBot defaults".to_string(),
+    };
+    let version = epbot_core::version().map_or_else(|_| "unknown".to_string(), |v| v.to_string());
+    println!("EPBot:       {}", version);
+    println!("Conventions: {}", conventions);
+    println!("Deals:       {} ({} x {} rounds)", timings.len(), CORPUS.len(), args.rounds);
+    println!("Total:       {:.3} s", total.as_secs_f64());
+    println!("Throughput:  {:.1} deals/s", timings.len() as f64 / total.as_secs_f64());
+    println!(
+        "Per deal:    p50 {:.2} ms, p90 {:.2} ms, p99 {:.2} ms, max {:.2} ms",
+        millis(percentile(&timings, 50)),
+        millis(percentile(&timings, 90)),
+        millis(percentile(&timings, 99)),
+        millis(percentile(&timings, 100)),
+    );
+    if failures > 0 {
+        println!("Failures:    {}", failures);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearest_rank_percentiles() {
+        let sorted: Vec<Duration> = (1..=10).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&sorted, 50), Duration::from_millis(5));
+        assert_eq!(percentile(&sorted, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&sorted, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&sorted, 0), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+}
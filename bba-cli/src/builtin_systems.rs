@@ -0,0 +1,47 @@
+//! Convention cards compiled into the binary, so `--ns-system sayc` works
+//! without a `.bbsa` file or a `--system-pack` at hand.
+//!
+//! The cards are the ones in the repository's `conventions/` directory.
+
+/// Each built-in card: the names it answers to (any case), its file name,
+/// and its content.
+const SYSTEMS: [(&[&str], &str, &str); 2] = [
+    (
+        &["sayc"],
+        "Sayc.bbsa",
+        include_str!("../../conventions/Sayc.bbsa"),
+    ),
+    (
+        &["2/1", "21gf", "2over1"],
+        "21GF.bbsa",
+        include_str!("../../conventions/21GF.bbsa"),
+    ),
+];
+
+/// The built-in card called `name`, as (file name, content).
+pub fn find(name: &str) -> Option<(&'static str, &'static str)> {
+    SYSTEMS
+        .iter()
+        .find(|(names, _, _)| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+        .map(|&(_, file, content)| (file, content))
+}
+
+/// The first name of every built-in card, for error messages.
+pub fn names() -> Vec<&'static str> {
+    SYSTEMS.iter().map(|(names, _, _)| names[0]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_cards_by_any_name() {
+        assert_eq!(find("SAYC").map(|(file, _)| file), Some("Sayc.bbsa"));
+        assert_eq!(find("2/1").map(|(file, _)| file), Some("21GF.bbsa"));
+        assert_eq!(find("21gf").map(|(file, _)| file), Some("21GF.bbsa"));
+        assert_eq!(find("acol"), None);
+        assert!(find("sayc").unwrap().1.contains("System type = 1"));
+        assert_eq!(names(), ["sayc", "2/1"]);
+    }
+}
@@ -0,0 +1,262 @@
+//! Offline comparison of two bid files: `bba-cli compare`.
+//!
+//! Matches the games of two PBN files by board number (and `[Room]`, so
+//! `--rotate-dealers` runs line up) or by deal, and lists the boards whose
+//! contract or auction differ, with both auctions side by side. Games with
+//! a `[DoubleDummyTricks]` tag (`--double-dummy` output) are also scored,
+//! and the IMP swing between the files is reported. Reads the files only;
+//! the engine is not involved.
+
+use crate::pbn::{self, split_games, InputEncoding, InputFile, RawGame};
+use crate::repl::{parse_dealer, parse_vulnerability};
+use anyhow::Result;
+use epbot_core::score;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Arguments for `bba-cli compare`.
+#[derive(clap::Args, Debug)]
+pub struct CompareArgs {
+    /// First PBN file
+    #[arg(value_name = "FILE_A")]
+    pub a: PathBuf,
+
+    /// Second PBN file
+    #[arg(value_name = "FILE_B")]
+    pub b: PathBuf,
+
+    /// Match games by their Deal tag instead of their board number
+    #[arg(long)]
+    pub by_deal: bool,
+}
+
+/// What one file (or one `--compare` set) says about a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Outcome {
+    pub(crate) contract: String,
+    pub(crate) calls: Vec<String>,
+    /// NS score of the contract played double dummy, when known.
+    pub(crate) score: Option<i32>,
+}
+
+impl Outcome {
+    fn of(game: &RawGame) -> Self {
+        let contract = match (game.tag("Contract"), game.tag("Declarer")) {
+            (Some("Pass"), _) => "Pass".to_string(),
+            (Some(contract), Some(declarer)) => format!("{} by {}", contract, declarer),
+            (Some(contract), None) => contract.to_string(),
+            (None, _) => "-".to_string(),
+        };
+        Outcome {
+            contract,
+            calls: game.auction_calls(),
+            score: dd_score(game),
+        }
+    }
+}
+
+/// NS score of a game's contract played double dummy, from its
+/// `[DoubleDummyTricks]`, `[Vulnerable]`, `[Contract]` and `[Declarer]`.
+fn dd_score(game: &RawGame) -> Option<i32> {
+    let tricks = pbn::parse_dd_tricks(game.tag("DoubleDummyTricks")?)?;
+    let vul = parse_vulnerability(game.tag("Vulnerable")?)?;
+    let contract = game.tag("Contract")?;
+    let declarer = game
+        .tag("Declarer")
+        .and_then(parse_dealer)
+        .or_else(|| contract.eq_ignore_ascii_case("pass").then_some(0))?;
+    score::dd_score_ns(contract, declarer as u8, vul as u8, &tricks)
+}
+
+/// IMPs NS gain in B over A on one board, when both are scored.
+pub(crate) fn swing(left: &Outcome, right: &Outcome) -> Option<i32> {
+    Some(score::imps(right.score? - left.score?))
+}
+
+/// The closing line totalling the swings of the boards both sides scored.
+pub(crate) fn imps_line(total: i32, scored: usize) -> String {
+    format!("NS {:+} IMPs in B over {} boards scored double dummy\n", total, scored)
+}
+
+/// The matching key of a game, or None if it lacks the tag matched on.
+fn key(game: &RawGame, by_deal: bool) -> Option<String> {
+    if by_deal {
+        return game.tag("Deal").map(str::to_string);
+    }
+    let board = game.tag("Board")?;
+    Some(match game.tag("Room") {
+        Some(room) => format!("Board {} ({})", board, room),
+        None => format!("Board {}", board),
+    })
+}
+
+/// Games keyed for matching, in file order; later duplicates are dropped.
+fn keyed(games: &[RawGame], by_deal: bool) -> Vec<(String, Outcome)> {
+    let mut seen = BTreeSet::new();
+    let mut out = Vec::new();
+    for game in games {
+        if let Some(k) = key(game, by_deal) {
+            if seen.insert(k.clone()) {
+                out.push((k, Outcome::of(game)));
+            }
+        }
+    }
+    out
+}
+
+/// Calls four to a line.
+fn auction_lines(calls: &[String]) -> Vec<String> {
+    calls
+        .chunks(4)
+        .map(|chunk| {
+            let line: String = chunk.iter().map(|c| format!("{:<6}", c)).collect();
+            line.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// One differing game: its heading with both contracts (and their scores
+/// and the swing, when scored), then both auctions side by side, then a
+/// blank line.
+pub(crate) fn write_difference(out: &mut String, heading: &str, left: &Outcome, right: &Outcome) {
+    let describe = |o: &Outcome| match o.score {
+        Some(score) => format!("{} (NS {})", o.contract, score),
+        None => o.contract.clone(),
+    };
+    out.push_str(&format!("{}: {} | {}", heading, describe(left), describe(right)));
+    if let Some(imps) = swing(left, right).filter(|&imps| imps != 0) {
+        out.push_str(&format!(", NS {:+} IMPs in B", imps));
+    }
+    out.push('\n');
+    let (l, r) = (auction_lines(&left.calls), auction_lines(&right.calls));
+    for i in 0..l.len().max(r.len()) {
+        let left = l.get(i).map_or("", String::as_str);
+        let right = r.get(i).map_or("", String::as_str);
+        let line = format!("  {:<24} | {}", left, right);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out.push('\n');
+}
+
+/// The report comparing the games of file A with those of file B.
+fn render(a: &[RawGame], b: &[RawGame], by_deal: bool) -> String {
+    let a = keyed(a, by_deal);
+    let b: BTreeMap<String, Outcome> = keyed(b, by_deal).into_iter().collect();
+    let mut out = String::new();
+    let mut matched = 0;
+    let mut differ = 0;
+    let mut only_a = 0;
+    let mut imps = 0;
+    let mut scored = 0;
+
+    for (k, left) in &a {
+        let Some(right) = b.get(k) else {
+            only_a += 1;
+            continue;
+        };
+        matched += 1;
+        if let Some(swing) = swing(left, right) {
+            imps += swing;
+            scored += 1;
+        }
+        if left == right {
+            continue;
+        }
+        differ += 1;
+        write_difference(&mut out, k, left, right);
+    }
+
+    let only_b = b.len() - matched;
+    out.push_str(&format!("{} of {} matched boards differ", differ, matched));
+    if only_a > 0 || only_b > 0 {
+        out.push_str(&format!("; {} only in A, {} only in B", only_a, only_b));
+    }
+    out.push('\n');
+    if scored > 0 {
+        out.push_str(&imps_line(imps, scored));
+    }
+    out
+}
+
+pub fn run(args: &CompareArgs) -> Result<()> {
+    let a = InputFile::read(&args.a, InputEncoding::Auto)?;
+    let b = InputFile::read(&args.b, InputEncoding::Auto)?;
+    print!("{}", render(&split_games(&a.text), &split_games(&b.text), args.by_deal));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "\
+[Board \"1\"]
+[Deal \"N:a\"]
+[Declarer \"S\"]
+[Contract \"4S\"]
+[Auction \"N\"]
+1S Pass 3S Pass
+4S AP
+
+[Board \"2\"]
+[Deal \"N:b\"]
+[Contract \"Pass\"]
+[Auction \"N\"]
+AP Pass
+
+[Board \"3\"]
+[Deal \"N:c\"]
+";
+
+    const B: &str = "\
+[Board \"1\"]
+[Deal \"N:a\"]
+[Declarer \"N\"]
+[Contract \"3NT\"]
+[Auction \"N\"]
+1S Pass 2NT Pass
+3NT AP
+
+[Board \"2\"]
+[Deal \"N:b\"]
+[Contract \"Pass\"]
+[Auction \"N\"]
+Pass Pass Pass Pass
+";
+
+    #[test]
+    fn reports_boards_that_bid_differently() {
+        let report = render(&split_games(A), &split_games(B), false);
+        assert_eq!(
+            report,
+            "\
+Board 1: 4S by S | 3NT by N
+  1S    Pass  3S    Pass   | 1S    Pass  2NT   Pass
+  4S    Pass  Pass  Pass   | 3NT   Pass  Pass  Pass
+
+1 of 2 matched boards differ; 1 only in A, 0 only in B
+"
+        );
+    }
+
+    #[test]
+    fn matches_by_deal_and_room() {
+        let b = B.replace("[Board \"1\"]", "[Board \"9\"]");
+        let report = render(&split_games(A), &split_games(&b), true);
+        assert!(report.starts_with("N:a: 4S by S | 3NT by N\n"), "{report}");
+
+        let a = A.replace("[Deal \"N:a\"]", "[Room \"Dealer E\"]\n[Deal \"N:a\"]");
+        let report = render(&split_games(&a), &split_games(B), false);
+        assert!(report.ends_with("0 of 1 matched boards differ; 2 only in A, 1 only in B\n"), "{report}");
+    }
+
+    #[test]
+    fn scores_boards_with_double_dummy_tricks() {
+        let tags = "[Vulnerable \"None\"]\n[DoubleDummyTricks \"9a7779a7774366643666\"]\n[Deal \"N:a\"]";
+        let (a, b) = (A.replacen("[Deal \"N:a\"]", tags, 1), B.replacen("[Deal \"N:a\"]", tags, 1));
+        let report = render(&split_games(&a), &split_games(&b), false);
+        assert!(report.starts_with("Board 1: 4S by S (NS 420) | 3NT by N (NS 400), NS -1 IMPs in B\n"), "{report}");
+        assert!(report.ends_with("NS -1 IMPs in B over 1 boards scored double dummy\n"), "{report}");
+    }
+}
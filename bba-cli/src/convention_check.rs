@@ -0,0 +1,35 @@
+//! Convention card checking: `bba-cli validate-conventions`.
+//!
+//! The engine loader skips lines it can't read and quietly ignores keys it
+//! doesn't know, so a typo in a `.bbsa` file only shows up as odd bidding.
+//! This reports such lines up front, with their line numbers (see
+//! `ConventionCard::check`).
+
+use anyhow::{Context, Result};
+use epbot_core::ConventionCard;
+use std::path::PathBuf;
+
+/// Arguments for `bba-cli validate-conventions`.
+#[derive(clap::Args, Debug)]
+pub struct ValidateConventionsArgs {
+    /// Convention files (.bbsa) to check
+    #[arg(value_name = "FILE", required = true)]
+    pub files: Vec<PathBuf>,
+}
+
+pub fn run(args: &ValidateConventionsArgs) -> Result<()> {
+    let mut problems = 0;
+    for path in &args.files {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read convention file {:?}", path))?;
+        let card = ConventionCard::from_content(&content);
+        for problem in card.check().context("Failed to create EPBot engine")? {
+            println!("{}:{}: {}", path.display(), problem.line, problem.message);
+            problems += 1;
+        }
+    }
+    if problems > 0 {
+        anyhow::bail!("{} problems found in {} convention files", problems, args.files.len());
+    }
+    Ok(())
+}
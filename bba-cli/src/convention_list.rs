@@ -0,0 +1,173 @@
+//! Convention card inspection: `bba-cli list-conventions` and
+//! `bba-cli diff-conventions`.
+//!
+//! Reads `.bbsa` files the same way the engine loader does and prints the
+//! settings they would apply, so users can confirm which system they run,
+//! or see where two systems part ways when they bid a deal differently.
+
+use anyhow::{Context, Result};
+use epbot_core::{ConventionCard, ConventionSetting};
+use std::path::{Path, PathBuf};
+
+/// Arguments for `bba-cli list-conventions`.
+#[derive(clap::Args, Debug)]
+pub struct ListConventionsArgs {
+    /// Convention file (.bbsa) to inspect
+    #[arg(value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Also list conventions the file switches off
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// The report for `card`: system and opponent types, then the conventions
+/// switched on (and, with `all`, those switched off), in file order.
+pub fn render(card: &ConventionCard, all: bool) -> String {
+    let settings = card.settings();
+    let mut out = String::new();
+    let mut on = Vec::new();
+    let mut off = Vec::new();
+    for setting in &settings {
+        match setting {
+            ConventionSetting::SystemType(value) => out.push_str(&format!("System type: {}\n", value)),
+            ConventionSetting::OpponentType(value) => out.push_str(&format!("Opponent type: {}\n", value)),
+            ConventionSetting::Convention { key, enabled: true } => on.push(key.as_str()),
+            ConventionSetting::Convention { key, enabled: false } => off.push(key.as_str()),
+        }
+    }
+
+    out.push_str(&format!("\nEnabled ({} of {}):\n", on.len(), on.len() + off.len()));
+    for key in &on {
+        out.push_str(&format!("  {}\n", key));
+    }
+    if all {
+        out.push_str(&format!("\nDisabled ({}):\n", off.len()));
+        for key in &off {
+            out.push_str(&format!("  {}\n", key));
+        }
+    }
+    out
+}
+
+pub fn run(args: &ListConventionsArgs) -> Result<()> {
+    print!("{}", render(&read_card(&args.file)?, args.all));
+    Ok(())
+}
+
+/// Arguments for `bba-cli diff-conventions`.
+#[derive(clap::Args, Debug)]
+pub struct DiffConventionsArgs {
+    /// First convention file (.bbsa)
+    #[arg(value_name = "A")]
+    pub a: PathBuf,
+
+    /// Second convention file (.bbsa)
+    #[arg(value_name = "B")]
+    pub b: PathBuf,
+}
+
+/// Each setting a card applies as (name, value), in file order, with the
+/// last value of a key set twice. Conventions read `on` or `off`.
+fn setting_values(card: &ConventionCard) -> Vec<(String, String)> {
+    let mut values: Vec<(String, String)> = Vec::new();
+    for setting in card.settings() {
+        let (name, value) = match setting {
+            ConventionSetting::SystemType(value) => ("System type".to_string(), value.to_string()),
+            ConventionSetting::OpponentType(value) => ("Opponent type".to_string(), value.to_string()),
+            ConventionSetting::Convention { key, enabled } => (key, if enabled { "on" } else { "off" }.to_string()),
+        };
+        match values.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+            Some(entry) => entry.1 = value,
+            None => values.push((name, value)),
+        }
+    }
+    values
+}
+
+/// The settings that differ between `a` and `b`, as (name, value in a,
+/// value in b): a's settings in file order, then those only b sets.
+fn diff(a: &ConventionCard, b: &ConventionCard) -> Vec<(String, Option<String>, Option<String>)> {
+    let a = setting_values(a);
+    let mut b = setting_values(b);
+    let mut changes = Vec::new();
+    for (name, value) in a {
+        let other = b
+            .iter()
+            .position(|(n, _)| n.eq_ignore_ascii_case(&name))
+            .map(|i| b.remove(i).1);
+        if other.as_ref() != Some(&value) {
+            changes.push((name, Some(value), other));
+        }
+    }
+    changes.extend(b.into_iter().map(|(name, value)| (name, None, Some(value))));
+    changes
+}
+
+/// The report for `diff-conventions`: one line per differing setting, with
+/// `-` for a setting a file doesn't mention, then a count.
+fn render_diff(a: &ConventionCard, b: &ConventionCard, a_name: &str, b_name: &str) -> String {
+    let changes = diff(a, b);
+    let width = changes.iter().map(|(name, _, _)| name.len()).max().unwrap_or(0).max("Setting".len());
+    let mut out = String::new();
+    if !changes.is_empty() {
+        out.push_str(&format!("{:<width$}  {:<8}  {}\n", "Setting", a_name, b_name));
+    }
+    for (name, a_value, b_value) in &changes {
+        out.push_str(&format!(
+            "{:<width$}  {:<8}  {}\n",
+            name,
+            a_value.as_deref().unwrap_or("-"),
+            b_value.as_deref().unwrap_or("-")
+        ));
+    }
+    out.push_str(&format!("{} settings differ\n", changes.len()));
+    out
+}
+
+pub fn run_diff(args: &DiffConventionsArgs) -> Result<()> {
+    let name = |path: &Path| path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned());
+    print!(
+        "{}",
+        render_diff(&read_card(&args.a)?, &read_card(&args.b)?, &name(&args.a), &name(&args.b))
+    );
+    Ok(())
+}
+
+fn read_card(path: &Path) -> Result<ConventionCard> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("Failed to read convention file {:?}", path))?;
+    Ok(ConventionCard::from_content(&content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_enabled_then_disabled() {
+        let card = ConventionCard::from_content("System type = 1\nStayman = 1\nGerber = 0\nDrury = true\n");
+        assert_eq!(
+            render(&card, false),
+            "System type: 1\n\nEnabled (2 of 3):\n  Stayman\n  Drury\n"
+        );
+        assert!(render(&card, true).ends_with("\nDisabled (1):\n  Gerber\n"));
+    }
+
+    #[test]
+    fn diffs_two_cards() {
+        let a = ConventionCard::from_content("System type = 0\nStayman = 1\nGerber = 0\nDrury = 1\nDrury = 0\n");
+        let b = ConventionCard::from_content("System type = 1\nstayman = true\nGerber = 1\nJacoby 2NT = 1\n");
+        assert_eq!(
+            render_diff(&a, &b, "a.bbsa", "b.bbsa"),
+            "\
+Setting      a.bbsa    b.bbsa
+System type  0         1
+Gerber       off       on
+Drury        off       -
+Jacoby 2NT   -         on
+4 settings differ
+"
+        );
+        assert_eq!(render_diff(&a, &a, "a", "a"), "0 settings differ\n");
+    }
+}
@@ -0,0 +1,172 @@
+//! Convert a file between formats without bidding it: `bba-cli convert`.
+//!
+//! The input may be PBN, LIN, GIB, dot-separated or JSON deals, or a BRI,
+//! DUP or DLM board file (see `formats`);
+//! the output is any of those, or the CSV rows `--output-format csv` would
+//! write, one per game with a `[Deal]`, read from the auctions and
+//! contracts already in the file.
+
+use crate::csv_export;
+use crate::formats::{self, Format};
+use crate::pbn::{self, split_games, InputEncoding, InputFile};
+use anyhow::{Context, Result};
+use log::info;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Arguments for `bba-cli convert`.
+#[derive(clap::Args, Debug)]
+pub struct ConvertArgs {
+    /// File to convert: PBN, or LIN, GIB, dot, BRI, DUP, DLM or JSON by
+    /// its extension (`-` reads stdin)
+    #[arg(short, long, value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// File to write (`-` writes stdout)
+    #[arg(short, long, value_name = "FILE")]
+    pub output: PathBuf,
+
+    /// Format to write: csv, pbn, lin, gib or dot. By default the output's
+    /// extension, or csv
+    #[arg(long, value_name = "FORMAT", value_parser = parse_target)]
+    pub to: Option<Target>,
+}
+
+/// What `bba-cli convert` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Csv,
+    Deals(Format),
+}
+
+fn parse_target(s: &str) -> std::result::Result<Target, String> {
+    if s.eq_ignore_ascii_case("csv") {
+        return Ok(Target::Csv);
+    }
+    Format::parse(s)
+        .filter(|f| f.is_writable())
+        .map(Target::Deals)
+        .ok_or_else(|| format!("unknown format '{}'; expected csv, pbn, lin, gib or dot", s))
+}
+
+/// `--to`, or the output's extension, or CSV.
+fn target(args: &ConvertArgs) -> Target {
+    args.to
+        .or_else(|| Format::from_extension(&args.output).filter(|f| f.is_writable()).map(Target::Deals))
+        .unwrap_or(Target::Csv)
+}
+
+/// The CSV text for a PBN file, and the number of rows in it.
+fn render_csv(text: &str) -> (String, usize) {
+    let mut out = format!("{}\n", csv_export::HEADER);
+    let mut rows = 0;
+    for game in split_games(text).iter().filter(|g| g.tag("Deal").is_some()) {
+        out.push_str(&csv_export::game_line(game));
+        out.push('\n');
+        rows += 1;
+    }
+    (out, rows)
+}
+
+/// A PBN file's text as `target`, and the number of deals in it.
+fn render(text: &str, target: Target) -> (String, usize) {
+    match target {
+        Target::Csv => render_csv(text),
+        Target::Deals(format) => formats::from_games(format, &split_games(text)),
+    }
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    let mut out = pbn::OutputFile::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    out.write_all(text.as_bytes())
+        .and_then(|_| out.flush())
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+pub fn run(args: &ConvertArgs) -> Result<()> {
+    let input = InputFile::read(&args.input, InputEncoding::Auto)?;
+    let target = target(args);
+    let (text, count) = render(&input.text, target);
+    write(&args.output, &text)?;
+    match target {
+        Target::Csv => info!("Wrote {} rows to {:?}", count, args.output),
+        Target::Deals(format) => info!("Wrote {} {} deals to {:?}", count, format.name(), args.output),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_row_per_deal() {
+        let text = "\
+% PBN 2.1
+
+[Board \"1\"]
+[Dealer \"N\"]
+[Deal \"N:a\"]
+[Contract \"1NT\"]
+[Auction \"N\"]
+1NT AP
+
+[Event \"no deal\"]
+
+[Board \"2\"]
+[Deal \"E:b\"]
+";
+        let (out, rows) = render(text, Target::Csv);
+        assert_eq!(rows, 2);
+        assert_eq!(
+            out,
+            "Board,Dealer,Vulnerable,Deal,Auction,Contract,Declarer\n\
+             1,N,,N:a,1NT Pass Pass Pass,1NT,\n\
+             2,,,E:b,,,\n"
+        );
+    }
+
+    #[test]
+    fn deals_survive_every_round_trip() {
+        let text = "\
+% PBN 2.1
+
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63\"]
+
+[Board \"2\"]
+[Dealer \"E\"]
+[Vulnerable \"NS\"]
+[Deal \"E:AKQJT98765432... .AKQJT98765432.. ..AKQJT98765432. ...AKQJT98765432\"]
+";
+        let deals = |pbn: &str| -> Vec<String> {
+            split_games(pbn).iter().filter_map(|g| pbn::deal_from_north(g.tag("Deal")?)).collect()
+        };
+        for format in [Format::Pbn, Format::Lin, Format::Gib, Format::Dot] {
+            let (out, count) = render(text, Target::Deals(format));
+            assert_eq!(count, 2, "{}", format.name());
+            let back = formats::to_pbn(format, &out);
+            assert_eq!(deals(&back), deals(text), "{}:\n{}", format.name(), out);
+            if matches!(format, Format::Pbn | Format::Lin) {
+                let games = split_games(&back);
+                assert_eq!(games[1].tag("Dealer"), Some("E"), "{}", format.name());
+                assert_eq!(games[1].tag("Vulnerable"), Some("NS"), "{}", format.name());
+            }
+        }
+    }
+
+    #[test]
+    fn picks_the_format_from_the_output() {
+        let args = |output: &str, to: Option<Target>| ConvertArgs { input: "-".into(), output: output.into(), to };
+        assert_eq!(target(&args("-", None)), Target::Csv);
+        assert_eq!(target(&args("out.csv", None)), Target::Csv);
+        assert_eq!(target(&args("out.GIB", None)), Target::Deals(Format::Gib));
+        assert_eq!(target(&args("out.lin", Some(Target::Deals(Format::Dot)))), Target::Deals(Format::Dot));
+        assert_eq!(parse_target("LIN"), Ok(Target::Deals(Format::Lin)));
+        assert!(parse_target("xml").unwrap_err().contains("unknown format 'xml'"));
+        assert!(parse_target("bri").is_err());
+        assert_eq!(target(&args("boards.dup", None)), Target::Csv);
+    }
+}
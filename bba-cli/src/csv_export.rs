@@ -0,0 +1,99 @@
+//! `--output-format csv`: one row per auction instead of a PBN file.
+//!
+//! The table is flat so it opens directly in a spreadsheet: board number,
+//! dealer, vulnerability, the deal in PBN notation, the calls separated by
+//! spaces, and the final contract and declarer. Fields are quoted as
+//! RFC 4180 asks, only when they hold a comma, quote or line break.
+
+use crate::pbn::RawGame;
+
+/// The first line of every CSV output.
+pub const HEADER: &str = "Board,Dealer,Vulnerable,Deal,Auction,Contract,Declarer";
+
+/// One row's fields, in `HEADER` order.
+pub struct Row<'a> {
+    pub board: String,
+    pub dealer: &'a str,
+    pub vulnerable: &'a str,
+    pub deal: &'a str,
+    pub auction: String,
+    pub contract: &'a str,
+    pub declarer: &'a str,
+}
+
+impl Row<'_> {
+    /// The row as a line of CSV, without its line ending.
+    pub fn to_line(&self) -> String {
+        [
+            self.board.as_str(),
+            self.dealer,
+            self.vulnerable,
+            self.deal,
+            self.auction.as_str(),
+            self.contract,
+            self.declarer,
+        ]
+        .iter()
+        .map(|f| field(f))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+}
+
+/// The row for a game copied from the input, read from its tags. Missing
+/// tags leave their fields empty.
+pub fn game_line(game: &RawGame) -> String {
+    Row {
+        board: game.tag("Board").unwrap_or_default().to_string(),
+        dealer: game.tag("Dealer").unwrap_or_default(),
+        vulnerable: game.tag("Vulnerable").unwrap_or_default(),
+        deal: game.tag("Deal").unwrap_or_default(),
+        auction: game.auction_calls().join(" "),
+        contract: game.tag("Contract").unwrap_or_default(),
+        declarer: game.tag("Declarer").unwrap_or_default(),
+    }
+    .to_line()
+}
+
+fn field(value: &str) -> std::borrow::Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\"")).into()
+    } else {
+        value.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbn::split_games;
+
+    #[test]
+    fn quotes_only_fields_that_need_it() {
+        assert_eq!(field("1NT Pass"), "1NT Pass");
+        assert_eq!(field("a,b"), "\"a,b\"");
+        assert_eq!(field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+
+    #[test]
+    fn copied_games_are_read_from_their_tags() {
+        let text = "\
+[Board \"7\"]
+[Dealer \"S\"]
+[Vulnerable \"All\"]
+[Deal \"N:a\"]
+[Declarer \"N\"]
+[Contract \"3NT\"]
+[Auction \"S\"]
+Pass 1NT Pass 3NT
+AP
+";
+        let game = split_games(text).remove(0);
+        assert_eq!(
+            game_line(&game),
+            "7,S,All,N:a,Pass 1NT Pass 3NT Pass Pass Pass,3NT,N"
+        );
+        let bare = split_games("[Board \"8\"]\n").remove(0);
+        assert_eq!(game_line(&bare), "8,,,,,,");
+    }
+}
@@ -0,0 +1,381 @@
+//! The `bba-cli deal --where` expression language.
+//!
+//! An expression is a condition on a dealt hand set, e.g.
+//! `hcp(S) >= 20`, `hcp(NS) >= 25 and spades(N) + spades(S) >= 8`, or
+//! `shape(E, any 4432) or not balanced(E)`:
+//!
+//! - numbers: `hcp(SEATS)`, `spades(SEATS)`, `hearts(SEATS)`,
+//!   `diamonds(SEATS)`, `clubs(SEATS)` and integers, joined with `+` and
+//!   `-`. `SEATS` is a seat (`N`, `North`, ...) or a side (`NS`, `EW`),
+//!   whose hands are added together.
+//! - comparisons: `<`, `<=`, `==` (or `=`), `!=`, `>=`, `>`, which chain:
+//!   `15 <= hcp(S) <= 17`.
+//! - `balanced(SEAT)` (4333, 4432, 5332), and `shape(SEAT, PATTERN)` with
+//!   the suit lengths in S-H-D-C order and `x` for any length (`5xxx`), or
+//!   `shape(SEAT, any PATTERN)` for the lengths in any order.
+//! - `and` (`&&`), `or` (`||`), `not` (`!`), and parentheses.
+
+use crate::deal_gen::{is_balanced, Dealt};
+
+/// A parsed `--where` expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    /// `a < b <= c ...`: every neighbouring pair must hold.
+    Compare(Value, Vec<(Op, Value)>),
+    Balanced(usize),
+    /// Seat, lengths in S, H, D, C order (None for `x`), and `any`.
+    Shape(usize, [Option<u8>; 4], bool),
+}
+
+/// A number in an expression: terms added or subtracted in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Value(Vec<(i32, Term)>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Number(i32),
+    Hcp(Vec<usize>),
+    /// Suit (S, H, D, C = 0..4) length over the seats.
+    Length(usize, Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+impl Expr {
+    /// Whether `deal` satisfies the expression.
+    pub fn accepts(&self, deal: &Dealt) -> bool {
+        match self {
+            Expr::Or(a, b) => a.accepts(deal) || b.accepts(deal),
+            Expr::And(a, b) => a.accepts(deal) && b.accepts(deal),
+            Expr::Not(a) => !a.accepts(deal),
+            Expr::Compare(first, rest) => {
+                let mut left = first.eval(deal);
+                rest.iter().all(|(op, value)| {
+                    let right = value.eval(deal);
+                    let holds = match op {
+                        Op::Lt => left < right,
+                        Op::Le => left <= right,
+                        Op::Eq => left == right,
+                        Op::Ne => left != right,
+                        Op::Ge => left >= right,
+                        Op::Gt => left > right,
+                    };
+                    left = right;
+                    holds
+                })
+            }
+            Expr::Balanced(seat) => is_balanced(deal.lengths(*seat)),
+            Expr::Shape(seat, pattern, any) => {
+                let lengths = deal.lengths(*seat);
+                let fits = |order: &[usize]| {
+                    order.iter().zip(pattern).all(|(&suit, want)| want.is_none_or(|n| lengths[suit] == n))
+                };
+                if *any {
+                    permutations().iter().any(|order| fits(order))
+                } else {
+                    fits(&[0, 1, 2, 3])
+                }
+            }
+        }
+    }
+}
+
+impl Value {
+    fn eval(&self, deal: &Dealt) -> i32 {
+        self.0
+            .iter()
+            .map(|(sign, term)| {
+                sign * match term {
+                    Term::Number(n) => *n,
+                    Term::Hcp(seats) => seats.iter().map(|&s| i32::from(deal.hcp(s))).sum(),
+                    Term::Length(suit, seats) => seats.iter().map(|&s| i32::from(deal.lengths(s)[*suit])).sum(),
+                }
+            })
+            .sum()
+    }
+}
+
+/// The 24 orders of the four suits.
+fn permutations() -> Vec<[usize; 4]> {
+    let mut out = Vec::with_capacity(24);
+    for a in 0..4 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                out.push([a, b, c, 6 - a - b - c]);
+            }
+        }
+    }
+    out
+}
+
+/// Parse a `--where` expression.
+pub fn parse(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0 };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected '{}'", token)),
+    }
+}
+
+/// Words (names and numbers, e.g. `hcp`, `20`, `5xxx`) and operators.
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric()) {
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(word);
+        } else {
+            chars.next();
+            let two = chars.peek().map(|&next| format!("{}{}", c, next));
+            match two.as_deref() {
+                Some("<=" | ">=" | "==" | "!=" | "&&" | "||") => {
+                    tokens.push(two.unwrap());
+                    chars.next();
+                }
+                _ if "<>=!+-(),".contains(c) => tokens.push(c.to_string()),
+                _ => return Err(format!("unexpected character '{}'", c)),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    /// The next token, lowercased, or an error naming what was wanted.
+    fn next(&mut self, wanted: &str) -> Result<String, String> {
+        let token = self.tokens.get(self.pos).ok_or_else(|| format!("expected {} at end of expression", wanted))?;
+        self.pos += 1;
+        Ok(token.to_lowercase())
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.peek().is_some_and(|t| t.eq_ignore_ascii_case(token));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        match self.next(&format!("'{}'", token))? {
+            t if t == token => Ok(()),
+            t => Err(format!("expected '{}' but found '{}'", token, t)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.eat("or") || self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.eat("and") || self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("not") || self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let expr = self.or()?;
+            self.expect(")")?;
+            return Ok(expr);
+        }
+        if self.eat("balanced") {
+            self.expect("(")?;
+            let seat = self.seat()?;
+            self.expect(")")?;
+            return Ok(Expr::Balanced(seat));
+        }
+        if self.eat("shape") {
+            self.expect("(")?;
+            let seat = self.seat()?;
+            self.expect(",")?;
+            let any = self.eat("any");
+            let pattern = self.pattern()?;
+            self.expect(")")?;
+            return Ok(Expr::Shape(seat, pattern, any));
+        }
+        let first = self.value()?;
+        let mut rest = Vec::new();
+        while let Some(op) = self.op() {
+            rest.push((op, self.value()?));
+        }
+        if rest.is_empty() {
+            return Err(match self.peek() {
+                Some(token) => format!("expected a comparison but found '{}'", token),
+                None => "expected a comparison at end of expression".to_string(),
+            });
+        }
+        Ok(Expr::Compare(first, rest))
+    }
+
+    fn op(&mut self) -> Option<Op> {
+        let op = match self.peek()? {
+            "<" => Op::Lt,
+            "<=" => Op::Le,
+            "=" | "==" => Op::Eq,
+            "!=" => Op::Ne,
+            ">=" => Op::Ge,
+            ">" => Op::Gt,
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        let mut terms = vec![(1, self.term()?)];
+        loop {
+            let sign = if self.eat("+") {
+                1
+            } else if self.eat("-") {
+                -1
+            } else {
+                return Ok(Value(terms));
+            };
+            terms.push((sign, self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<Term, String> {
+        let word = self.next("a number or hcp(...)")?;
+        if let Ok(n) = word.parse() {
+            return Ok(Term::Number(n));
+        }
+        let suit = ["spades", "hearts", "diamonds", "clubs"].iter().position(|&s| s == word);
+        if word != "hcp" && suit.is_none() {
+            return Err(format!("unknown function '{}'; expected hcp, spades, hearts, diamonds or clubs", word));
+        }
+        self.expect("(")?;
+        let seats = self.seats()?;
+        self.expect(")")?;
+        Ok(match suit {
+            Some(suit) => Term::Length(suit, seats),
+            None => Term::Hcp(seats),
+        })
+    }
+
+    /// A seat or side: N, E, S, W (or their full names), NS, EW.
+    fn seats(&mut self) -> Result<Vec<usize>, String> {
+        let word = self.next("a seat")?;
+        Ok(match word.as_str() {
+            "n" | "north" => vec![0],
+            "e" | "east" => vec![1],
+            "s" | "south" => vec![2],
+            "w" | "west" => vec![3],
+            "ns" => vec![0, 2],
+            "ew" => vec![1, 3],
+            _ => return Err(format!("unknown seat '{}'; expected N, E, S, W, NS or EW", word)),
+        })
+    }
+
+    fn seat(&mut self) -> Result<usize, String> {
+        match self.seats()?[..] {
+            [seat] => Ok(seat),
+            _ => Err("expected a single seat, not a side".to_string()),
+        }
+    }
+
+    /// Four suit lengths or `x`, e.g. `4432` or `5xxx`.
+    fn pattern(&mut self) -> Result<[Option<u8>; 4], String> {
+        let word = self.next("a shape pattern")?;
+        let bad = || format!("bad shape pattern '{}'; expected four lengths or x, e.g. 4432 or 5xxx", word);
+        let lengths: Vec<Option<u8>> = word
+            .chars()
+            .map(|c| match c {
+                'x' => Ok(None),
+                _ => c.to_digit(10).map(|d| Some(d as u8)).ok_or_else(bad),
+            })
+            .collect::<Result<_, _>>()?;
+        let total: u8 = lengths.iter().flatten().sum();
+        let wild = lengths.iter().any(Option::is_none);
+        match <[Option<u8>; 4]>::try_from(lengths) {
+            Ok(pattern) if total == 13 || (wild && total < 13) => Ok(pattern),
+            _ => Err(bad()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deal_gen::Shuffler;
+
+    #[test]
+    fn parses_expressions() {
+        assert_eq!(
+            parse("hcp(S) >= 20").unwrap(),
+            Expr::Compare(Value(vec![(1, Term::Hcp(vec![2]))]), vec![(Op::Ge, Value(vec![(1, Term::Number(20))]))])
+        );
+        assert!(matches!(parse("15 <= hcp(south) <= 17").unwrap(), Expr::Compare(_, rest) if rest.len() == 2));
+        assert!(matches!(
+            parse("not balanced(E) or shape(e, any 4432) && spades(NS) - 8 > 0").unwrap(),
+            Expr::Or(a, b) if matches!(*a, Expr::Not(_)) && matches!(*b, Expr::And(..))
+        ));
+        assert_eq!(parse("shape(N, 5xxx)").unwrap(), Expr::Shape(0, [Some(5), None, None, None], false));
+    }
+
+    #[test]
+    fn reports_mistakes() {
+        assert_eq!(parse("hcp(S) >").unwrap_err(), "expected a number or hcp(...) at end of expression");
+        assert_eq!(parse("hcp(Q) > 1").unwrap_err(), "unknown seat 'q'; expected N, E, S, W, NS or EW");
+        assert_eq!(parse("points(S) > 1").unwrap_err().split(';').next(), Some("unknown function 'points'"));
+        assert_eq!(parse("hcp(S)").unwrap_err(), "expected a comparison at end of expression");
+        assert_eq!(parse("hcp(S) > 1)").unwrap_err(), "unexpected ')'");
+        assert!(parse("shape(N, 4442)").unwrap_err().starts_with("bad shape pattern"));
+        assert_eq!(parse("balanced(NS)").unwrap_err(), "expected a single seat, not a side");
+        assert_eq!(parse("hcp(S) > 1 ; 2").unwrap_err(), "unexpected character ';'");
+    }
+
+    #[test]
+    fn evaluates_against_deals() {
+        let mut shuffler = Shuffler::new(3);
+        let strong = parse("hcp(S) >= 15 and hcp(NS) - hcp(S) < 10").unwrap();
+        let any_4432 = parse("shape(W, any 4432)").unwrap();
+        let majors = parse("spades(N) + hearts(N) = 13 - diamonds(N) - clubs(N)").unwrap();
+        for _ in 0..200 {
+            let deal = shuffler.deal();
+            assert_eq!(strong.accepts(&deal), deal.hcp(2) >= 15 && deal.hcp(0) < 10);
+            let mut lengths = deal.lengths(3);
+            lengths.sort_unstable();
+            assert_eq!(any_4432.accepts(&deal), lengths == [2, 3, 4, 4]);
+            assert!(majors.accepts(&deal));
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! Random deals, without an external dealer program: `bba-cli deal`.
+//!
+//! Shuffles `--count` deals from a seed (so a set can be dealt again) and
+//! writes them as PBN games with the standard board dealer and
+//! vulnerability. `--constraint` keeps only deals where a seat's hand fits,
+//! e.g. "South 15-17 balanced", and `--where` keeps only deals where an
+//! expression holds, e.g. "hcp(NS) >= 25 and spades(N) + spades(S) >= 8"
+//! (see `deal_expr`). The output can be piped straight into
+//! `bba-cli --input -`.
+
+use crate::deal_expr::{self, Expr};
+use crate::pbn;
+use anyhow::{Context, Result};
+use log::info;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Arguments for `bba-cli deal`.
+#[derive(clap::Args, Debug)]
+pub struct DealArgs {
+    /// Number of deals to generate
+    #[arg(short = 'n', long, value_name = "N", default_value_t = 1)]
+    pub count: u32,
+
+    /// Seed for the shuffle; the same seed deals the same boards. Without
+    /// it a seed is taken from the clock and logged
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Only keep deals where a seat's hand fits, e.g. "South 15-17
+    /// balanced" or "N 12+ 5+S 0-1C" (repeat for several seats)
+    #[arg(short, long, value_name = "SPEC", value_parser = parse_constraint)]
+    pub constraint: Vec<Constraint>,
+
+    /// Only keep deals where an expression holds, e.g. "hcp(S) >= 20" or
+    /// "shape(N, any 4432) and hcp(NS) >= 25" (repeat to require several)
+    #[arg(long = "where", value_name = "EXPR", value_parser = deal_expr::parse)]
+    pub filter: Vec<Expr>,
+
+    /// PBN file to write (`-` writes stdout)
+    #[arg(short, long, value_name = "FILE", default_value = "-")]
+    pub output: PathBuf,
+}
+
+/// Shuffles tried for each deal before giving up on the constraints.
+const MAX_TRIES: u32 = 1_000_000;
+
+const RANKS: &str = "AKQJT98765432";
+
+/// A dealt pack: `cards[13 * seat..][..13]` is seat's hand (N, E, S, W).
+/// Card `c` is suit `c / 13` (S, H, D, C) and rank `c % 13` (ace first).
+pub struct Dealt([u8; 52]);
+
+impl Dealt {
+    fn hand(&self, seat: usize) -> &[u8] {
+        &self.0[13 * seat..13 * seat + 13]
+    }
+
+    /// High-card points of `seat`'s hand (A=4, K=3, Q=2, J=1).
+    pub fn hcp(&self, seat: usize) -> u8 {
+        self.hand(seat).iter().map(|&c| 4u8.saturating_sub(c % 13)).sum()
+    }
+
+    /// Suit lengths of `seat`'s hand in S, H, D, C order.
+    pub fn lengths(&self, seat: usize) -> [u8; 4] {
+        let mut lengths = [0; 4];
+        for &c in self.hand(seat) {
+            lengths[(c / 13) as usize] += 1;
+        }
+        lengths
+    }
+
+    /// The deal as a PBN `[Deal]` value from North, each suit's cards in
+    /// rank order.
+    pub fn pbn(&self) -> String {
+        let hands: Vec<String> = (0..4)
+            .map(|seat| {
+                let mut hand = self.hand(seat).to_vec();
+                hand.sort_unstable();
+                (0..4)
+                    .map(|suit| {
+                        hand.iter()
+                            .filter(|&&c| c / 13 == suit)
+                            .map(|&c| RANKS.as_bytes()[(c % 13) as usize] as char)
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .collect();
+        format!("N:{}", hands.join(" "))
+    }
+}
+
+/// A seeded card shuffler (xorshift64; plenty for dealing practice boards).
+pub struct Shuffler(u64);
+
+impl Shuffler {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 first, so nearby seeds start far apart and the state is
+        // never zero (where xorshift sticks).
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        Shuffler((z ^ (z >> 31)).max(1))
+    }
+
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// The next deal.
+    pub fn deal(&mut self) -> Dealt {
+        let mut cards = [0u8; 52];
+        for (i, card) in cards.iter_mut().enumerate() {
+            *card = i as u8;
+        }
+        for i in (1..cards.len()).rev() {
+            let j = (self.next() % (i as u64 + 1)) as usize;
+            cards.swap(i, j);
+        }
+        Dealt(cards)
+    }
+}
+
+/// A seed from the clock, for when none is given.
+pub fn clock_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64)
+        ^ u64::from(std::process::id())
+}
+
+/// An inclusive range, e.g. 15-17 HCP or 5+ cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Range {
+    min: u8,
+    max: u8,
+}
+
+impl Range {
+    fn contains(self, n: u8) -> bool {
+        (self.min..=self.max).contains(&n)
+    }
+}
+
+/// What one seat's hand must hold (`--constraint`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constraint {
+    seat: usize,
+    hcp: Option<Range>,
+    balanced: Option<bool>,
+    /// Suit lengths in S, H, D, C order.
+    lengths: [Option<Range>; 4],
+}
+
+impl Constraint {
+    fn accepts(&self, deal: &Dealt) -> bool {
+        let lengths = deal.lengths(self.seat);
+        self.hcp.is_none_or(|r| r.contains(deal.hcp(self.seat)))
+            && self.balanced.is_none_or(|b| is_balanced(lengths) == b)
+            && self.lengths.iter().zip(lengths).all(|(r, n)| r.is_none_or(|r| r.contains(n)))
+    }
+}
+
+/// 4333, 4432 and 5332 count as balanced.
+pub fn is_balanced(mut lengths: [u8; 4]) -> bool {
+    lengths.sort_unstable();
+    matches!(lengths, [3, 3, 3, 4] | [2, 3, 4, 4] | [2, 3, 3, 5])
+}
+
+/// `15-17`, `12+` or `5`, up to `max`.
+fn parse_range(s: &str, max: u8) -> Option<Range> {
+    let range = if let Some(min) = s.strip_suffix('+') {
+        Range { min: min.parse().ok()?, max }
+    } else if let Some((min, high)) = s.split_once('-') {
+        Range { min: min.parse().ok()?, max: high.parse().ok()? }
+    } else {
+        let n = s.parse().ok()?;
+        Range { min: n, max: n }
+    };
+    (range.min <= range.max && range.max <= max).then_some(range)
+}
+
+/// Parse `SEAT CONDITION...`: conditions are an HCP range (`15-17`, `12+`,
+/// optionally followed by `hcp`), `balanced` or `unbalanced`, and suit
+/// lengths (`5+S`, `4-5H`, `0-1C`).
+pub fn parse_constraint(s: &str) -> std::result::Result<Constraint, String> {
+    let mut words = s.split_whitespace();
+    let seat = match words.next().map(str::to_uppercase).as_deref() {
+        Some("N" | "NORTH") => 0,
+        Some("E" | "EAST") => 1,
+        Some("S" | "SOUTH") => 2,
+        Some("W" | "WEST") => 3,
+        Some(other) => return Err(format!("unknown seat '{}'; expected N, E, S or W", other)),
+        None => return Err("empty constraint; expected e.g. \"South 15-17 balanced\"".to_string()),
+    };
+    let mut constraint = Constraint { seat, hcp: None, balanced: None, lengths: [None; 4] };
+    for word in words {
+        let lower = word.to_lowercase();
+        match lower.as_str() {
+            "balanced" => constraint.balanced = Some(true),
+            "unbalanced" => constraint.balanced = Some(false),
+            "hcp" => {}
+            _ => {
+                let bad = || format!("unknown condition '{}'; expected e.g. 15-17, 12+, balanced or 5+S", word);
+                let (range, suit) = match lower.char_indices().last() {
+                    Some((i, c @ ('s' | 'h' | 'd' | 'c'))) => (&lower[..i], "shdc".find(c)),
+                    _ => (lower.strip_suffix("hcp").unwrap_or(&lower), None),
+                };
+                match suit {
+                    Some(suit) => constraint.lengths[suit] = Some(parse_range(range, 13).ok_or_else(bad)?),
+                    None => constraint.hcp = Some(parse_range(range, 37).ok_or_else(bad)?),
+                }
+            }
+        }
+    }
+    Ok(constraint)
+}
+
+/// `count` deals satisfying every constraint and filter, as a PBN file.
+fn render(count: u32, seed: u64, constraints: &[Constraint], filters: &[Expr]) -> Result<String> {
+    let mut shuffler = Shuffler::new(seed);
+    let mut out = format!("% PBN 2.1\n% Dealt by bba-cli, seed {}\n", seed);
+    for number in 1..=count {
+        let deal = (0..MAX_TRIES)
+            .map(|_| shuffler.deal())
+            .find(|deal| constraints.iter().all(|c| c.accepts(deal)) && filters.iter().all(|f| f.accepts(deal)))
+            .with_context(|| format!("No deal fitting the constraints found in {} shuffles", MAX_TRIES))?;
+        out.push_str(&format!(
+            "\n[Board \"{}\"]\n[Dealer \"{}\"]\n[Vulnerable \"{}\"]\n[Deal \"{}\"]\n",
+            number,
+            pbn::board_dealer_tag(number),
+            pbn::board_vulnerable_tag(number),
+            deal.pbn()
+        ));
+    }
+    Ok(out)
+}
+
+pub fn run(args: &DealArgs) -> Result<()> {
+    let seed = args.seed.unwrap_or_else(clock_seed);
+    let text = render(args.count, seed, &args.constraint, &args.filter)?;
+    let mut out = pbn::OutputFile::create(&args.output).with_context(|| format!("Failed to create {:?}", args.output))?;
+    out.write_all(text.as_bytes())
+        .and_then(|_| out.flush())
+        .with_context(|| format!("Failed to write {:?}", args.output))?;
+    info!("Wrote {} deals to {:?} (seed {})", args.count, args.output, seed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deals_are_complete_and_repeatable() {
+        let mut shuffler = Shuffler::new(42);
+        let deals: Vec<String> = (0..20).map(|_| shuffler.deal().pbn()).collect();
+        for deal in &deals {
+            assert_eq!(pbn::check_deal(deal), Ok(()), "{}", deal);
+        }
+        let mut again = Shuffler::new(42);
+        assert_eq!(again.deal().pbn(), deals[0]);
+        assert_ne!(Shuffler::new(43).deal().pbn(), deals[0]);
+    }
+
+    #[test]
+    fn parses_constraints() {
+        let c = parse_constraint("South 15-17 balanced").unwrap();
+        assert_eq!(c.seat, 2);
+        assert_eq!(c.hcp, Some(Range { min: 15, max: 17 }));
+        assert_eq!(c.balanced, Some(true));
+
+        let c = parse_constraint("n 12+hcp 5+S 0-1c").unwrap();
+        assert_eq!(c.hcp, Some(Range { min: 12, max: 37 }));
+        assert_eq!(c.lengths[0], Some(Range { min: 5, max: 13 }));
+        assert_eq!(c.lengths[3], Some(Range { min: 0, max: 1 }));
+
+        assert!(parse_constraint("Q 12+").unwrap_err().contains("unknown seat"));
+        assert!(parse_constraint("S 17-15").unwrap_err().contains("'17-15'"));
+        assert!(parse_constraint("S 14S").is_err());
+        assert!(parse_constraint("S strong").is_err());
+    }
+
+    #[test]
+    fn constrained_deals_fit() {
+        let text = render(5, 7, &[parse_constraint("S 15-17 balanced").unwrap(), parse_constraint("N 6+H").unwrap()], &[]).unwrap();
+        let games = pbn::split_games(&text);
+        assert_eq!(games.len(), 5);
+        assert_eq!(games[1].tag("Dealer"), Some("E"));
+        assert_eq!(games[1].tag("Vulnerable"), Some("NS"));
+
+        let mut shuffler = Shuffler::new(7);
+        let south = parse_constraint("S 15-17 balanced").unwrap();
+        for _ in 0..200 {
+            let deal = shuffler.deal();
+            let fits = (15..=17).contains(&deal.hcp(2)) && is_balanced(deal.lengths(2));
+            assert_eq!(south.accepts(&deal), fits);
+        }
+    }
+
+    #[test]
+    fn filtered_deals_fit() {
+        let filter = deal_expr::parse("hcp(S) >= 20 and shape(S, any 4432)").unwrap();
+        let text = render(3, 11, &[], std::slice::from_ref(&filter)).unwrap();
+        let games = pbn::split_games(&text);
+        assert_eq!(games.len(), 3);
+        let mut shuffler = Shuffler::new(11);
+        let first = (0..MAX_TRIES).map(|_| shuffler.deal()).find(|deal| filter.accepts(deal)).unwrap();
+        assert!(first.hcp(2) >= 20);
+        assert_eq!(games[0].tag("Deal"), Some(first.pbn().as_str()));
+    }
+}
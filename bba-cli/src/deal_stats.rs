@@ -0,0 +1,179 @@
+//! Distribution statistics for a deal set: `bba-cli stats`.
+//!
+//! Works on the parsed deals alone, without the engine: average HCP per
+//! seat, how often hands are balanced, suit-length distributions, and the
+//! most common hand patterns.
+
+use crate::pbn::{InputEncoding, InputFile};
+use anyhow::Result;
+use bridge_parsers::Direction;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Arguments for `bba-cli stats`.
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// PBN file to analyze
+    #[arg(short, long, value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// Number of hand patterns to list
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    pub top_shapes: usize,
+}
+
+const SEATS: [(Direction, &str); 4] = [
+    (Direction::North, "North"),
+    (Direction::East, "East"),
+    (Direction::South, "South"),
+    (Direction::West, "West"),
+];
+
+const SUITS: [&str; 4] = ["Spades", "Hearts", "Diamonds", "Clubs"];
+
+/// Suit lengths from 8 up share the last row of the length table.
+const MAX_LENGTH_ROW: usize = 8;
+
+/// Running totals over every hand seen.
+#[derive(Debug, Default)]
+pub struct DealSetStats {
+    pub deals: usize,
+    hcp_total: [u32; 4],
+    balanced: [usize; 4],
+    /// suit_lengths[suit][length], lengths >= 8 in the last slot
+    suit_lengths: [[usize; MAX_LENGTH_ROW + 1]; 4],
+    /// Pattern ("4432") to count, over all seats
+    patterns: BTreeMap<String, usize>,
+}
+
+impl DealSetStats {
+    /// Add one hand: `seat` 0..4 (N, E, S, W), its HCP, and its suit lengths
+    /// in S, H, D, C order.
+    pub fn add_hand(&mut self, seat: usize, hcp: u8, lengths: [u8; 4]) {
+        self.hcp_total[seat] += hcp as u32;
+        if is_balanced(lengths) {
+            self.balanced[seat] += 1;
+        }
+        for (suit, &len) in lengths.iter().enumerate() {
+            self.suit_lengths[suit][(len as usize).min(MAX_LENGTH_ROW)] += 1;
+        }
+        *self.patterns.entry(pattern(lengths)).or_insert(0) += 1;
+    }
+
+    pub fn average_hcp(&self, seat: usize) -> f64 {
+        ratio(self.hcp_total[seat] as usize, self.deals)
+    }
+
+    /// Fraction (0..1) of `seat`'s hands that are balanced.
+    pub fn balanced_rate(&self, seat: usize) -> f64 {
+        ratio(self.balanced[seat], self.deals)
+    }
+
+    /// Patterns by descending frequency (ties alphabetical), as fractions
+    /// of all hands.
+    pub fn top_patterns(&self, n: usize) -> Vec<(&str, f64)> {
+        let hands = self.deals * 4;
+        let mut patterns: Vec<(&str, usize)> =
+            self.patterns.iter().map(|(p, &c)| (p.as_str(), c)).collect();
+        patterns.sort_by_key(|&(_, c)| std::cmp::Reverse(c));
+        patterns
+            .into_iter()
+            .take(n)
+            .map(|(p, c)| (p, ratio(c, hands)))
+            .collect()
+    }
+
+    /// Render the report tables.
+    pub fn render(&self, top_shapes: usize) -> String {
+        let mut out = format!("Deals: {}\n\n", self.deals);
+
+        out.push_str("Seat     Avg HCP  Balanced\n");
+        for (seat, (_, name)) in SEATS.iter().enumerate() {
+            out.push_str(&format!(
+                "{:<8} {:>7.2}  {:>7.1}%\n",
+                name,
+                self.average_hcp(seat),
+                100.0 * self.balanced_rate(seat)
+            ));
+        }
+
+        out.push_str("\nLength");
+        for suit in SUITS {
+            out.push_str(&format!("  {:>8}", suit));
+        }
+        out.push('\n');
+        for len in 0..=MAX_LENGTH_ROW {
+            let label = if len == MAX_LENGTH_ROW { format!("{}+", len) } else { len.to_string() };
+            out.push_str(&format!("{:<6}", label));
+            for suit in 0..4 {
+                let share = ratio(self.suit_lengths[suit][len], self.deals * 4);
+                out.push_str(&format!("  {:>7.1}%", 100.0 * share));
+            }
+            out.push('\n');
+        }
+
+        out.push_str("\nPattern  Share\n");
+        for (p, share) in self.top_patterns(top_shapes) {
+            out.push_str(&format!("{:<8} {:>5.1}%\n", p, 100.0 * share));
+        }
+        out
+    }
+}
+
+/// 4333, 4432 and 5332 count as balanced.
+fn is_balanced(lengths: [u8; 4]) -> bool {
+    matches!(pattern(lengths).as_str(), "4333" | "4432" | "5332")
+}
+
+/// Suit lengths longest first, e.g. "5431".
+fn pattern(mut lengths: [u8; 4]) -> String {
+    lengths.sort_unstable_by(|a, b| b.cmp(a));
+    lengths.iter().map(|l| l.to_string()).collect()
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+pub fn run(args: &StatsArgs) -> Result<()> {
+    let boards = InputFile::read(&args.input, InputEncoding::Auto)?.boards()?;
+
+    let mut stats = DealSetStats::default();
+    for board in &boards {
+        stats.deals += 1;
+        for (seat, (dir, _)) in SEATS.iter().enumerate() {
+            let hand = board.deal.hand(*dir);
+            stats.add_hand(seat, hand.hcp(), hand.suit_lengths());
+        }
+    }
+
+    print!("{}", stats.render(args.top_shapes));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_hands() {
+        let mut stats = DealSetStats {
+            deals: 2,
+            ..Default::default()
+        };
+        stats.add_hand(0, 15, [4, 3, 3, 3]);
+        stats.add_hand(0, 9, [6, 4, 2, 1]);
+        stats.add_hand(1, 12, [2, 4, 4, 3]);
+        stats.add_hand(1, 10, [9, 2, 1, 1]);
+
+        assert_eq!(stats.average_hcp(0), 12.0);
+        assert_eq!(stats.balanced_rate(0), 0.5);
+        assert_eq!(stats.balanced_rate(1), 0.5);
+        assert_eq!(stats.suit_lengths[0][MAX_LENGTH_ROW], 1);
+        assert_eq!(stats.top_patterns(1), vec![("4333", 0.125)]);
+    }
+}
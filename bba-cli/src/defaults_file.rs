@@ -0,0 +1,100 @@
+//! Persistent default arguments from a `.bbarc` file.
+//!
+//! The file holds `run` arguments, one per line (`--flag=value` for options
+//! that take a value); blank lines and lines starting with `#` are skipped.
+//! Its arguments are placed right after `run` (spelled out or implied), before
+//! the real ones, so anything given on the command line wins. Other
+//! subcommands don't read it.
+//!
+//! Which file is used: `--config FILE` if given, else `.bbarc` in the
+//! current directory, else `~/.bbarc`. Setting `BBA_NO_CONFIG` (to anything)
+//! turns discovery off; an explicit `--config` is still read.
+
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = ".bbarc";
+pub const DISABLE_VAR: &str = "BBA_NO_CONFIG";
+
+/// The arguments in a defaults file.
+fn parse(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// The value of `--config FILE` / `--config=FILE` in `args`, if any.
+fn explicit_path(args: &[OsString]) -> Option<PathBuf> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--config" {
+            return iter.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix("--config=") {
+            return Some(PathBuf::from(path));
+        }
+        if arg == "--" {
+            break;
+        }
+    }
+    None
+}
+
+/// The first existing `.bbarc` in `dirs`.
+fn discover(dirs: &[Option<PathBuf>]) -> Option<PathBuf> {
+    dirs.iter()
+        .flatten()
+        .map(|dir| dir.join(FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// `args` with the defaults file's arguments spliced in at `at`, just after
+/// the `run` subcommand, plus the file they came from.
+pub fn args(mut args: Vec<OsString>, at: usize) -> Result<(Vec<OsString>, Option<PathBuf>)> {
+    let path = match explicit_path(&args) {
+        Some(path) => Some(path),
+        None if std::env::var_os(DISABLE_VAR).is_some() => None,
+        None => discover(&[
+            std::env::current_dir().ok(),
+            std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from),
+        ]),
+    };
+    let Some(path) = path else {
+        return Ok((args, None));
+    };
+
+    let defaults = read(&path)?;
+    let rest = args.split_off(at.min(args.len()));
+    args.extend(defaults.into_iter().map(OsString::from));
+    args.extend(rest);
+    Ok((args, Some(path)))
+}
+
+fn read(path: &Path) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read defaults file {:?}", path))?;
+    Ok(parse(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_one_argument_per_line() {
+        let text = "# house defaults\n--ns-conventions=cards/sayc.bbsa\n\n  -v  \n--event=Club night\n";
+        assert_eq!(parse(text), ["--ns-conventions=cards/sayc.bbsa", "-v", "--event=Club night"]);
+    }
+
+    #[test]
+    fn finds_explicit_config() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(explicit_path(&args(&["bba-cli", "--config", "a.rc"])), Some(PathBuf::from("a.rc")));
+        assert_eq!(explicit_path(&args(&["bba-cli", "-i", "x", "--config=b.rc"])), Some(PathBuf::from("b.rc")));
+        assert_eq!(explicit_path(&args(&["bba-cli", "--", "--config", "c.rc"])), None);
+    }
+}
@@ -0,0 +1,56 @@
+//! `--focus-seat`: a compact extract of one seat's calls, for reviewing a
+//! single player's bidding across a deal set. The full auctions still go to
+//! the PBN output; the report is written alongside it.
+
+const SEATS: [&str; 4] = ["N", "E", "S", "W"];
+
+pub fn seat_name(seat: u8) -> &'static str {
+    SEATS[seat as usize % 4]
+}
+
+/// One report line: the board (and run label, if any), the dealer, then the
+/// whole auction with `seat`'s calls in brackets, e.g.
+/// `Board 3  E: 1C [1H] Pass [2NT] Pass 3NT Pass Pass Pass`.
+/// Seats are 0..4 for N, E, S, W.
+pub fn focus_line(board: u32, label: Option<&str>, dealer: u8, calls: &[&str], seat: u8) -> String {
+    let mut line = format!("Board {}", board);
+    if let Some(label) = label {
+        line.push_str(&format!(" ({})", label));
+    }
+    line.push_str(&format!("  {}:", seat_name(dealer)));
+
+    if calls.is_empty() {
+        line.push_str(" (no auction)");
+        return line;
+    }
+    for (i, call) in calls.iter().enumerate() {
+        if (dealer as usize + i) % 4 == seat as usize {
+            line.push_str(&format!(" [{}]", call));
+        } else {
+            line.push_str(&format!(" {}", call));
+        }
+    }
+    if calls.len() <= (seat as usize + 4 - dealer as usize) % 4 {
+        line.push_str(&format!("  ({} made no call)", seat_name(seat)));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn brackets_focus_seat_calls() {
+        let calls = ["1C", "1H", "Pass", "2NT", "Pass", "3NT", "Pass", "Pass", "Pass"];
+        assert_eq!(
+            focus_line(3, None, 1, &calls, 2),
+            "Board 3  E: 1C [1H] Pass 2NT Pass [3NT] Pass Pass Pass"
+        );
+        assert_eq!(
+            focus_line(4, Some("Dealer N"), 0, &["Pass"], 3),
+            "Board 4 (Dealer N)  N: Pass  (W made no call)"
+        );
+        assert_eq!(focus_line(5, None, 0, &[], 0), "Board 5  N: (no auction)");
+    }
+}
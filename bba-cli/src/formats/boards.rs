@@ -0,0 +1,205 @@
+//! Board files from dealing machines: BRI and DUP (binary) and Dealmaster
+//! DLM (text), as BigDeal and Dealmaster Pro write them for club sessions.
+//!
+//! Cards are numbered by suit (S, H, D, C) and rank, ace first: card 1 is
+//! the ace of spades, card 52 the two of clubs. None of these formats holds
+//! the dealer or vulnerability, so each board gets the standard ones for its
+//! number.
+//!
+//! - BRI: a 128-byte record per board, from board 1. It opens with the 13
+//!   card numbers of North, then East, then South, each as two ASCII digits
+//!   (78 bytes); West holds the rest.
+//! - DUP (Duplimate): a 156-byte record per board: the hands as text
+//!   (68 bytes), then the same 78-byte block as BRI, which is what is read.
+//! - DLM: `[Document]` settings and `Board NN=` lines of 26 letters, each
+//!   giving the seats (N, E, S, W = 0..4) of two cards in turn, as `A` plus
+//!   four times the first card's seat plus the second's. A file scrambled
+//!   with a crypto key other than 0 can't be read.
+
+use super::{board_game, Format};
+use log::warn;
+
+const RANKS: &str = "AKQJT98765432";
+
+/// BRI: record size, and where its card numbers start.
+const BRI: (usize, usize) = (128, 0);
+/// DUP: record size, and where its BRI block starts.
+const DUP: (usize, usize) = (156, 68);
+
+/// Each card's seat (N, E, S, W = 0..4), by card number less one.
+type Holders = [u8; 52];
+
+/// A BRI or DUP file as PBN. Boards that don't hold a whole deal are
+/// skipped with a warning.
+pub fn binary_to_pbn(format: Format, bytes: &[u8]) -> String {
+    let (size, offset) = if format == Format::Dup { DUP } else { BRI };
+    let boards: Vec<(u32, Holders)> = bytes
+        .chunks(size)
+        .zip(1..)
+        .filter_map(|(record, number)| {
+            let block = record.get(offset..offset + 78).ok_or_else(|| "record is cut short".to_string());
+            block
+                .and_then(bri_holders)
+                .map_err(|e| warn!("Skipping {} board {}: {}", format.name(), number, e))
+                .ok()
+                .map(|holders| (number, holders))
+        })
+        .collect();
+    write_pbn(format, &boards)
+}
+
+/// A DLM file as PBN. Boards that don't hold a whole deal are skipped with
+/// a warning.
+pub fn dlm_to_pbn(text: &str) -> String {
+    let mut boards = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.eq_ignore_ascii_case("Crypto key") && value.trim() != "0" {
+            warn!("DLM file is scrambled with crypto key {}; its boards can't be read", value.trim());
+            return write_pbn(Format::Dlm, &[]);
+        }
+        let Some(number) = key.strip_prefix("Board ").and_then(|n| n.trim().parse().ok()) else {
+            continue;
+        };
+        match dlm_holders(value.trim()) {
+            Ok(holders) => boards.push((number, holders)),
+            Err(e) => warn!("Skipping DLM board {}: {}", number, e),
+        }
+    }
+    write_pbn(Format::Dlm, &boards)
+}
+
+/// North, East and South's card numbers, two digits each.
+fn bri_holders(block: &[u8]) -> Result<Holders, String> {
+    let mut holders = [3; 52];
+    let mut dealt = [false; 52];
+    for (i, pair) in block.chunks(2).enumerate() {
+        let number = std::str::from_utf8(pair)
+            .ok()
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|n| (1..=52).contains(n))
+            .ok_or_else(|| format!("invalid card number '{}'", String::from_utf8_lossy(pair)))?;
+        if std::mem::replace(&mut dealt[number - 1], true) {
+            return Err(format!("card {} is dealt twice", number));
+        }
+        holders[number - 1] = (i / 13) as u8;
+    }
+    Ok(holders)
+}
+
+/// 26 letters, each the seats of two cards.
+fn dlm_holders(code: &str) -> Result<Holders, String> {
+    if code.len() != 26 {
+        return Err(format!("expected 26 letters, got {}", code.len()));
+    }
+    let mut holders = [0; 52];
+    for (i, c) in code.bytes().enumerate() {
+        let value = c
+            .checked_sub(b'A')
+            .filter(|v| *v < 16)
+            .ok_or_else(|| format!("invalid letter '{}'", c as char))?;
+        holders[2 * i] = value / 4;
+        holders[2 * i + 1] = value % 4;
+    }
+    for seat in 0..4 {
+        let cards = holders.iter().filter(|&&h| h == seat).count();
+        if cards != 13 {
+            return Err(format!("{} holds {} cards, expected 13", ["North", "East", "South", "West"][seat as usize], cards));
+        }
+    }
+    Ok(holders)
+}
+
+/// The PBN Deal value, from North.
+fn deal(holders: &Holders) -> String {
+    let hands: Vec<String> = (0..4)
+        .map(|seat| {
+            (0..4)
+                .map(|suit| {
+                    RANKS
+                        .chars()
+                        .enumerate()
+                        .filter(|(rank, _)| holders[suit * 13 + rank] == seat)
+                        .map(|(_, c)| c)
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join(".")
+        })
+        .collect();
+    format!("N:{}", hands.join(" "))
+}
+
+fn write_pbn(format: Format, boards: &[(u32, Holders)]) -> String {
+    let mut out = format!("% PBN 2.1\n% Converted by bba-cli from {} boards\n", format.name());
+    for (number, holders) in boards {
+        out.push_str(&board_game(*number, &deal(holders)));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbn::split_games;
+
+    const DEAL: &str = "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63";
+
+    fn holders() -> Holders {
+        let mut holders = [0; 52];
+        for (seat, hand) in DEAL[2..].split(' ').enumerate() {
+            for (suit, cards) in hand.split('.').enumerate() {
+                for c in cards.chars() {
+                    holders[suit * 13 + RANKS.find(c).unwrap()] = seat as u8;
+                }
+            }
+        }
+        holders
+    }
+
+    /// North, East and South's card numbers, as BRI writes them.
+    fn bri_block() -> String {
+        (0..3)
+            .flat_map(|seat| (1..=52).filter(move |&n| holders()[n - 1] == seat))
+            .map(|n| format!("{:02}", n))
+            .collect()
+    }
+
+    #[test]
+    fn reads_bri_and_dup_records() {
+        let bri = format!("{}{}", bri_block(), " ".repeat(50));
+        let dup = format!("{}{}{}", "x".repeat(68), bri_block(), "0".repeat(10));
+        for (format, record) in [(Format::Bri, bri), (Format::Dup, dup)] {
+            let mut bytes = record.clone().into_bytes();
+            bytes.extend(record.replacen("01", "ZZ", 1).bytes());
+            bytes.extend(record.bytes());
+            let games = split_games(&binary_to_pbn(format, &bytes));
+            assert_eq!(games.len(), 2, "{}", format.name());
+            assert_eq!(games[0].tag("Deal"), Some(DEAL));
+            assert_eq!(games[1].tag("Board"), Some("3"));
+            assert_eq!(games[1].tag("Dealer"), Some("S"));
+            assert_eq!(games[1].tag("Vulnerable"), Some("EW"));
+        }
+        assert_eq!(bri_holders(b"99").unwrap_err(), "invalid card number '99'");
+    }
+
+    #[test]
+    fn reads_dlm_boards() {
+        let holders = holders();
+        let code: String = holders.chunks(2).map(|pair| (b'A' + 4 * pair[0] + pair[1]) as char).collect();
+        let text = format!(
+            "[Document]\r\nHeadline=Club\r\nCrypto key=0\r\n[Boards]\r\nBoard 07={}\r\nBoard 08=AAAA\r\n",
+            code
+        );
+        let games = split_games(&dlm_to_pbn(&text));
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].tag("Board"), Some("7"));
+        assert_eq!(games[0].tag("Deal"), Some(DEAL));
+        assert_eq!(dlm_holders("AAAAAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(), "North holds 52 cards, expected 13");
+
+        let scrambled = text.replace("Crypto key=0", "Crypto key=17");
+        assert!(split_games(&dlm_to_pbn(&scrambled)).is_empty());
+    }
+}
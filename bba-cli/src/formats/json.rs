@@ -0,0 +1,107 @@
+//! Deals as JSON: an array of objects, for programs that already produce
+//! JSON.
+//!
+//! Each object needs a `deal` (a PBN Deal value, such as
+//! `"N:AQ753.7.A86.A854 ..."`); `dealer`, `vulnerability` and `board` are
+//! optional and fill the PBN tags of the same meaning, as the columns of
+//! `--input-sqlite` do. `board` may be a number or a string. Other fields
+//! are ignored.
+//!
+//! ```json
+//! [{"board": 1, "dealer": "N", "vulnerability": "None", "deal": "N:..."}]
+//! ```
+
+use crate::pbn::check_deal;
+use log::warn;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Deserialize)]
+struct JsonDeal {
+    deal: String,
+    dealer: Option<String>,
+    vulnerability: Option<String>,
+    board: Option<Value>,
+}
+
+impl JsonDeal {
+    fn board(&self) -> Option<String> {
+        match &self.board {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Number(n)) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    fn to_pbn(&self) -> String {
+        let mut game = String::from("\n");
+        let tags = [
+            ("Board", self.board()),
+            ("Dealer", self.dealer.clone()),
+            ("Vulnerable", self.vulnerability.clone()),
+            ("Deal", Some(self.deal.trim().to_string())),
+        ];
+        for (name, value) in tags {
+            if let Some(value) = value {
+                game.push_str(&format!("[{} \"{}\"]\n", name, value.replace('"', "")));
+            }
+        }
+        game
+    }
+}
+
+/// A JSON array of deals as a PBN file. Entries without a whole deal are
+/// skipped with a warning, and so is the whole file if it isn't an array.
+pub fn to_pbn(text: &str) -> String {
+    let mut out = String::from("% PBN 2.1\n% Converted by bba-cli from JSON deals\n");
+    let entries: Vec<Value> = match serde_json::from_str(text) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Can't read JSON deals: {} (expected an array of {{\"deal\": ...}} objects)", e);
+            return out;
+        }
+    };
+    for (i, entry) in entries.into_iter().enumerate() {
+        let deal = serde_json::from_value::<JsonDeal>(entry)
+            .map_err(|e| e.to_string())
+            .and_then(|d| check_deal(d.deal.trim()).map(|_| d));
+        match deal {
+            Ok(deal) => out.push_str(&deal.to_pbn()),
+            Err(e) => warn!("Skipping JSON deal {}: {}", i + 1, e),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbn::split_games;
+
+    const DEAL: &str = "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63";
+
+    #[test]
+    fn reads_an_array_of_deals() {
+        let text = format!(
+            r#"[
+  {{"board": 5, "dealer": "N", "vulnerability": "NS", "deal": "{DEAL}", "source": "sim"}},
+  {{"deal": "{DEAL}"}},
+  {{"board": "6a", "deal": "N:AQ753.7.A86.A854"}},
+  {{"dealer": "E"}}
+]"#
+        );
+        let games = split_games(&to_pbn(&text));
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("Board"), Some("5"));
+        assert_eq!(games[0].tag("Dealer"), Some("N"));
+        assert_eq!(games[0].tag("Vulnerable"), Some("NS"));
+        assert_eq!(games[0].tag("Deal"), Some(DEAL));
+        assert_eq!(games[1].tag("Board"), None);
+        assert_eq!(games[1].tag("Deal"), Some(DEAL));
+    }
+
+    #[test]
+    fn skips_a_file_that_is_not_an_array() {
+        assert!(split_games(&to_pbn(r#"{"deal": "N:..."}"#)).is_empty());
+    }
+}
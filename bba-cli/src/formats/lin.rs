@@ -0,0 +1,405 @@
+//! BBO hand records (`.lin`), as Bridge Base Online saves played and
+//! vugraph boards.
+//!
+//! A LIN file is a flat run of `key|value|` pairs: `md` starts a board with
+//! its dealer and hands, `sv` gives the vulnerability, `ah` the board title
+//! ("Board 12"), `qx` the room and board ("o12"), `pn` the players and `mb`
+//! each call, with `!` and an `an` explanation for an alert. Play (`pc`),
+//! chat and the rest are ignored.
+//!
+//! `--output-format lin` writes the same keys back, one board per line, so
+//! generated auctions can be replayed in BBO's hand viewer.
+
+use crate::pbn::{deal_from_north, normalize_call, RawGame};
+use log::warn;
+
+const RANKS: &str = "AKQJT98765432";
+
+/// One board of a LIN file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LinBoard {
+    pub number: u32,
+    /// Player names in N, E, S, W order (empty when unknown).
+    pub players: [String; 4],
+    /// Dealer seat letter.
+    pub dealer: char,
+    /// PBN Vulnerable value.
+    pub vulnerable: &'static str,
+    /// PBN Deal value, from North.
+    pub deal: String,
+    /// Calls of the table auction, in EPBot's spelling.
+    pub calls: Vec<String>,
+    /// Alerted calls, by index into `calls`, with their explanations
+    /// (empty when there is none).
+    pub alerts: Vec<(usize, String)>,
+}
+
+impl LinBoard {
+    /// A PBN game as a board, from its Board, Dealer, Vulnerable, Deal and
+    /// player tags and its auction. None without a usable Deal tag.
+    pub fn from_game(game: &RawGame) -> Option<LinBoard> {
+        let deal = deal_from_north(game.tag("Deal")?)?;
+        let tag = |name: &str| game.tag(name).unwrap_or_default().to_string();
+        let vulnerable = game.tag("Vulnerable").unwrap_or_default().to_ascii_uppercase().replace(['/', ' ', '-'], "");
+        Some(LinBoard {
+            number: game.tag("Board").and_then(|b| b.trim().parse().ok()).unwrap_or(1),
+            players: [tag("North"), tag("East"), tag("South"), tag("West")],
+            dealer: game.tag("Dealer").and_then(|d| d.trim().chars().next()).unwrap_or('N').to_ascii_uppercase(),
+            vulnerable: match vulnerable.as_str() {
+                "NS" => "NS",
+                "EW" => "EW",
+                "ALL" | "BOTH" | "GAMEALL" => "All",
+                _ => "None",
+            },
+            deal,
+            calls: game.auction_calls(),
+            alerts: Vec::new(),
+        })
+    }
+
+    /// The board as one line of a LIN file (without its line ending).
+    pub fn to_lin(&self) -> String {
+        // Hands in S, W, N, E order, from the Deal's N, E, S, W.
+        let hands: Vec<&str> = self.deal.get(2..).unwrap_or_default().split_whitespace().collect();
+        let lin_hand = |seat: usize| {
+            let suits = hands.get(seat).copied().unwrap_or_default().split('.');
+            "SHDC".chars().zip(suits).map(|(suit, cards)| format!("{}{}", suit, cards)).collect::<String>()
+        };
+        let dealer = match self.dealer {
+            'S' => 1,
+            'W' => 2,
+            'E' => 4,
+            _ => 3,
+        };
+        let clean = |text: &str| text.replace('|', "/");
+        let names = [2, 3, 0, 1].map(|seat| clean(&self.players[seat])).join(",");
+        let mut line = format!(
+            "pn|{}|st||md|{}{}|rh||ah|Board {}|sv|{}|",
+            names,
+            dealer,
+            [2, 3, 0, 1].map(lin_hand).join(","),
+            self.number,
+            match self.vulnerable {
+                "NS" => 'n',
+                "EW" => 'e',
+                "All" => 'b',
+                _ => 'o',
+            }
+        );
+        for (i, call) in self.calls.iter().enumerate() {
+            let call = match call.as_str() {
+                "Pass" => "p".to_string(),
+                "X" => "d".to_string(),
+                "XX" => "r".to_string(),
+                bid => bid.trim_end_matches('T').to_string(),
+            };
+            match self.alerts.iter().find(|(index, _)| *index == i) {
+                Some((_, text)) if !text.is_empty() => line.push_str(&format!("mb|{}!|an|{}|", call, clean(text))),
+                Some(_) => line.push_str(&format!("mb|{}!|", call)),
+                None => line.push_str(&format!("mb|{}|", call)),
+            }
+        }
+        line.push_str("pg||");
+        line
+    }
+}
+
+/// A board being read: what its keys have set so far.
+#[derive(Default)]
+struct Pending {
+    number: Option<u32>,
+    players: [String; 4],
+    dealer_and_deal: Option<(char, String)>,
+    /// An `md` was seen, even one that didn't parse.
+    has_md: bool,
+    vulnerable: Option<&'static str>,
+    calls: Vec<String>,
+    alerts: Vec<(usize, String)>,
+}
+
+impl Pending {
+    fn new(players: &[String; 4]) -> Self {
+        Pending { players: players.clone(), ..Default::default() }
+    }
+
+    /// The finished board, numbered `fallback` if the file didn't number it.
+    /// None when it has no usable deal.
+    fn finish(self, fallback: u32) -> Option<LinBoard> {
+        let (dealer, deal) = self.dealer_and_deal?;
+        Some(LinBoard {
+            number: self.number.unwrap_or(fallback),
+            players: self.players,
+            dealer,
+            vulnerable: self.vulnerable.unwrap_or("None"),
+            deal,
+            calls: self.calls,
+            alerts: self.alerts,
+        })
+    }
+}
+
+/// The boards of a LIN file. Boards whose hands don't parse are skipped
+/// with a warning.
+pub fn parse(text: &str) -> Vec<LinBoard> {
+    // Records may be wrapped anywhere between pairs; the line breaks carry
+    // no meaning.
+    let joined: String = text.lines().map(str::trim).collect();
+    let mut fields = joined.split('|');
+    let mut boards = Vec::new();
+    let mut players: [String; 4] = Default::default();
+    let mut pending: Option<Pending> = None;
+
+    while let Some(key) = fields.next() {
+        let value = fields.next().unwrap_or("").trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "pn" => {
+                players = parse_players(value);
+                // Before its hands, `pn` names the board's players; after
+                // them, it names the next board's.
+                if let Some(board) = pending.as_mut().filter(|b| !b.has_md) {
+                    board.players = players.clone();
+                }
+            }
+            "qx" => {
+                finish(pending.take(), &mut boards);
+                let mut board = Pending::new(&players);
+                board.number = digits(value);
+                pending = Some(board);
+            }
+            "md" => {
+                if pending.as_ref().is_some_and(|b| b.has_md) {
+                    finish(pending.take(), &mut boards);
+                }
+                let board = pending.get_or_insert_with(|| Pending::new(&players));
+                board.has_md = true;
+                match parse_md(value) {
+                    Ok(parsed) => board.dealer_and_deal = Some(parsed),
+                    Err(e) => warn!("Skipping LIN board with hands '{}': {}", value, e),
+                }
+            }
+            "ah" => {
+                let board = pending.get_or_insert_with(|| Pending::new(&players));
+                board.number = digits(value).or(board.number);
+            }
+            "sv" => {
+                let board = pending.get_or_insert_with(|| Pending::new(&players));
+                board.vulnerable = Some(match value.to_ascii_lowercase().as_str() {
+                    "n" => "NS",
+                    "e" => "EW",
+                    "b" => "All",
+                    _ => "None",
+                });
+            }
+            "mb" => {
+                let board = pending.get_or_insert_with(|| Pending::new(&players));
+                board.calls.extend(normalize_call(value));
+                if value.ends_with('!') && !board.calls.is_empty() {
+                    board.alerts.push((board.calls.len() - 1, String::new()));
+                }
+            }
+            "an" => {
+                // Explains the call just made, alerting it if `!` didn't.
+                if let Some(board) = pending.as_mut().filter(|b| !b.calls.is_empty()) {
+                    let call = board.calls.len() - 1;
+                    match board.alerts.last_mut().filter(|(index, _)| *index == call) {
+                        Some((_, text)) => *text = value.to_string(),
+                        None => board.alerts.push((call, value.to_string())),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    finish(pending, &mut boards);
+    boards
+}
+
+/// Add the board being read to `boards`, if it has a deal.
+fn finish(pending: Option<Pending>, boards: &mut Vec<LinBoard>) {
+    if let Some(board) = pending.and_then(|p| p.finish(boards.len() as u32 + 1)) {
+        boards.push(board);
+    }
+}
+
+/// The boards as a PBN file, with the table auction of each (if any) in
+/// its `[Auction]` section.
+pub fn to_pbn(boards: &[LinBoard]) -> String {
+    let mut out = String::from("% PBN 2.1\n% Converted by bba-cli from BBO LIN\n");
+    for board in boards {
+        out.push_str(&format!("\n[Board \"{}\"]\n", board.number));
+        for (seat, name) in [("West", 3), ("North", 0), ("East", 1), ("South", 2)] {
+            let player = &board.players[name];
+            if !player.is_empty() {
+                out.push_str(&format!("[{} \"{}\"]\n", seat, player.replace('"', "'")));
+            }
+        }
+        out.push_str(&format!(
+            "[Dealer \"{}\"]\n[Vulnerable \"{}\"]\n[Deal \"{}\"]\n",
+            board.dealer, board.vulnerable, board.deal
+        ));
+        if !board.calls.is_empty() {
+            out.push_str(&format!("[Auction \"{}\"]\n", board.dealer));
+            for line in board.calls.chunks(4) {
+                out.push_str(&line.join(" "));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// The first run of digits in `text`, e.g. 12 in "Board 12" or "o12".
+fn digits(text: &str) -> Option<u32> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let run = &text[start..];
+    run[..run.find(|c: char| !c.is_ascii_digit()).unwrap_or(run.len())].parse().ok()
+}
+
+/// `pn|South,West,North,East|` (vugraph files list both rooms; the first
+/// four are used), as names in N, E, S, W order.
+fn parse_players(value: &str) -> [String; 4] {
+    let names: Vec<&str> = value.split(',').map(str::trim).collect();
+    let name = |i: usize| names.get(i).copied().unwrap_or("").to_string();
+    [name(2), name(3), name(0), name(1)]
+}
+
+/// `md|3SAK5HQJ2D987CT32,S...,S...,|`: the dealer digit (1 = South, 2 =
+/// West, 3 = North, 4 = East) and the hands of South, West, North and East.
+/// A missing last hand is the cards the other three don't hold. Returns the
+/// dealer letter and the PBN Deal value from North.
+fn parse_md(value: &str) -> Result<(char, String), String> {
+    let mut chars = value.chars();
+    let dealer = match chars.next() {
+        Some('1') => 'S',
+        Some('2') => 'W',
+        Some('3') => 'N',
+        Some('4') => 'E',
+        other => return Err(format!("bad dealer '{}'", other.map(String::from).unwrap_or_default())),
+    };
+    let mut hands: Vec<[Vec<u8>; 4]> = chars
+        .as_str()
+        .split(',')
+        .map(parse_hand)
+        .collect::<Result<_, _>>()?;
+    hands.truncate(4);
+    let count = |hand: &[Vec<u8>; 4]| hand.iter().map(Vec::len).sum::<usize>();
+    if hands.len() == 3 || hands.get(3).is_some_and(|h| count(h) == 0) {
+        let mut rest: [Vec<u8>; 4] = Default::default();
+        for (suit, cards) in rest.iter_mut().enumerate() {
+            *cards = (0..13).filter(|rank| !hands[..3].iter().any(|h| h[suit].contains(rank))).collect();
+        }
+        hands.resize(3, Default::default());
+        hands.push(rest);
+    }
+    if hands.len() != 4 {
+        return Err(format!("expected 4 hands, got {}", hands.len()));
+    }
+    if let Some(hand) = hands.iter().find(|h| count(h) != 13) {
+        return Err(format!("a hand has {} cards, expected 13", count(hand)));
+    }
+    let pbn = |hand: &[Vec<u8>; 4]| {
+        hand.iter()
+            .map(|ranks| ranks.iter().map(|&r| RANKS.as_bytes()[r as usize] as char).collect::<String>())
+            .collect::<Vec<_>>()
+            .join(".")
+    };
+    // LIN lists S, W, N, E; PBN from North is N, E, S, W.
+    let deal = [2, 3, 0, 1].map(|i| pbn(&hands[i])).join(" ");
+    Ok((dealer, format!("N:{}", deal)))
+}
+
+/// One LIN hand, `SAK5HQJ2D987CT32`, as the ranks (0 = ace) held in each of
+/// S, H, D, C, highest first.
+fn parse_hand(text: &str) -> Result<[Vec<u8>; 4], String> {
+    let mut suits: [Vec<u8>; 4] = Default::default();
+    let mut suit = None;
+    for c in text.replace("10", "T").chars().map(|c| c.to_ascii_uppercase()) {
+        if let Some(s) = "SHDC".find(c) {
+            suit = Some(s);
+        } else if let Some(rank) = RANKS.find(c) {
+            let s = suit.ok_or_else(|| format!("card '{}' before any suit", c))?;
+            suits[s].push(rank as u8);
+        } else if !c.is_whitespace() {
+            return Err(format!("invalid card '{}'", c));
+        }
+    }
+    for ranks in &mut suits {
+        ranks.sort_unstable();
+        if ranks.windows(2).any(|w| w[0] == w[1]) {
+            return Err("a card appears twice".to_string());
+        }
+    }
+    Ok(suits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIN: &str = "pn|Sam,Wes,Nora,Ed|st||md|3S8HAQ65DKJ93CK972,SKJ62HJT98DT54C63,SAQ753H7DA86CA854,|rh||ah|Board 5|sv|n|\n\
+mb|p|mb|1S|mb|p|mb|2N!|an|GF raise|mb|p|mb|4S|mb|p|mb|p|mb|p|pc|HA|pg||\n\
+qx|o6|md|1SAKQJT98765432,HAKQJT98765432,DAKQJT98765432,|sv|b|mb|7S|mb|d|mb|r|pg||\n";
+
+    #[test]
+    fn reads_boards() {
+        let boards = parse(LIN);
+        assert_eq!(boards.len(), 2);
+        let first = &boards[0];
+        assert_eq!(first.number, 5);
+        assert_eq!(first.players, ["Nora", "Ed", "Sam", "Wes"]);
+        assert_eq!(first.dealer, 'N');
+        assert_eq!(first.vulnerable, "NS");
+        assert_eq!(first.deal, "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63");
+        assert_eq!(first.calls, ["Pass", "1S", "Pass", "2NT", "Pass", "4S", "Pass", "Pass", "Pass"]);
+        assert_eq!(first.alerts, [(3, "GF raise".to_string())]);
+
+        let second = &boards[1];
+        assert_eq!(second.number, 6);
+        assert_eq!(second.dealer, 'S');
+        assert_eq!(second.vulnerable, "All");
+        assert_eq!(second.deal, "N:..AKQJT98765432. ...AKQJT98765432 AKQJT98765432... .AKQJT98765432..");
+        assert_eq!(second.calls, ["7S", "X", "XX"]);
+    }
+
+    #[test]
+    fn writes_pbn() {
+        let text = to_pbn(&parse(LIN));
+        let games = crate::pbn::split_games(&text);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("North"), Some("Nora"));
+        assert_eq!(games[0].tag("Dealer"), Some("N"));
+        assert_eq!(crate::pbn::check_deal(games[0].tag("Deal").unwrap()), Ok(()));
+        assert_eq!(games[0].auction_calls().len(), 9);
+        assert_eq!(games[1].tag("Board"), Some("6"));
+        assert!(text.contains("[Auction \"S\"]\n7S X XX\n"), "{text}");
+    }
+
+    #[test]
+    fn writes_lin_that_reads_back() {
+        let boards = parse(LIN);
+        for board in &boards {
+            let line = board.to_lin();
+            assert_eq!(parse(&line), std::slice::from_ref(board), "{}", line);
+        }
+        assert_eq!(
+            boards[0].to_lin(),
+            "pn|Sam,Wes,Nora,Ed|st||md|3S8HAQ65DKJ93CK972,SKJ62HJT98DT54C63,SAQ753H7DA86CA854,ST94HK432DQ72CQJT|\
+rh||ah|Board 5|sv|n|mb|p|mb|1S|mb|p|mb|2N!|an|GF raise|mb|p|mb|4S|mb|p|mb|p|mb|p|pg||"
+        );
+
+        let game = crate::pbn::split_games(&to_pbn(&boards)).remove(1);
+        let copied = LinBoard::from_game(&game).unwrap();
+        assert_eq!(copied, LinBoard { alerts: Vec::new(), ..boards[1].clone() });
+    }
+
+    #[test]
+    fn skips_bad_hands_and_numbers_the_rest() {
+        let boards = parse("md|3SAKQ,SJ,SX,|mb|1S|md|2S23456789TJQKAH,H23456789TJQKA,D23456789TJQKA,|");
+        assert_eq!(boards.len(), 1);
+        assert_eq!(boards[0].number, 1);
+        assert_eq!(boards[0].dealer, 'W');
+        assert!(boards[0].calls.is_empty());
+        assert_eq!(parse_md("5S,S,S,"), Err("bad dealer '5'".to_string()));
+        assert_eq!(parse_md("1SAK,S,S,").unwrap_err(), "a hand has 2 cards, expected 13");
+    }
+}
@@ -0,0 +1,103 @@
+//! One deal per line: GIB deal lines and dot-separated deals.
+//!
+//! Both give a deal as four `S.H.D.C` hands separated by spaces. A
+//! dot-separated line starts from North (a PBN Deal value without its `N:`);
+//! a GIB line starts from West, and may end in `:` and GIB's double-dummy
+//! tricks, which are ignored. Neither records the board, so deals read from
+//! them are numbered from 1 with the standard dealer and vulnerability.
+
+use super::{board_game, Format};
+use crate::pbn::{check_deal, deal_from_north, RawGame};
+use log::warn;
+
+/// The seat of a line's first hand (N, E, S, W = 0..4).
+fn first_seat(format: Format) -> usize {
+    match format {
+        Format::Gib => 3,
+        _ => 0,
+    }
+}
+
+/// The deal lines of `text` as a PBN file. Blank lines and `#` or `%`
+/// comments are skipped, and so, with a warning, are lines that aren't a
+/// whole deal.
+pub fn to_pbn(format: Format, text: &str) -> String {
+    let mut out = format!("% PBN 2.1\n% Converted by bba-cli from {} deals\n", format.name());
+    let mut number = 0;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', '%']) {
+            continue;
+        }
+        let hands: Vec<&str> = line.split(':').next().unwrap_or_default().split_whitespace().collect();
+        if hands.len() != 4 {
+            warn!("Skipping {} deal '{}': expected 4 hands, got {}", format.name(), line, hands.len());
+            continue;
+        }
+        let first = first_seat(format);
+        let deal = format!("N:{}", (0..4).map(|seat| hands[(seat + 4 - first) % 4]).collect::<Vec<_>>().join(" "));
+        if let Err(e) = check_deal(&deal) {
+            warn!("Skipping {} deal '{}': {}", format.name(), line, e);
+            continue;
+        }
+        number += 1;
+        out.push_str(&board_game(number, &deal));
+    }
+    out
+}
+
+/// The deals of `games` as lines, and how many there are. Games without a
+/// usable `[Deal]` are left out.
+pub fn from_games(format: Format, games: &[RawGame]) -> (String, usize) {
+    let mut out = String::new();
+    let mut count = 0;
+    for deal in games.iter().filter_map(|g| deal_from_north(g.tag("Deal")?)) {
+        let hands: Vec<&str> = deal[2..].split_whitespace().collect();
+        let first = first_seat(format);
+        out.push_str(&(0..4).map(|i| hands[(first + i) % 4]).collect::<Vec<_>>().join(" "));
+        out.push('\n');
+        count += 1;
+    }
+    (out, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbn::split_games;
+
+    const DEAL: &str = "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63";
+
+    #[test]
+    fn reads_lines_from_their_first_seat() {
+        let gib = "KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972:88887777666655554444\n";
+        let dot = "# comment\n\nAQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63\n";
+        for (format, text) in [(Format::Gib, gib), (Format::Dot, dot)] {
+            let games = split_games(&to_pbn(format, text));
+            assert_eq!(games.len(), 1);
+            assert_eq!(games[0].tag("Deal"), Some(DEAL));
+            assert_eq!(games[0].tag("Board"), Some("1"));
+            assert_eq!(games[0].tag("Dealer"), Some("N"));
+            assert_eq!(games[0].tag("Vulnerable"), Some("None"));
+        }
+    }
+
+    #[test]
+    fn skips_partial_deals() {
+        let text = "AQ753.7.A86.A854 T94.K432.Q72.QJT\nAQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63\n";
+        let games = split_games(&to_pbn(Format::Dot, text));
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].tag("Board"), Some("1"));
+    }
+
+    #[test]
+    fn writes_lines_that_read_back() {
+        let text = format!("[Board \"2\"]\n[Deal \"E:{}\"]\n\n[Event \"no deal\"]\n", "T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854");
+        let games = split_games(&text);
+        for format in [Format::Gib, Format::Dot] {
+            let (lines, count) = from_games(format, &games);
+            assert_eq!(count, 1);
+            assert_eq!(split_games(&to_pbn(format, &lines))[0].tag("Deal"), Some(DEAL));
+        }
+        assert_eq!(from_games(Format::Gib, &games).0, "KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972\n");
+    }
+}
@@ -0,0 +1,133 @@
+//! Deal formats besides PBN.
+//!
+//! An input in another format is converted to PBN text as it is read (see
+//! `InputFile::read`), so every pass over the input sees ordinary PBN games.
+//! `bba-cli convert` writes them back out.
+
+pub mod boards;
+pub mod json;
+pub mod lin;
+pub mod lines;
+
+use crate::pbn::{board_dealer_tag, board_vulnerable_tag, RawGame};
+use std::path::Path;
+
+/// A deal file format, named by its usual file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Pbn,
+    /// BBO hand records (see `lin`).
+    Lin,
+    /// GIB deal lines, West first (see `lines`).
+    Gib,
+    /// Dot-separated deal lines, North first (see `lines`).
+    Dot,
+    /// BigDeal board files (see `boards`); read only, like `Dup` and `Dlm`.
+    Bri,
+    /// Duplimate board files.
+    Dup,
+    /// Dealmaster Pro board files.
+    Dlm,
+    /// An array of deals as JSON objects (see `json`); read only.
+    Json,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Pbn => "PBN",
+            Format::Lin => "LIN",
+            Format::Gib => "GIB",
+            Format::Dot => "dot",
+            Format::Bri => "BRI",
+            Format::Dup => "DUP",
+            Format::Dlm => "DLM",
+            Format::Json => "JSON",
+        }
+    }
+
+    /// Whether the format is binary, and so converted before decoding.
+    pub fn is_binary(self) -> bool {
+        matches!(self, Format::Bri | Format::Dup)
+    }
+
+    /// Whether `bba-cli convert` can write the format.
+    pub fn is_writable(self) -> bool {
+        matches!(self, Format::Pbn | Format::Lin | Format::Gib | Format::Dot)
+    }
+
+    /// A format name (its extension), in any case.
+    pub fn parse(name: &str) -> Option<Format> {
+        match name.to_ascii_lowercase().as_str() {
+            "pbn" => Some(Format::Pbn),
+            "lin" => Some(Format::Lin),
+            "gib" => Some(Format::Gib),
+            "dot" => Some(Format::Dot),
+            "bri" => Some(Format::Bri),
+            "dup" => Some(Format::Dup),
+            "dlm" => Some(Format::Dlm),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// The format named by `path`'s extension, if any.
+    pub fn from_extension(path: &Path) -> Option<Format> {
+        path.extension().and_then(|e| e.to_str()).and_then(Format::parse)
+    }
+
+    /// An input's format: by its extension, or a LIN file (on stdin, say)
+    /// by opening with a `key|` pair, which no PBN file does. PBN otherwise.
+    pub fn of_input(path: &Path, text: &str) -> Format {
+        let start = text.trim_start().as_bytes();
+        let lin = start.len() >= 3 && start[..2].iter().all(u8::is_ascii_lowercase) && start[2] == b'|';
+        Format::from_extension(path).unwrap_or(if lin { Format::Lin } else { Format::Pbn })
+    }
+}
+
+/// `text` in a text `format` as a PBN file.
+pub fn to_pbn(format: Format, text: &str) -> String {
+    match format {
+        Format::Pbn => text.to_string(),
+        Format::Lin => lin::to_pbn(&lin::parse(text)),
+        Format::Gib | Format::Dot => lines::to_pbn(format, text),
+        Format::Dlm => boards::dlm_to_pbn(text),
+        Format::Json => json::to_pbn(text),
+        Format::Bri | Format::Dup => boards::binary_to_pbn(format, text.as_bytes()),
+    }
+}
+
+/// A game for board `number` with the standard dealer and vulnerability,
+/// for formats that don't record them.
+fn board_game(number: u32, deal: &str) -> String {
+    format!(
+        "\n[Board \"{}\"]\n[Dealer \"{}\"]\n[Vulnerable \"{}\"]\n[Deal \"{}\"]\n",
+        number,
+        board_dealer_tag(number),
+        board_vulnerable_tag(number),
+        deal
+    )
+}
+
+/// PBN `games` in a writable `format`, and how many deals were written.
+/// Games without a usable `[Deal]` are left out of the LIN, GIB and dot
+/// formats.
+pub fn from_games(format: Format, games: &[RawGame]) -> (String, usize) {
+    match format {
+        Format::Pbn => {
+            let mut out = String::from("% PBN 2.1\n");
+            for game in games {
+                out.push('\n');
+                out.push_str(game.text.trim_end());
+                out.push('\n');
+            }
+            (out, games.iter().filter(|g| g.tag("Deal").is_some()).count())
+        }
+        Format::Lin => {
+            let boards: Vec<String> = games.iter().filter_map(lin::LinBoard::from_game).map(|b| b.to_lin()).collect();
+            (boards.iter().map(|b| format!("{}\n", b)).collect(), boards.len())
+        }
+        Format::Gib | Format::Dot => lines::from_games(format, games),
+        Format::Bri | Format::Dup | Format::Dlm | Format::Json => unreachable!("{} files are only read", format.name()),
+    }
+}
@@ -4,13 +4,40 @@
 //! Cross-platform: macOS, Linux, and Windows.
 
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 use log::{debug, error, info};
-use std::path::PathBuf;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 
 mod batch;
+mod bench;
+mod builtin_systems;
+mod compare;
+mod convention_check;
+mod convention_list;
+mod convert;
+mod csv_export;
+mod deal_expr;
+mod deal_gen;
+mod deal_stats;
+mod defaults_file;
+mod focus;
+mod formats;
+mod parallel;
+mod pbn;
+mod play;
+mod repl;
+mod resource;
+#[cfg(feature = "sqlite")]
+mod sqlite_input;
+mod strip;
+mod system_pack;
+mod systems;
+mod validate;
+mod watch;
 
 use batch::{process_pbn_file, OutputConfig};
+use system_pack::SystemPack;
 
 /// Bridge Bidding Analyzer CLI
 ///
@@ -18,22 +45,154 @@ use batch::{process_pbn_file, OutputConfig};
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[command(propagate_version = true)]
-struct Args {
-    /// Input PBN file containing deals to analyze
-    #[arg(short, long, value_name = "FILE")]
-    input: PathBuf,
+#[command(arg_required_else_help = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
 
-    /// Output PBN file for results with generated auctions
-    #[arg(short, long, value_name = "FILE")]
-    output: PathBuf,
+    /// Read `run`'s default arguments from FILE instead of the discovered
+    /// .bbarc (./.bbarc, then ~/.bbarc). Arguments on the command line
+    /// override the file's. Set BBA_NO_CONFIG to skip discovery
+    #[arg(long, value_name = "FILE", global = true)]
+    config: Option<PathBuf>,
 
-    /// Convention file (.bbsa) for North-South partnership
+    /// Enable verbose logging (use -vv for debug output)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+}
+
+/// `run`: bid the deals of PBN files into output files.
+#[derive(clap::Args, Debug)]
+struct RunArgs {
+    /// Input PBN file containing deals to analyze. Repeat to bid several
+    /// files in one run, with conventions loaded once
+    #[cfg_attr(not(feature = "sqlite"), arg(required = true))]
+    #[cfg_attr(
+        feature = "sqlite",
+        arg(required_unless_present = "input_sqlite", conflicts_with = "input_sqlite")
+    )]
+    #[arg(short, long, value_name = "FILE", action = clap::ArgAction::Append)]
+    input: Vec<PathBuf>,
+
+    /// Bid the deals --query selects from this SQLite database instead of
+    /// reading --input. The query must return a `pbn` column, and may return
+    /// `dealer`, `vulnerability` and `board`
+    #[cfg(feature = "sqlite")]
+    #[arg(long = "input-sqlite", value_name = "DB", requires = "query", conflicts_with_all = ["systems", "compare"])]
+    input_sqlite: Option<PathBuf>,
+
+    /// SQL query selecting the deals for --input-sqlite
+    #[cfg(feature = "sqlite")]
+    #[arg(long, value_name = "SQL", requires = "input_sqlite")]
+    query: Option<String>,
+
+    /// With --input-sqlite, append the auctions to this table of the same
+    /// database (created if missing) instead of writing --output
+    #[cfg(feature = "sqlite")]
+    #[arg(long = "results-table", value_name = "TABLE", requires = "input_sqlite", value_parser = parse_tag_name)]
+    results_table: Option<String>,
+
+    /// Output PBN file for results with generated auctions. With several
+    /// inputs, repeat once per --input in the same order. With
+    /// --input-sqlite, a name ending in .ndjson writes one JSON record per
+    /// auction instead
+    #[cfg_attr(not(feature = "sqlite"), arg(required_unless_present_any = ["output_dir", "systems", "compare"]))]
+    #[cfg_attr(feature = "sqlite", arg(required_unless_present_any = ["output_dir", "systems", "compare", "results_table"]))]
+    #[arg(
+        short,
+        long,
+        value_name = "FILE",
+        conflicts_with = "output_dir",
+        action = clap::ArgAction::Append
+    )]
+    output: Vec<PathBuf>,
+
+    /// Write each output into DIR under its input's file name, instead of
+    /// listing --output files
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Write a PBN file (pbn), a CSV table with one row per auction
+    /// giving board, dealer, vulnerability, deal, auction, contract and
+    /// declarer (csv), or BBO hand-viewer movies with alerts (lin).
+    /// --output-dir names CSV and LIN outputs with their extension
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "pbn",
+        value_parser = parse_output_format_arg,
+        conflicts_with_all = ["passthrough", "verify_output", "threads"]
+    )]
+    output_format: batch::OutputFormat,
+
+    /// Convention file (.bbsa) for North-South partnership; EPBot's default
+    /// bidding if omitted
     #[arg(long = "ns-conventions", value_name = "FILE")]
-    ns_conventions: PathBuf,
+    ns_conventions: Option<PathBuf>,
 
-    /// Convention file (.bbsa) for East-West partnership
+    /// Convention file (.bbsa) for East-West partnership; EPBot's default
+    /// bidding if omitted
     #[arg(long = "ew-conventions", value_name = "FILE")]
-    ew_conventions: PathBuf,
+    ew_conventions: Option<PathBuf>,
+
+    /// Convention file (.bbsa) for North alone, overriding the NS card for
+    /// North's own bidding, so the partners may play different systems
+    #[arg(long = "north-conventions", value_name = "FILE", conflicts_with = "swap_sides")]
+    north_conventions: Option<PathBuf>,
+
+    /// Convention file (.bbsa) for East alone (see --north-conventions)
+    #[arg(long = "east-conventions", value_name = "FILE", conflicts_with = "swap_sides")]
+    east_conventions: Option<PathBuf>,
+
+    /// Convention file (.bbsa) for South alone (see --north-conventions)
+    #[arg(long = "south-conventions", value_name = "FILE", conflicts_with = "swap_sides")]
+    south_conventions: Option<PathBuf>,
+
+    /// Convention file (.bbsa) for West alone (see --north-conventions)
+    #[arg(long = "west-conventions", value_name = "FILE", conflicts_with = "swap_sides")]
+    west_conventions: Option<PathBuf>,
+
+    /// Zip bundle of convention files, with an optional manifest.json
+    /// mapping system names to files. Select systems with --ns-system and
+    /// --ew-system.
+    #[arg(long = "system-pack", value_name = "ZIP")]
+    system_pack: Option<PathBuf>,
+
+    /// North-South system: from --system-pack if given (by manifest name or
+    /// .bbsa file stem), otherwise a built-in card (sayc, 2/1) or a .bbsa
+    /// file path
+    #[arg(long = "ns-system", value_name = "NAME", conflicts_with = "ns_conventions")]
+    ns_system: Option<String>,
+
+    /// East-West system, as for --ns-system
+    #[arg(long = "ew-system", value_name = "NAME", conflicts_with = "ew_conventions")]
+    ew_system: Option<String>,
+
+    /// Comma-separated convention files (.bbsa) to compare: bid every deal
+    /// once per file, used by both partnerships, and print a table per deal
+    /// of each file's final contract (scored and matchpointed with
+    /// --double-dummy). Writes no output
+    #[arg(
+        long,
+        value_name = "FILES",
+        value_delimiter = ',',
+        conflicts_with_all = ["output", "output_dir", "ns_conventions", "ew_conventions", "system_pack", "ns_system", "ew_system", "north_conventions", "east_conventions",
+            "south_conventions", "west_conventions", "watch", "manifest"]
+    )]
+    systems: Vec<PathBuf>,
+
+    /// Bid every deal twice, once with the usual NS/EW conventions and once
+    /// with these, and print the deals whose auctions differ side by side.
+    /// Each card is a built-in system name or a .bbsa file; a single card is
+    /// used by both partnerships. With --double-dummy the contracts are
+    /// scored and the IMP swing totalled. Writes no output
+    #[arg(
+        long,
+        value_name = "NS_CARD[,EW_CARD]",
+        value_delimiter = ',',
+        conflicts_with_all = ["output", "output_dir", "systems", "watch", "manifest"]
+    )]
+    compare: Vec<String>,
 
     /// Event name for PBN output
     #[arg(long, default_value = "")]
@@ -47,9 +206,31 @@ struct Args {
     #[arg(long = "ew-system-name", default_value = "2/1GF - 2/1 Game Force")]
     ew_system_name: String,
 
-    /// Enable verbose logging (use -vv for debug output)
-    #[arg(short, long, action = clap::ArgAction::Count)]
-    verbose: u8,
+    /// Keep running, and bid everything again whenever an input file, a
+    /// convention file or the system pack changes. Prints a timestamped
+    /// summary after each run; stop with Ctrl-C
+    #[arg(long)]
+    watch: bool,
+
+    /// Bid each input on N worker processes, each taking an equal share of
+    /// its deals, and join their output in input order. Each worker is a
+    /// separate process because EPBot engines in one process can't bid at
+    /// the same time. --max-consecutive-errors counts within each share
+    #[arg(
+        short = 'j',
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..),
+        conflicts_with = "focus_report"
+    )]
+    threads: u16,
+
+    /// Read, bid and write each input a game at a time instead of reading
+    /// it all at once, for files too large to hold in memory. Output is the
+    /// same as without it
+    #[arg(long, default_value_t = false, conflicts_with_all = ["passthrough", "focus_seat", "threads"])]
+    stream: bool,
 
     /// Dry run - parse input but don't write output
     #[arg(long, default_value_t = false)]
@@ -63,17 +244,372 @@ struct Args {
     auction_prefix: Option<String>,
 
     /// Compute single-dummy analysis after each auction. Adds [Result], [Score],
-    /// [Scoring], and a board-id hash comment to the PBN output. Off by default;
-    /// adds roughly 0.22 ms per board (~3-4% on a 500-board file).
+    /// [Scoring], and a board-id hash comment to the PBN output (--double-dummy
+    /// results take precedence). Off by default; adds roughly 0.22 ms per board
+    /// (~3-4% on a 500-board file).
     #[arg(long = "single-dummy", default_value_t = false)]
     single_dummy: bool,
 
+    /// Stop each auction as soon as a bid reaches level N (1-7), e.g. 4 for a
+    /// stop-at-game study. The recorded auction is truncated on that call and
+    /// closed with the PBN `*` marker — it is NOT a legal complete auction.
+    #[arg(long = "stop-at-level", value_name = "N", value_parser = clap::value_parser!(u8).range(1..=7))]
+    stop_at_level: Option<u8>,
+
+    /// Let only one partnership bid (NS by default, or the one given); the
+    /// other passes throughout, giving the uncontested auction. The forced
+    /// passes appear in the [Auction] section.
+    #[arg(long = "no-opponents", value_name = "SIDE", num_args = 0..=1, default_missing_value = "NS", value_parser = parse_pair_arg)]
+    no_opponents: Option<u8>,
+
+    /// Switch on an engine bidding-style setting (e.g. a more aggressive
+    /// preempt or a conservative slam setting) for both partnerships, on top
+    /// of the convention cards. NAME is the engine's own setting name; the
+    /// run stops before bidding if this EPBot build doesn't have it.
+    #[arg(long, value_name = "NAME")]
+    style: Option<String>,
+
+    /// Switch one convention on or off for one partnership on top of its
+    /// convention card, e.g. `NS:Forcing 1NT=0` (repeatable; VALUE is 1/0,
+    /// true/false or on/off). The run stops before bidding if the engine
+    /// doesn't know KEY
+    #[arg(long = "set-convention", value_name = "SIDE:KEY=VALUE", value_parser = parse_set_convention_arg)]
+    set_convention: Vec<epbot_core::ConventionOverride>,
+
+    /// What to do with an auction the engine passes out: record it (written
+    /// with [Contract "Pass"]), skip it (left out of the output), or error
+    /// (counted as a failed deal). Passouts are counted either way
+    #[arg(long, value_name = "MODE", default_value = "record", value_parser = parse_passout_handling_arg)]
+    passout_handling: batch::PassoutHandling,
+
+    /// Treat any Deal tag that fails to parse, or an existing auction that
+    /// disagrees with its Dealer tag, as a hard error (reporting the game
+    /// index) instead of skipping that game. Also stops stray spaces in
+    /// Deal tags ("N: AKQ. JT9...") from being tidied up, so such tags fail
+    /// too. For CI gating of PBN files.
+    #[arg(long = "strict-parse", visible_alias = "strict", default_value_t = false)]
+    strict_parse: bool,
+
+    /// Fail any deal with neither a Dealer tag nor a [Board] number to take
+    /// the standard dealer from, instead of bidding it with North as dealer.
+    /// Also fills the dealer of a PBN export file, which is otherwise
+    /// required.
+    #[arg(long = "require-dealer", default_value_t = false)]
+    require_dealer: bool,
+
+    /// Fail any deal with neither a Vulnerable tag nor a [Board] number to
+    /// take the standard vulnerability from, instead of bidding it with
+    /// nobody vulnerable.
+    #[arg(long = "require-vulnerability", default_value_t = false)]
+    require_vulnerability: bool,
+
+    /// Number the boards N, N+1, ... in input order instead of keeping their
+    /// [Board] numbers. A deal without a Dealer or Vulnerable tag takes the
+    /// standard one for its new number.
+    #[arg(
+        long = "renumber-from",
+        value_name = "N",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with = "passthrough"
+    )]
+    renumber_from: Option<u32>,
+
+    /// Read and rewrite the input without bidding. Every game is written back
+    /// exactly as read, so the output is byte-for-byte identical to the input.
+    #[arg(long, default_value_t = false)]
+    passthrough: bool,
+
+    /// Bid each deal four times, once with each seat as dealer. Every auction
+    /// is written as its own game, labeled with a [Room] tag.
+    #[arg(long = "rotate-dealers", default_value_t = false)]
+    rotate_dealers: bool,
+
+    /// Bid each deal a second time with the NS and EW convention cards (and
+    /// system names) exchanged. Combines with --rotate-dealers.
+    #[arg(long = "swap-sides", default_value_t = false)]
+    swap_sides: bool,
+
+    /// Start each auction from the calls already in the game's Auction
+    /// section, letting EPBot bid on from there ("what would the bot bid
+    /// next?"). Games without an auction are bid from scratch
+    #[arg(long, conflicts_with_all = ["auction_prefix", "rotate_dealers"])]
+    continue_auctions: bool,
+
+    /// Only bid games dated on or after DATE (YYYY.MM.DD or YYYY-MM-DD).
+    /// Older games are copied to the output unchanged. Games without a
+    /// parseable date are bid unless --since-strict is given.
+    #[arg(long, value_name = "DATE", value_parser = parse_since_arg)]
+    since: Option<pbn::PbnDate>,
+
+    /// Tag holding each game's date for --since.
+    #[arg(long = "since-tag", value_name = "TAG", default_value = "Date", requires = "since")]
+    since_tag: String,
+
+    /// With --since, also copy through games whose date is missing or
+    /// unparseable instead of bidding them.
+    #[arg(long = "since-strict", default_value_t = false, requires = "since")]
+    since_strict: bool,
+
+    /// Write only deals where the bid contract, played double-dummy, is off
+    /// par by at least --par-threshold IMPs. Needs a [DoubleDummyTricks] tag
+    /// on each input game (games without one are dropped) unless
+    /// --double-dummy is given.
+    #[arg(long = "par-disagreements", default_value_t = false)]
+    par_disagreements: bool,
+
+    /// Minimum IMP difference from par for --par-disagreements.
+    #[arg(long = "par-threshold", value_name = "IMPS", default_value_t = 2, requires = "par_disagreements")]
+    par_threshold: u32,
+
+    /// Solve each deal double dummy: write [DoubleDummyTricks], par
+    /// ([OptimumScore], [ParScore], [ParContract]), [OptimumResultTable],
+    /// and the bid contract's double-dummy [Result] and [Score], and warn
+    /// about auctions whose contract scores below par.
+    #[arg(long = "double-dummy", default_value_t = false)]
+    double_dummy: bool,
+
+    /// Tag name for the generated contract.
+    #[arg(long = "contract-tag", value_name = "TAG", default_value = "Contract", value_parser = parse_tag_name)]
+    contract_tag: String,
+
+    /// Tag name for the generated declarer.
+    #[arg(long = "declarer-tag", value_name = "TAG", default_value = "Declarer", value_parser = parse_tag_name)]
+    declarer_tag: String,
+
+    /// Tag name for the generated auction section.
+    #[arg(long = "auction-tag", value_name = "TAG", default_value = "Auction", value_parser = parse_tag_name)]
+    auction_tag: String,
+
+    /// Pad the last line of each generated auction to four columns with `-`
+    /// placeholders, for importers that want a rectangular grid.
+    #[arg(long)]
+    pad_auction_grid: bool,
+
+    /// Pad each call of the auction grid to N columns, so calls line up in
+    /// fixed columns. N must fit the longest call (Pass) plus a space; a
+    /// call with a note that doesn't fit is followed by one space
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(5..=40))]
+    auction_column_width: Option<u16>,
+
+    /// Layout of generated auctions: grid (four calls to a line) or vertical
+    /// (one call per line after the caller's seat, e.g. `N 1H`, `E Pass`).
+    /// --pad-auction-grid only applies to the grid
+    #[arg(long, value_name = "LAYOUT", default_value = "grid", value_parser = parse_auction_layout_arg)]
+    auction_layout: batch::AuctionLayout,
+
+    /// Spelling of the Vulnerable tag in generated games: short (None, NS,
+    /// EW, All), hyphen (N/S, E/W) or british (Love All, Game All). Games
+    /// copied from the input keep their own.
+    #[arg(long, value_name = "STYLE", default_value = "short", value_parser = parse_vulnerability_style_arg)]
+    vulnerability_style: batch::VulnerabilityStyle,
+
+    /// Record the convention file names that produced each generated game,
+    /// as [BBANsSystem "sayc.bbsa"] and [BBAEwSystem "acol.bbsa"]
+    #[arg(long)]
+    stamp_conventions: bool,
+
+    /// Record how the output was produced in its leading `%` header: the
+    /// bba-cli and EPBot versions, both convention sources, and the UTC time
+    #[arg(long)]
+    stamp_metadata: bool,
+
+    /// Tag name for the NS convention file with --stamp-conventions.
+    #[arg(long, value_name = "TAG", default_value = "BBANsSystem", value_parser = parse_tag_name, requires = "stamp_conventions")]
+    ns_stamp_tag: String,
+
+    /// Tag name for the EW convention file with --stamp-conventions.
+    #[arg(long, value_name = "TAG", default_value = "BBAEwSystem", value_parser = parse_tag_name, requires = "stamp_conventions")]
+    ew_stamp_tag: String,
+
+    /// Tag each generated game with [BBAIndex "N"], its 1-based position in
+    /// the input, matching the "Game N" prefix of log messages.
+    #[arg(long = "annotate-index", default_value_t = false)]
+    annotate_index: bool,
+
+    /// Attach a [Note] to every generated call giving the bidder's HCP and
+    /// suit lengths (spades-hearts-diamonds-clubs), e.g. "S: 15 HCP,
+    /// 5-3-3-2", after the call's meaning if it has one
+    #[arg(long)]
+    annotate_strength: bool,
+
+    /// Bid each deal K times and keep the most common auction. A comment
+    /// after the auction reports whether all K agreed, or lists each
+    /// distinct auction with its count.
+    #[arg(long, value_name = "K", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: u32,
+
+    /// Abort the run once N deals in a row have failed, reporting how many
+    /// succeeded first. Guards against grinding through a doomed run.
+    #[arg(long = "max-consecutive-errors", value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    max_consecutive_errors: Option<u64>,
+
+    /// Flush the output file after every K games, so an interrupted run
+    /// leaves whole games on disk (1 flushes after each game)
+    #[arg(long, value_name = "K", default_value_t = 100, value_parser = clap::value_parser!(u32).range(1..))]
+    flush_every: u32,
+
+    /// Also sync the output to disk at every flush
+    #[arg(long)]
+    fsync: bool,
+
+    /// Print "Board N: contract by declarer" for every auction to stdout;
+    /// with --dry-run, a preview that writes nothing
+    #[arg(long)]
+    print_contracts: bool,
+
+    /// Write each auction's engine inputs and output as JSON, one file per
+    /// auction (game-0001.json, ...), to DIR for debugging
+    #[arg(long, value_name = "DIR", visible_alias = "dump-wrapper-io")]
+    dump_engine_io: Option<PathBuf>,
+
+    /// Also write a report of just this seat's calls: one line per auction,
+    /// with the seat's calls in brackets and the rest as context
+    #[arg(long, value_name = "SEAT", value_parser = parse_seat_arg)]
+    focus_seat: Option<u8>,
+
+    /// File for the --focus-seat report (default: stdout)
+    #[arg(long, value_name = "FILE", requires = "focus_seat")]
+    focus_report: Option<PathBuf>,
+
+    /// After writing, re-read the output and fail if its games or auctions
+    /// differ from what was meant to be written
+    #[arg(long)]
+    verify_output: bool,
+
+    /// Rewrite the Deal tag of games copied from the input (--passthrough,
+    /// --since, inconsistent auctions) to start from North, keeping each
+    /// hand at its seat. Generated games always start from North
+    #[arg(long)]
+    canonical_first_seat: bool,
+
+    /// Don't add "?" Event, Site, Date, Board, West, North, East and South
+    /// tags to games copied from the input (--since, inconsistent auctions)
+    /// that lack them. Generated games always have these tags
+    #[arg(long)]
+    no_placeholder_tags: bool,
+
+    /// Input file encoding: auto (UTF-8, falling back to Latin-1 for files
+    /// that aren't valid UTF-8), utf8, or latin1
+    #[arg(long, value_name = "ENC", default_value = "auto", value_parser = parse_encoding_arg)]
+    encoding: pbn::InputEncoding,
+
+    /// Replace `x` spot cards in Deal tags (e.g. AKxxx) with real cards: in
+    /// each suit the lowest unnamed ranks go to the x's in the order they
+    /// appear in the tag. Without this, such deals are rejected.
+    #[arg(long)]
+    fill_spots: bool,
+
+    /// Comma-separated tags to keep in games copied from the input
+    /// (--passthrough, --since, inconsistent auctions); all others are
+    /// dropped. Generated games are unaffected.
+    #[arg(long = "keep-tags", value_name = "TAGS", value_delimiter = ',', conflicts_with = "drop_tags")]
+    keep_tags: Option<Vec<String>>,
+
+    /// Comma-separated tags to drop from games copied from the input.
+    #[arg(long = "drop-tags", value_name = "TAGS", value_delimiter = ',')]
+    drop_tags: Option<Vec<String>>,
+
+    /// Give up on an auction after SECS seconds of wall-clock time: the deal
+    /// is marked as an error and processing continues. A hung engine call
+    /// can't be interrupted, so its worker thread is abandoned.
+    #[arg(
+        long,
+        visible_alias = "deal-timeout",
+        value_name = "SECS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    timeout: Option<u64>,
+
+    /// Print a one-line JSON summary of the run (the processing stats plus
+    /// elapsed_secs) to stdout, or to FILE if given.
+    #[arg(long = "summary-json", value_name = "FILE", num_args = 0..=1)]
+    summary_json: Option<Option<PathBuf>>,
+
+    /// Write a JSON list of the output files produced, one
+    /// {input, output, deals, auctions, errors} record per file, to FILE.
+    #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+    manifest: Option<PathBuf>,
+
+    /// Report elapsed time, deals/second, and peak resident memory (where the
+    /// platform exposes it; "n/a" otherwise) at the end of the run.
+    #[arg(long = "resource-report", default_value_t = false)]
+    resource_report: bool,
+
     /// Scoring mode for the auction. Affects [Score] computation and the
     /// [Scoring] tag.
     #[arg(long, value_name = "MODE", default_value = "MP", value_parser = parse_scoring_arg)]
     scoring: epbot_core::Scoring,
 }
 
+/// What bba-cli does. Without a subcommand it is `run` (see
+/// `with_default_command`).
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Bid the deals of PBN files (--input) and write them with their
+    /// auctions (--output). This is the default: `bba-cli --input ...`
+    /// without a subcommand runs it.
+    #[command(args_override_self = true)]
+    Run(Box<RunArgs>),
+
+    /// Bid deals read from stdin, one per line, printing each auction.
+    /// Lines are `[DEALER VUL] DEAL`, e.g. `S NS N:AKQ.JT9.876.543 ...`.
+    /// With --seat, you bid one seat and EPBot the other three.
+    #[command(visible_alias = "interactive")]
+    Repl(repl::ReplArgs),
+
+    /// Bid deals given on the command line and print each auction, e.g.
+    /// `bid --deal "N:AKQ.JT9.876.5432 ..." --dealer S --vulnerability NS`.
+    Bid(repl::BidArgs),
+
+    /// Print distribution statistics for the deals in a PBN file: average
+    /// HCP and balanced-hand rate per seat, suit lengths, and common
+    /// patterns. Does not bid.
+    Stats(deal_stats::StatsArgs),
+
+    /// Print the settings a convention file (.bbsa) applies: system and
+    /// opponent types and the conventions it switches on.
+    ListConventions(convention_list::ListConventionsArgs),
+
+    /// Check convention files (.bbsa) for lines the engine would skip or
+    /// misread: malformed lines, bad values, unknown or repeated keys. Exits
+    /// non-zero if anything is wrong.
+    ValidateConventions(convention_check::ValidateConventionsArgs),
+
+    /// Print the settings two convention files (.bbsa) set differently,
+    /// e.g. to see why two systems bid a deal differently.
+    DiffConventions(convention_list::DiffConventionsArgs),
+
+    /// List the boards two PBN files bid differently (contract or auction),
+    /// with both auctions side by side. Does not bid.
+    Compare(compare::CompareArgs),
+
+    /// Write only the Board, Dealer, Vulnerable and Deal tags of each game,
+    /// dropping auctions, contracts, notes and commentary. Does not bid.
+    Strip(strip::StripArgs),
+
+    /// Check PBN files without bidding: complete deals, known dealer and
+    /// vulnerability, and legal, finished auctions that agree with each
+    /// game's Contract and Declarer, and with --strict the PBN 2.1
+    /// mandatory tags. Problems are reported with their line numbers; exits
+    /// non-zero if anything is wrong.
+    Validate(validate::ValidateArgs),
+
+    /// Write a PBN file's games as CSV rows (as --output-format csv), from
+    /// the auctions already in it, or convert deals between the PBN, LIN,
+    /// GIB and dot formats. Does not bid.
+    Convert(convert::ConvertArgs),
+
+    /// Generate random deals, e.g. `deal --count 100 --seed 42 --constraint
+    /// "South 15-17 balanced"`, and write them as PBN, ready to bid with
+    /// `--input -`. Does not bid.
+    Deal(deal_gen::DealArgs),
+
+    /// Bid a fixed set of deals built into the binary and report total time,
+    /// throughput, and per-deal timing percentiles, for comparing machines
+    /// and EPBot versions.
+    Bench(bench::BenchArgs),
+}
+
 fn parse_scoring_arg(s: &str) -> std::result::Result<epbot_core::Scoring, String> {
     match s.to_uppercase().as_str() {
         "MP" | "MATCHPOINTS" => Ok(epbot_core::Scoring::Matchpoints),
@@ -82,10 +618,254 @@ fn parse_scoring_arg(s: &str) -> std::result::Result<epbot_core::Scoring, String
     }
 }
 
+fn parse_set_convention_arg(s: &str) -> std::result::Result<epbot_core::ConventionOverride, String> {
+    let invalid = || format!("invalid convention setting '{}'; expected SIDE:KEY=VALUE, e.g. \"NS:Forcing 1NT=0\"", s);
+    let (side, rest) = s.split_once(':').ok_or_else(invalid)?;
+    let (key, value) = rest.rsplit_once('=').ok_or_else(invalid)?;
+    let side = match side.trim().to_uppercase().as_str() {
+        "NS" => 0,
+        "EW" => 1,
+        other => return Err(format!("unknown side '{}'; expected NS or EW", other)),
+    };
+    let enabled = match value.trim().to_lowercase().as_str() {
+        "1" | "true" | "on" => true,
+        "0" | "false" | "off" => false,
+        other => return Err(format!("unknown convention value '{}'; expected 1/0, true/false or on/off", other)),
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(invalid());
+    }
+    Ok(epbot_core::ConventionOverride {
+        side,
+        key: key.to_string(),
+        enabled,
+    })
+}
+
+fn parse_encoding_arg(s: &str) -> std::result::Result<pbn::InputEncoding, String> {
+    match s.to_lowercase().as_str() {
+        "auto" => Ok(pbn::InputEncoding::Auto),
+        "utf8" | "utf-8" => Ok(pbn::InputEncoding::Utf8),
+        "latin1" | "latin-1" | "iso-8859-1" | "windows-1252" => Ok(pbn::InputEncoding::Latin1),
+        other => Err(format!("unknown encoding '{}'; expected auto, utf8 or latin1", other)),
+    }
+}
+
+fn parse_auction_layout_arg(s: &str) -> std::result::Result<batch::AuctionLayout, String> {
+    match s.to_lowercase().as_str() {
+        "grid" => Ok(batch::AuctionLayout::Grid),
+        "vertical" => Ok(batch::AuctionLayout::Vertical),
+        other => Err(format!("unknown auction layout '{}'; expected grid or vertical", other)),
+    }
+}
+
+fn parse_output_format_arg(s: &str) -> std::result::Result<batch::OutputFormat, String> {
+    match s.to_lowercase().as_str() {
+        "pbn" => Ok(batch::OutputFormat::Pbn),
+        "csv" => Ok(batch::OutputFormat::Csv),
+        "lin" => Ok(batch::OutputFormat::Lin),
+        other => Err(format!("unknown output format '{}'; expected pbn, csv or lin", other)),
+    }
+}
+
+fn parse_passout_handling_arg(s: &str) -> std::result::Result<batch::PassoutHandling, String> {
+    match s.to_lowercase().as_str() {
+        "record" => Ok(batch::PassoutHandling::Record),
+        "skip" => Ok(batch::PassoutHandling::Skip),
+        "error" => Ok(batch::PassoutHandling::Error),
+        other => Err(format!("unknown passout handling '{}'; expected record, skip or error", other)),
+    }
+}
+
+fn parse_vulnerability_style_arg(s: &str) -> std::result::Result<batch::VulnerabilityStyle, String> {
+    match s.to_lowercase().as_str() {
+        "short" => Ok(batch::VulnerabilityStyle::Short),
+        "hyphen" => Ok(batch::VulnerabilityStyle::Hyphen),
+        "british" => Ok(batch::VulnerabilityStyle::British),
+        other => Err(format!("unknown vulnerability style '{}'; expected short, hyphen or british", other)),
+    }
+}
+
+/// A seat: 0..4 for N, E, S, W.
+fn parse_seat_arg(s: &str) -> std::result::Result<u8, String> {
+    match s.to_uppercase().as_str() {
+        "N" | "NORTH" => Ok(0),
+        "E" | "EAST" => Ok(1),
+        "S" | "SOUTH" => Ok(2),
+        "W" | "WEST" => Ok(3),
+        other => Err(format!("unknown seat '{}'; expected N, E, S or W", other)),
+    }
+}
+
+/// A partnership name: 0 for NS, 1 for EW.
+fn parse_pair_arg(s: &str) -> std::result::Result<u8, String> {
+    match s.to_uppercase().as_str() {
+        "NS" => Ok(0),
+        "EW" => Ok(1),
+        other => Err(format!("unknown partnership '{}'; expected NS or EW", other)),
+    }
+}
+
+fn parse_since_arg(s: &str) -> std::result::Result<pbn::PbnDate, String> {
+    pbn::PbnDate::parse(s)
+        .ok_or_else(|| format!("invalid date '{}'; expected YYYY.MM.DD or YYYY-MM-DD", s))
+}
+
+/// PBN tag names are letters, digits, and underscores, starting with a letter.
+fn parse_tag_name(s: &str) -> std::result::Result<String, String> {
+    let valid = s.starts_with(|c: char| c.is_ascii_alphabetic())
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(s.to_string())
+    } else {
+        Err(format!("invalid PBN tag name '{}'", s))
+    }
+}
+
+/// Read one side's convention card. A system name is looked up in
+/// `--system-pack` if there is one, else among the built-in cards, else
+/// read as a `.bbsa` path; without a name the card is the `.bbsa` file.
+/// Returns the card text and a description of where it came from.
+fn load_conventions(
+    side: &str,
+    file: Option<&Path>,
+    system: Option<&str>,
+    pack: Option<&mut SystemPack>,
+) -> Result<Option<(String, String)>> {
+    if let Some(name) = system {
+        if let Some(pack) = pack {
+            return pack.read(name).map(Some);
+        }
+        if let Some((card, content)) = builtin_systems::find(name) {
+            return Ok(Some((content.to_string(), format!("built-in:{}", card))));
+        }
+        if !Path::new(name).exists() {
+            anyhow::bail!(
+                "unknown {} system '{}'; expected one of {} or a .bbsa file",
+                side,
+                name,
+                builtin_systems::names().join(", ")
+            );
+        }
+    }
+    let Some(path) = file.or(system.map(Path::new)) else {
+        info!("No {} conventions given; {} bid with EPBot's defaults", side, side);
+        return Ok(None);
+    };
+    if !path.exists() {
+        anyhow::bail!("{} conventions file not found: {:?}", side, path);
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {} conventions file", side))?;
+    Ok(Some((content, path.display().to_string())))
+}
+
+/// `--north-conventions` .. `--west-conventions`, indexed N, E, S, W.
+fn seat_convention_args(args: &RunArgs) -> [Option<&PathBuf>; 4] {
+    [
+        args.north_conventions.as_ref(),
+        args.east_conventions.as_ref(),
+        args.south_conventions.as_ref(),
+        args.west_conventions.as_ref(),
+    ]
+}
+
+/// The files `--watch` re-runs on: the inputs and wherever the convention
+/// cards come from.
+fn watch_paths(args: &RunArgs) -> Vec<PathBuf> {
+    let mut paths = args.input.clone();
+    paths.extend(args.ns_conventions.iter().cloned());
+    paths.extend(args.ew_conventions.iter().cloned());
+    paths.extend(seat_convention_args(args).into_iter().flatten().cloned());
+    paths.extend(args.system_pack.iter().cloned());
+    paths
+}
+
+/// Pair each input with its output: the `--output` list in order, or the
+/// input's file name under `--output-dir` (with `extension`, if given).
+fn output_paths(
+    inputs: &[PathBuf],
+    outputs: &[PathBuf],
+    output_dir: Option<&Path>,
+    extension: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+    let Some(dir) = output_dir else {
+        if outputs.len() != inputs.len() {
+            anyhow::bail!(
+                "{} --input files but {} --output files; give one output per input or use --output-dir",
+                inputs.len(),
+                outputs.len()
+            );
+        }
+        return Ok(outputs.to_vec());
+    };
+
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let mut paths: Vec<PathBuf> = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let name = input
+            .file_name()
+            .with_context(|| format!("Input {:?} has no file name", input))?;
+        let mut path = dir.join(name);
+        // A LIN, GIB or dot input is bid to PBN, so its output shouldn't
+        // keep the input's extension.
+        let converted = formats::Format::from_extension(input).is_some_and(|f| f != formats::Format::Pbn);
+        if let Some(extension) = extension.or_else(|| converted.then_some("pbn")) {
+            path.set_extension(extension);
+        }
+        if paths.contains(&path) {
+            anyhow::bail!("Two inputs would both be written to {:?}", path);
+        }
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// `--summary-json`: the final stats as a single JSON object.
+fn write_summary_json(
+    stats: &batch::ProcessingStats,
+    elapsed: std::time::Duration,
+    path: Option<&Path>,
+) -> Result<()> {
+    #[derive(serde::Serialize)]
+    struct Summary<'a> {
+        #[serde(flatten)]
+        stats: &'a batch::ProcessingStats,
+        elapsed_secs: f64,
+    }
+
+    let json = serde_json::to_string(&Summary {
+        stats,
+        elapsed_secs: elapsed.as_secs_f64(),
+    })?;
+    match path {
+        Some(path) => std::fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write summary to {:?}", path))?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// One `--manifest` record: an output file and the counts behind it.
+#[derive(serde::Serialize)]
+struct ManifestEntry<'a> {
+    input: &'a Path,
+    output: &'a Path,
+    deals: usize,
+    auctions: usize,
+    errors: usize,
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let (argv, run_at) = with_default_command(std::env::args_os().collect());
+    let (argv, defaults) = match run_at {
+        Some(at) => defaults_file::args(argv, at + 1)?,
+        None => (argv, None),
+    };
+    let cli = Cli::parse_from(argv);
 
-    let log_level = match args.verbose {
+    let log_level = match cli.verbose {
         0 => "info",
         1 => "debug",
         _ => "trace",
@@ -100,73 +880,493 @@ fn main() -> Result<()> {
         Ok(v) => info!("BBA-CLI v{} (EPBot {})", env!("CARGO_PKG_VERSION"), v),
         Err(_) => info!("BBA-CLI v{}", env!("CARGO_PKG_VERSION")),
     }
+    if let Some(path) = defaults {
+        info!("Default arguments read from {:?}", path);
+    }
 
-    debug!("Input: {:?}", args.input);
-    debug!("Output: {:?}", args.output);
-    debug!("NS Conventions: {:?}", args.ns_conventions);
-    debug!("EW Conventions: {:?}", args.ew_conventions);
+    match cli.command {
+        Command::Run(ref args) => run(args),
+        Command::Repl(ref repl_args) => repl::run(repl_args),
+        Command::Bid(ref bid_args) => repl::run_bid(bid_args),
+        Command::Stats(ref stats_args) => deal_stats::run(stats_args),
+        Command::ListConventions(ref list_args) => convention_list::run(list_args),
+        Command::ValidateConventions(ref check_args) => convention_check::run(check_args),
+        Command::DiffConventions(ref diff_args) => convention_list::run_diff(diff_args),
+        Command::Compare(ref compare_args) => compare::run(compare_args),
+        Command::Strip(ref strip_args) => strip::run(strip_args),
+        Command::Validate(ref validate_args) => validate::run(validate_args),
+        Command::Convert(ref convert_args) => convert::run(convert_args),
+        Command::Deal(ref deal_args) => deal_gen::run(deal_args),
+        Command::Bench(ref bench_args) => bench::run(bench_args),
+    }
+}
 
-    // Validate input files
-    if !args.input.exists() {
-        anyhow::bail!("Input file not found: {:?}", args.input);
+/// Bidding PBN files is the `run` subcommand, and also what bba-cli does
+/// when no subcommand is given: `bba-cli --input a.pbn ...` reads as
+/// `bba-cli run --input a.pbn ...`. Returns the arguments with `run` put in
+/// where it was left out, and where `run` is (None for other subcommands,
+/// or with no arguments at all, which prints the help).
+fn with_default_command(mut args: Vec<OsString>) -> (Vec<OsString>, Option<usize>) {
+    // Global options may come before the subcommand.
+    let mut at = 1;
+    while let Some(arg) = args.get(at).and_then(|a| a.to_str()) {
+        match arg {
+            "--config" => at += 2,
+            "--verbose" => at += 1,
+            _ if arg.starts_with("--config=") => at += 1,
+            _ if arg.len() > 1 && arg.starts_with('-') && arg[1..].bytes().all(|b| b == b'v') => at += 1,
+            _ => break,
+        }
     }
-    if !args.ns_conventions.exists() {
-        anyhow::bail!("NS conventions file not found: {:?}", args.ns_conventions);
+    let Some(first) = args.get(at) else {
+        return (args, None);
+    };
+    match first.to_str() {
+        Some("run") => return (args, Some(at)),
+        Some("help" | "-h" | "--help" | "-V" | "--version") => return (args, None),
+        Some(name) if Cli::command().find_subcommand(name).is_some() => return (args, None),
+        _ => {}
     }
-    if !args.ew_conventions.exists() {
-        anyhow::bail!("EW conventions file not found: {:?}", args.ew_conventions);
+    args.insert(at, OsString::from("run"));
+    (args, Some(at))
+}
+
+/// `run`: bid every --input (or the --input-sqlite query, --systems or
+/// --compare) into its output.
+fn run(args: &RunArgs) -> Result<()> {
+    if let Some(chunk) = parallel::Chunk::from_env()? {
+        return run_chunk(args, &chunk);
     }
+    check_stdio(args)?;
 
-    let auction_prefix: Option<Vec<String>> = args
-        .auction_prefix
-        .as_deref()
-        .map(|s| s.split_whitespace().map(|t| t.to_string()).collect());
+    #[cfg(feature = "sqlite")]
+    if let (Some(db), Some(query)) = (&args.input_sqlite, &args.query) {
+        return run_sqlite(args, db, query);
+    }
 
-    if let Some(ref bids) = auction_prefix {
-        info!("Auction prefix: {} bid(s) — {}", bids.len(), bids.join(" "));
+    if !args.systems.is_empty() {
+        return run_systems(args);
+    }
+    if !args.compare.is_empty() {
+        return run_compare(args);
     }
 
-    let config = OutputConfig {
-        event: args.event,
-        ns_system_name: args.ns_system_name,
-        ew_system_name: args.ew_system_name,
-        ns_conventions_path: args.ns_conventions.display().to_string(),
-        ew_conventions_path: args.ew_conventions.display().to_string(),
-        scoring: args.scoring,
-        single_dummy: args.single_dummy,
+    let extension = match args.output_format {
+        batch::OutputFormat::Pbn => None,
+        batch::OutputFormat::Csv => Some("csv"),
+        batch::OutputFormat::Lin => Some("lin"),
     };
+    let outputs = output_paths(&args.input, &args.output, args.output_dir.as_deref(), extension)?;
 
-    if args.single_dummy {
-        info!("Single-dummy analysis enabled (Result/Score/board-id will be emitted)");
+    // Validate input files
+    for (input, output) in args.input.iter().zip(&outputs) {
+        debug!("Input: {:?}", input);
+        debug!("Output: {:?}", output);
+        if !input.exists() && !pbn::is_stdio(input) {
+            anyhow::bail!("Input file not found: {:?}", input);
+        }
+    }
+
+    if args.watch {
+        return watch::run(&watch_paths(args), || run_batch(args, &outputs));
+    }
+    run_batch(args, &outputs).map(|_| ())
+}
+
+/// `--input -` reads stdin and `--output -` writes stdout. Refuse what
+/// can't work that way: reading stdin twice, or printing anything else to
+/// stdout alongside the output.
+fn check_stdio(args: &RunArgs) -> Result<()> {
+    let stdin = args.input.iter().filter(|p| pbn::is_stdio(p)).count();
+    let stdout = args.output.iter().filter(|p| pbn::is_stdio(p)).count();
+    if stdin > 1 || stdout > 1 {
+        anyhow::bail!("--input - and --output - can each be given only once");
+    }
+    if stdin > 0 {
+        if args.output_dir.is_some() {
+            anyhow::bail!("--input - has no file name for --output-dir; use --output");
+        }
+        if args.watch || args.threads > 1 || !args.systems.is_empty() || !args.compare.is_empty() {
+            anyhow::bail!("--input - can't be combined with --watch, --threads, --systems or --compare");
+        }
+    }
+    let other_stdout = args.print_contracts
+        || (args.focus_seat.is_some() && args.focus_report.is_none())
+        || matches!(args.summary_json, Some(None));
+    if stdout > 0 && (other_stdout || args.verify_output || args.threads > 1) {
+        anyhow::bail!(
+            "--output - can't be combined with --print-contracts, --verify-output or --threads, \
+             nor with --focus-seat or --summary-json unless they write to a FILE"
+        );
+    }
+    Ok(())
+}
+
+/// Bid every `--input` into its output, then report on the run.
+fn run_batch(args: &RunArgs, outputs: &[PathBuf]) -> Result<batch::ProcessingStats> {
+    let resources = resource::ResourceReport::start();
+    let setup = setup_batch(args)?;
+
+    // Every file goes through this thread's engine, so EPBot is set up and
+    // the conventions are loaded once for the whole run.
+    let multiple = args.input.len() > 1;
+    let mut stats = batch::ProcessingStats::default();
+    let mut manifest = Vec::new();
+    for (i, (input, output)) in args.input.iter().zip(outputs).enumerate() {
+        info!("Processing {:?}...", input);
+
+        let in_workers = if args.threads > 1 && !args.passthrough {
+            let threads = usize::from(args.threads);
+            parallel::run(i, input, output, threads, args.encoding, args.dry_run)
+                .with_context(|| format!("Failed to process {:?}", input))?
+        } else {
+            None
+        };
+        let file_stats = match in_workers {
+            Some(stats) => stats,
+            None if args.stream => batch::process_pbn_file_streaming(
+                input,
+                output,
+                setup.ns_conventions.as_deref(),
+                setup.ew_conventions.as_deref(),
+                args.dry_run,
+                &setup.config,
+                setup.auction_prefix.as_deref(),
+            )
+            .with_context(|| format!("Failed to process {:?}", input))?,
+            None => process_pbn_file(
+                input,
+                output,
+                setup.ns_conventions.as_deref(),
+                setup.ew_conventions.as_deref(),
+                args.dry_run,
+                &setup.config,
+                setup.auction_prefix.as_deref(),
+            )
+            .with_context(|| format!("Failed to process {:?}", input))?,
+        };
+
+        if multiple {
+            info!(
+                "{:?}: processed {} deals, generated {} auctions, {} errors",
+                input, file_stats.deals_processed, file_stats.auctions_generated, file_stats.errors
+            );
+        }
+        if !args.dry_run {
+            info!("Output written to {:?}", output);
+        }
+        manifest.push(ManifestEntry {
+            input,
+            output,
+            deals: file_stats.deals_processed,
+            auctions: file_stats.auctions_generated,
+            errors: file_stats.errors,
+        });
+        stats.add(&file_stats);
+    }
+
+    if let Some(ref path) = args.manifest {
+        let json = serde_json::to_string_pretty(&manifest)?;
+        std::fs::write(path, json + "\n")
+            .with_context(|| format!("Failed to write manifest to {:?}", path))?;
     }
 
-    info!("Processing {:?}...", args.input);
+    if multiple {
+        info!("Totals over {} files:", args.input.len());
+    }
+    report_run(args, &stats, &resources)?;
+    Ok(stats)
+}
 
+/// A `--threads` worker: bid this process's share of one input into the
+/// chunk's file and leave its stats for the parent.
+fn run_chunk(args: &RunArgs, chunk: &parallel::Chunk) -> Result<()> {
+    let input = args
+        .input
+        .get(chunk.input)
+        .with_context(|| format!("Worker input {} out of range", chunk.input))?;
+    let mut setup = setup_batch(args)?;
+    setup.config.chunk = Some((chunk.index, chunk.count));
     let stats = process_pbn_file(
-        &args.input,
-        &args.output,
-        &args.ns_conventions,
-        &args.ew_conventions,
+        input,
+        &chunk.output_path(),
+        setup.ns_conventions.as_deref(),
+        setup.ew_conventions.as_deref(),
         args.dry_run,
-        &config,
-        auction_prefix.as_deref(),
+        &setup.config,
+        setup.auction_prefix.as_deref(),
     )
-    .context("Failed to process PBN file")?;
+    .with_context(|| format!("Failed to process {:?}", input))?;
+    chunk.write_stats(&stats)
+}
+
+/// `--systems`: bid every `--input` under each system and print the
+/// comparison tables.
+fn run_systems(args: &RunArgs) -> Result<()> {
+    for input in &args.input {
+        if !input.exists() {
+            anyhow::bail!("Input file not found: {:?}", input);
+        }
+    }
+    let resources = resource::ResourceReport::start();
+    let setup = setup_batch(args)?;
+    let stats = systems::run(&args.input, &args.systems, &setup.config, setup.auction_prefix.as_deref())?;
+    report_run(args, &stats, &resources)
+}
+
+/// `--compare`: bid every `--input` with the usual cards and with the
+/// `--compare` ones, and print the deals that bid differently.
+fn run_compare(args: &RunArgs) -> Result<()> {
+    if args.compare.len() > 2 {
+        anyhow::bail!("--compare takes one card, or an NS and an EW card");
+    }
+    for input in &args.input {
+        if !input.exists() {
+            anyhow::bail!("Input file not found: {:?}", input);
+        }
+    }
+    let resources = resource::ResourceReport::start();
+    let setup = setup_batch(args)?;
+    let ns = load_conventions("--compare NS", None, Some(&args.compare[0]), None)?;
+    let ew = match args.compare.get(1) {
+        Some(name) => load_conventions("--compare EW", None, Some(name), None)?,
+        None => ns.clone(),
+    };
+
+    let source = |s: &str| if s.is_empty() { "EPBot defaults".to_string() } else { s.to_string() };
+    let sets = [
+        systems::CardSet {
+            name: format!(
+                "NS {}, EW {}",
+                source(&setup.config.ns_conventions_path),
+                source(&setup.config.ew_conventions_path)
+            ),
+            ns: setup.ns_conventions.as_deref(),
+            ew: setup.ew_conventions.as_deref(),
+        },
+        systems::CardSet {
+            name: format!(
+                "NS {}, EW {}",
+                ns.as_ref().map_or("", |(_, s)| s.as_str()),
+                ew.as_ref().map_or("", |(_, s)| s.as_str())
+            ),
+            ns: ns.as_ref().map(|(content, _)| content.as_str()),
+            ew: ew.as_ref().map(|(content, _)| content.as_str()),
+        },
+    ];
+    let stats = systems::compare(&args.input, &sets, &setup.config, setup.auction_prefix.as_deref())?;
+    report_run(args, &stats, &resources)
+}
 
+/// `--input-sqlite`: bid the rows of `query` with the usual settings.
+#[cfg(feature = "sqlite")]
+fn run_sqlite(args: &RunArgs, db: &Path, query: &str) -> Result<()> {
+    if args.output.len() > 1 {
+        anyhow::bail!("--input-sqlite writes a single --output");
+    }
+    let resources = resource::ResourceReport::start();
+    let sink = sqlite_input::Sink::new(args.output.first().map(PathBuf::as_path), args.results_table.as_deref())?;
+    let setup = setup_batch(args)?;
+    let stats = sqlite_input::run(
+        db,
+        query,
+        &sink,
+        setup.ns_conventions.as_deref(),
+        setup.ew_conventions.as_deref(),
+        args.dry_run,
+        &setup.config,
+        setup.auction_prefix.as_deref(),
+    )?;
+    report_run(args, &stats, &resources)
+}
+
+/// The end-of-run totals, plus `--resource-report` and `--summary-json`.
+fn report_run(args: &RunArgs, stats: &batch::ProcessingStats, resources: &resource::ResourceReport) -> Result<()> {
     info!(
         "Processed {} deals, generated {} auctions",
         stats.deals_processed, stats.auctions_generated
     );
 
+    if stats.deals_skipped > 0 {
+        info!("Copied {} deals unchanged", stats.deals_skipped);
+    }
+
+    if stats.deals_filtered > 0 {
+        info!("Dropped {} deals at or near par", stats.deals_filtered);
+    }
+
+    if stats.par_checked > 0 {
+        info!("{} of {} auctions reached double-dummy par", stats.par_reached, stats.par_checked);
+    }
+
+    if stats.passouts > 0 {
+        let handling = match args.passout_handling {
+            batch::PassoutHandling::Record => "recorded",
+            batch::PassoutHandling::Skip => "left out",
+            batch::PassoutHandling::Error => "counted as errors",
+        };
+        info!("{} auctions passed out ({})", stats.passouts, handling);
+    }
+
     if stats.errors > 0 {
         error!("{} deals had errors", stats.errors);
     }
 
+    if args.resource_report {
+        for line in resources.finish(stats.deals_processed) {
+            info!("{}", line);
+        }
+    }
+
+    if let Some(ref target) = args.summary_json {
+        write_summary_json(stats, resources.elapsed(), target.as_deref())?;
+    }
+
     if args.dry_run {
         info!("Dry run complete - no output written");
-    } else {
-        info!("Output written to {:?}", args.output);
     }
 
     Ok(())
 }
+
+/// Conventions, output settings and auction prefix for a run, shared by
+/// every input file.
+struct Batch {
+    ns_conventions: Option<String>,
+    ew_conventions: Option<String>,
+    config: OutputConfig,
+    auction_prefix: Option<Vec<String>>,
+}
+
+/// Load the convention cards and build the `OutputConfig` from `args`.
+fn setup_batch(args: &RunArgs) -> Result<Batch> {
+    let mut pack = args.system_pack.as_deref().map(SystemPack::open).transpose()?;
+    let ns_conventions = load_conventions(
+        "NS",
+        args.ns_conventions.as_deref(),
+        args.ns_system.as_deref(),
+        pack.as_mut(),
+    )?;
+    let ew_conventions = load_conventions(
+        "EW",
+        args.ew_conventions.as_deref(),
+        args.ew_system.as_deref(),
+        pack.as_mut(),
+    )?;
+    let mut seat_conventions: [Option<(String, String)>; 4] = Default::default();
+    for (seat, (path, name)) in seat_convention_args(args)
+        .into_iter()
+        .zip(["North", "East", "South", "West"])
+        .enumerate()
+    {
+        if let Some(path) = path {
+            seat_conventions[seat] = load_conventions(name, Some(path), None, None)?;
+        }
+    }
+    // An empty source means EPBot's defaults; no CC line is written for it.
+    let ns_source = ns_conventions.as_ref().map(|(_, source)| source.clone()).unwrap_or_default();
+    let ew_source = ew_conventions.as_ref().map(|(_, source)| source.clone()).unwrap_or_default();
+    debug!("NS Conventions: {}", ns_source);
+    debug!("EW Conventions: {}", ew_source);
+
+    let auction_prefix: Option<Vec<String>> = args
+        .auction_prefix
+        .as_deref()
+        .map(|s| s.split_whitespace().map(|t| t.to_string()).collect());
+
+    if let Some(ref style) = args.style {
+        epbot_core::check_style(style).context("Unsupported --style")?;
+        info!("Bidding style: {}", style);
+    }
+    for o in &args.set_convention {
+        epbot_core::check_convention(&o.key).context("Unknown --set-convention key")?;
+        info!("{} convention '{}' {}", if o.side == 0 { "NS" } else { "EW" }, o.key, if o.enabled { "on" } else { "off" });
+    }
+
+    if let Some(ref bids) = auction_prefix {
+        info!("Auction prefix: {} bid(s) — {}", bids.len(), bids.join(" "));
+    }
+
+    let config = OutputConfig {
+        event: args.event.clone(),
+        ns_system_name: args.ns_system_name.clone(),
+        ew_system_name: args.ew_system_name.clone(),
+        ns_conventions_path: ns_source,
+        ew_conventions_path: ew_source,
+        seat_conventions,
+        scoring: args.scoring,
+        single_dummy: args.single_dummy,
+        stop_at_level: args.stop_at_level,
+        // --no-opponents names the side that bids; the other one passes
+        passing_side: args.no_opponents.map(|bidding| 1 - bidding),
+        style: args.style.clone(),
+        conventions: args.set_convention.clone(),
+        passout_handling: args.passout_handling,
+        output_format: args.output_format,
+        strict_parse: args.strict_parse,
+        require_dealer: args.require_dealer,
+        require_vulnerability: args.require_vulnerability,
+        renumber_from: args.renumber_from,
+        passthrough: args.passthrough,
+        rotate_dealers: args.rotate_dealers,
+        swap_sides: args.swap_sides,
+        continue_auctions: args.continue_auctions,
+        since: args.since,
+        since_tag: args.since_tag.clone(),
+        since_strict: args.since_strict,
+        par_threshold: args.par_disagreements.then_some(args.par_threshold),
+        double_dummy: args.double_dummy,
+        contract_tag: args.contract_tag.clone(),
+        declarer_tag: args.declarer_tag.clone(),
+        auction_tag: args.auction_tag.clone(),
+        pad_auction_grid: args.pad_auction_grid,
+        auction_layout: args.auction_layout,
+        auction_column_width: args.auction_column_width.map(usize::from),
+        vulnerability_style: args.vulnerability_style,
+        chunk: None,
+        annotate_index: args.annotate_index,
+        annotate_strength: args.annotate_strength,
+        stamp_tags: args
+            .stamp_conventions
+            .then(|| (args.ns_stamp_tag.clone(), args.ew_stamp_tag.clone())),
+        stamp_metadata: args.stamp_metadata,
+        repeat: args.repeat,
+        encoding: args.encoding,
+        fill_spots: args.fill_spots,
+        verify_output: args.verify_output,
+        flush_every: args.flush_every as usize,
+        fsync: args.fsync,
+        print_contracts: args.print_contracts,
+        dump_engine_io: args.dump_engine_io.clone(),
+        focus_seat: args.focus_seat,
+        focus_report: args.focus_report.clone(),
+        canonical_first_seat: args.canonical_first_seat,
+        placeholder_tags: !args.no_placeholder_tags,
+        tag_filter: match (args.keep_tags.clone(), args.drop_tags.clone()) {
+            (Some(keep), _) => pbn::TagFilter::Keep(keep),
+            (None, Some(drop)) => pbn::TagFilter::Drop(drop),
+            (None, None) => pbn::TagFilter::All,
+        },
+        max_consecutive_errors: args.max_consecutive_errors.map(|n| n as usize),
+        timeout: args.timeout.map(std::time::Duration::from_secs),
+    };
+
+    if args.single_dummy {
+        info!("Single-dummy analysis enabled (Result/Score/board-id will be emitted)");
+    }
+
+    if let Some(bidding) = args.no_opponents {
+        info!("Uncontested auctions: only {} bid", if bidding == 0 { "NS" } else { "EW" });
+    }
+
+    if let Some(level) = args.stop_at_level {
+        info!("Auctions will stop at level {} (truncated, not complete)", level);
+    }
+
+    Ok(Batch {
+        ns_conventions: ns_conventions.map(|(content, _)| content),
+        ew_conventions: ew_conventions.map(|(content, _)| content),
+        config,
+        auction_prefix,
+    })
+}
@@ -0,0 +1,209 @@
+//! `--threads N`: bid one input file in N worker processes.
+//!
+//! EPBot keeps state shared by every engine in a process, so engines bidding
+//! on several threads at once disturb each other's auctions. Each worker is
+//! instead a copy of this program, started with the same arguments and told
+//! through `CHUNK_VAR` which share of the boards to bid: worker k of n bids
+//! boards `len*k/n .. len*(k+1)/n` of one input into its own file. The parent
+//! joins the files in order, adds up the workers' stats, and replays their
+//! stdout (e.g. `--print-contracts`) in order too.
+
+use crate::batch::{self, ProcessingStats};
+use crate::pbn::{InputEncoding, InputFile, PbnJoiner};
+use anyhow::{Context, Result};
+use log::{debug, info};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Set in a worker's environment to `INPUT/INDEX/COUNT`: the position of
+/// its input among `--input`, and its share of that file's boards.
+pub const CHUNK_VAR: &str = "BBA_CHUNK";
+/// Set in a worker's environment to the directory it writes its output and
+/// stats into.
+pub const CHUNK_DIR_VAR: &str = "BBA_CHUNK_DIR";
+
+/// A worker's assignment, read from its environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Index of the input in `--input`.
+    pub input: usize,
+    pub index: usize,
+    pub count: usize,
+    pub dir: PathBuf,
+}
+
+impl Chunk {
+    /// The assignment if this process is a worker, None otherwise.
+    pub fn from_env() -> Result<Option<Chunk>> {
+        let Some(spec) = std::env::var_os(CHUNK_VAR) else {
+            return Ok(None);
+        };
+        let spec = spec.to_string_lossy();
+        let dir = std::env::var_os(CHUNK_DIR_VAR)
+            .with_context(|| format!("{} is set but {} is not", CHUNK_VAR, CHUNK_DIR_VAR))?;
+        let chunk = Chunk::parse(&spec, PathBuf::from(dir))
+            .with_context(|| format!("invalid {} '{}'; expected INPUT/INDEX/COUNT", CHUNK_VAR, spec))?;
+        Ok(Some(chunk))
+    }
+
+    fn parse(spec: &str, dir: PathBuf) -> Option<Chunk> {
+        let mut parts = spec.split('/').map(|p| p.parse::<usize>().ok());
+        let (input, index, count) = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() || index >= count {
+            return None;
+        }
+        Some(Chunk {
+            input,
+            index,
+            count,
+            dir,
+        })
+    }
+
+    pub fn output_path(&self) -> PathBuf {
+        self.dir.join(format!("chunk-{}.pbn", self.index))
+    }
+
+    fn stats_path(&self) -> PathBuf {
+        self.dir.join(format!("chunk-{}.json", self.index))
+    }
+
+    /// Leave the worker's stats for the parent to collect.
+    pub fn write_stats(&self, stats: &ProcessingStats) -> Result<()> {
+        let path = self.stats_path();
+        std::fs::write(&path, serde_json::to_string(stats)?).with_context(|| format!("Failed to write {:?}", path))
+    }
+}
+
+/// Bid `input` (the `input_index`th `--input`) into `output` on up to
+/// `threads` workers. Returns None without starting any when the file has
+/// fewer than two deals to share out, so the caller bids it in-process.
+pub fn run(
+    input_index: usize,
+    input: &Path,
+    output: &Path,
+    threads: usize,
+    encoding: InputEncoding,
+    dry_run: bool,
+) -> Result<Option<ProcessingStats>> {
+    let deals = batch::deal_games(&InputFile::read(input, encoding)?.text).len();
+    let count = threads.min(deals);
+    if count < 2 {
+        return Ok(None);
+    }
+    info!("Bidding {} deals on {} workers", deals, count);
+
+    let dir = std::env::temp_dir().join(format!("bba-cli-{}-{}", std::process::id(), input_index));
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let chunks: Vec<Chunk> = (0..count)
+        .map(|index| Chunk {
+            input: input_index,
+            index,
+            count,
+            dir: dir.clone(),
+        })
+        .collect();
+
+    let finished = run_workers(&chunks).and_then(|stdouts| {
+        let mut stats = ProcessingStats::default();
+        for chunk in &chunks {
+            let path = chunk.stats_path();
+            let text = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+            stats.add(&serde_json::from_str(&text).with_context(|| format!("Invalid worker stats in {:?}", path))?);
+        }
+        if !dry_run {
+            merge_outputs(&chunks, output)?;
+        }
+        let mut stdout = std::io::stdout().lock();
+        for text in stdouts {
+            stdout.write_all(&text)?;
+        }
+        Ok(stats)
+    });
+    if let Err(e) = std::fs::remove_dir_all(&dir) {
+        debug!("Failed to remove {:?}: {}", dir, e);
+    }
+    finished.map(Some)
+}
+
+/// Start a worker per chunk and wait for them all. Returns each worker's
+/// stdout, in chunk order; their log output goes straight to stderr.
+fn run_workers(chunks: &[Chunk]) -> Result<Vec<Vec<u8>>> {
+    let exe = std::env::current_exe().context("Failed to locate the bba-cli executable")?;
+    let mut children = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        let mut command = Command::new(&exe);
+        command
+            .args(std::env::args_os().skip(1))
+            .env(CHUNK_VAR, format!("{}/{}/{}", chunk.input, chunk.index, chunk.count))
+            .env(CHUNK_DIR_VAR, &chunk.dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped());
+        // Keep the workers' start-up chatter out of the log unless asked for.
+        if std::env::var_os("RUST_LOG").is_none() && log::max_level() < log::LevelFilter::Debug {
+            command.env("RUST_LOG", "warn");
+        }
+        let child = command.spawn().context("Failed to start worker process")?;
+        children.push(child);
+    }
+
+    // Collect every worker's output concurrently, so none stalls on a full
+    // stdout pipe while an earlier one is being waited on.
+    let outputs: Vec<std::io::Result<std::process::Output>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = children
+            .into_iter()
+            .map(|child| scope.spawn(move || child.wait_with_output()))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().expect("worker wait thread panicked"))
+            .collect()
+    });
+
+    let mut stdouts = Vec::with_capacity(chunks.len());
+    for (chunk, output) in chunks.iter().zip(outputs) {
+        let output = output.context("Failed to wait for worker process")?;
+        if !output.status.success() {
+            anyhow::bail!("Worker {} of {} exited with {}", chunk.index + 1, chunk.count, output.status);
+        }
+        stdouts.push(output.stdout);
+    }
+    Ok(stdouts)
+}
+
+/// Join the workers' files into `output` (see `PbnJoiner`).
+fn merge_outputs(chunks: &[Chunk], output: &Path) -> Result<()> {
+    let file = std::fs::File::create(output).with_context(|| format!("Failed to create {:?}", output))?;
+    let mut joiner = PbnJoiner::new(std::io::BufWriter::new(file));
+    for chunk in chunks {
+        let path = chunk.output_path();
+        let text = std::fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+        joiner.append(&text)?;
+    }
+    joiner.into_inner().flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_chunk_spec() {
+        let dir = PathBuf::from("/tmp/x");
+        assert_eq!(
+            Chunk::parse("1/2/4", dir.clone()),
+            Some(Chunk {
+                input: 1,
+                index: 2,
+                count: 4,
+                dir: dir.clone(),
+            })
+        );
+        assert_eq!(Chunk::parse("0/4/4", dir.clone()), None);
+        assert_eq!(Chunk::parse("0/1", dir.clone()), None);
+        assert_eq!(Chunk::parse("0/1/2/3", dir.clone()), None);
+        assert_eq!(Chunk::parse("a/1/2", dir), None);
+    }
+}
@@ -0,0 +1,1589 @@
+//! Raw PBN helpers that sit alongside bridge-parsers.
+//!
+//! bridge-parsers gives us typed boards but drops the `%` escape lines and
+//! skips deals it can't parse, so the leading directive block and the raw
+//! per-game tags are recovered here by scanning the file text.
+
+use crate::formats::{self, Format};
+use anyhow::{Context, Result};
+use bridge_parsers::pbn::reader::{read_pbn, read_pbn_file};
+use bridge_parsers::{Board, Direction, Vulnerability};
+use std::fs::File;
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// PBN version written when the input doesn't declare one.
+pub const DEFAULT_VERSION: &str = "2.1";
+
+/// Recognized `%` directives from the leading escape block of a PBN file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PbnHeader {
+    /// Version from `% PBN x.y`, if declared.
+    pub version: Option<String>,
+    /// True if the file declares `% EXPORT` (strict export format).
+    pub export: bool,
+    /// Unrecognized `%` lines, kept verbatim (including the `%`).
+    pub passthrough: Vec<String>,
+}
+
+impl PbnHeader {
+    /// Parse the leading escape block: every `%` line before the first tag.
+    /// Blank lines in the block are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut header = PbnHeader::default();
+        for line in text.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let Some(directive) = trimmed.strip_prefix('%') else {
+                break;
+            };
+            let mut words = directive.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some("PBN"), Some(version), None) => header.version = Some(version.to_string()),
+                (Some("EXPORT"), None, None) => header.export = true,
+                _ => header.passthrough.push(trimmed.to_string()),
+            }
+        }
+        header
+    }
+
+    /// Export-format files must carry the mandatory tags, so games that
+    /// lack them are rejected instead of silently defaulted.
+    pub fn is_strict(&self) -> bool {
+        self.export
+    }
+
+    /// Version to write in the output's `% PBN` line.
+    pub fn output_version(&self) -> &str {
+        self.version.as_deref().unwrap_or(DEFAULT_VERSION)
+    }
+}
+
+/// How the bytes of an input PBN file are decoded (`--encoding`).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum InputEncoding {
+    /// UTF-8, falling back to Latin-1 if the file isn't valid UTF-8.
+    #[default]
+    Auto,
+    Utf8,
+    Latin1,
+}
+
+/// Whether `path` is `-`, which stands for stdin as an input and stdout as
+/// an output.
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// An input PBN file, decoded once and shared by every pass over its text.
+#[derive(Debug)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub text: String,
+    /// True if `text` isn't the file's bytes verbatim (Latin-1 decoded,
+    /// spots filled, or read from stdin, so there is no file).
+    transcoded: bool,
+}
+
+impl InputFile {
+    /// Read and decode `path`, or stdin if it is `-`. A file in another
+    /// format (LIN, GIB, dot, BRI, DUP, DLM or JSON) is converted to PBN (see
+    /// `formats`).
+    pub fn read(path: &Path, encoding: InputEncoding) -> Result<Self> {
+        let stdin = is_stdio(path);
+        let bytes = if stdin {
+            let mut bytes = Vec::new();
+            std::io::stdin().read_to_end(&mut bytes).context("Failed to read stdin")?;
+            bytes
+        } else {
+            std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?
+        };
+        if let Some(format) = Format::from_extension(path).filter(|f| f.is_binary()) {
+            log::info!("Converting {:?} from {} to PBN", path, format.name());
+            return Ok(InputFile {
+                path: path.to_path_buf(),
+                text: formats::boards::binary_to_pbn(format, &bytes),
+                transcoded: true,
+            });
+        }
+        let (text, transcoded) = match (encoding, String::from_utf8(bytes)) {
+            (InputEncoding::Latin1, Ok(text)) if text.is_ascii() => (text, false),
+            (InputEncoding::Latin1, result) => {
+                let bytes = result.map_or_else(|e| e.into_bytes(), String::into_bytes);
+                (decode_latin1(&bytes), true)
+            }
+            (_, Ok(text)) => (text, false),
+            (InputEncoding::Utf8, Err(e)) => {
+                anyhow::bail!("{:?} is not valid UTF-8 ({}); try --encoding latin1", path, e.utf8_error())
+            }
+            (InputEncoding::Auto, Err(e)) => {
+                log::warn!("{:?} is not valid UTF-8; reading it as Latin-1", path);
+                (decode_latin1(e.as_bytes()), true)
+            }
+        };
+        let (text, transcoded) = match Format::of_input(path, &text) {
+            Format::Pbn => (text, transcoded),
+            format => {
+                log::info!("Converting {:?} from {} to PBN", path, format.name());
+                (formats::to_pbn(format, &text), true)
+            }
+        };
+        Ok(InputFile {
+            path: path.to_path_buf(),
+            text,
+            transcoded: transcoded || stdin,
+        })
+    }
+
+    /// One game of `path`, read on its own by `--stream`.
+    pub fn from_game(path: &Path, game: RawGame) -> Self {
+        InputFile {
+            path: path.to_path_buf(),
+            text: game.text,
+            transcoded: true,
+        }
+    }
+
+    /// Typed boards from bridge-parsers. A transcoded input is parsed from
+    /// its decoded text, since the file's own bytes aren't what we read.
+    pub fn boards(&self) -> Result<Vec<Board>> {
+        let boards = if self.transcoded {
+            read_pbn(&self.text)
+        } else {
+            read_pbn_file(&self.path)
+        };
+        boards.context("Failed to parse PBN file")
+    }
+
+    /// `--fill-spots`: replace `x` spot cards in every Deal tag with real
+    /// cards (see `fill_spots`). Returns how many deals were filled; a deal
+    /// that can't be filled is left alone, to be rejected when bid.
+    pub fn fill_spots(&mut self) -> usize {
+        self.rewrite_deal_tags(|deal| {
+            if !deal.contains(['x', 'X']) {
+                return None;
+            }
+            fill_spots(deal)
+                .map_err(|e| log::warn!("Cannot fill spots in deal '{}': {}", deal, e))
+                .ok()
+        })
+    }
+
+    /// Tidy stray whitespace in every Deal tag (see `normalize_deal_spacing`).
+    /// Returns how many tags changed.
+    pub fn normalize_deal_spacing(&mut self) -> usize {
+        self.rewrite_deal_tags(|deal| normalize_deal_spacing(deal).filter(|tidy| tidy != deal))
+    }
+
+    /// Rewrite Deal tags with `f` (None keeps a tag) and count the rewrites.
+    fn rewrite_deal_tags(&mut self, mut f: impl FnMut(&str) -> Option<String>) -> usize {
+        let mut count = 0;
+        let text = map_deal_tags(&self.text, |deal| {
+            let new = f(deal);
+            count += new.is_some() as usize;
+            new
+        });
+        if count > 0 {
+            self.text = text;
+            self.transcoded = true;
+        }
+        count
+    }
+}
+
+/// Where an output goes: a file, or stdout for `-`.
+pub enum OutputFile {
+    File(File),
+    Stdout(std::io::Stdout),
+}
+
+impl OutputFile {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        if is_stdio(path) {
+            Ok(OutputFile::Stdout(std::io::stdout()))
+        } else {
+            File::create(path).map(OutputFile::File)
+        }
+    }
+
+    /// `--fsync`: push written data to disk. Nothing to do for stdout.
+    pub fn sync_data(&self) -> std::io::Result<()> {
+        match self {
+            OutputFile::File(file) => file.sync_data(),
+            OutputFile::Stdout(_) => Ok(()),
+        }
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            OutputFile::File(file) => file.write(buf),
+            OutputFile::Stdout(stdout) => stdout.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            OutputFile::File(file) => file.flush(),
+            OutputFile::Stdout(stdout) => stdout.flush(),
+        }
+    }
+}
+
+/// Latin-1 as browsers (and most Windows-era PBN tools) mean it: Windows-1252.
+fn decode_latin1(bytes: &[u8]) -> String {
+    let (text, _, _) = encoding_rs::WINDOWS_1252.decode(bytes);
+    text.into_owned()
+}
+
+/// One game as it appears in the file: its tag pairs in order, plus the
+/// exact source text (including trailing blank lines) for passthrough.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawGame {
+    pub tags: Vec<(String, String)>,
+    pub text: String,
+}
+
+impl RawGame {
+    /// Value of the first tag named `name`, if present.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Calls of the game's `[Auction]` section, normalized to the spellings
+    /// EPBot uses ("Pass", "X", "XX", "1NT"). Notes (`=1=`), comments,
+    /// `*` and `-` are dropped; `AP` expands to three passes. A seat letter
+    /// opening a line (`N 1H`, the vertical layout) is skipped. Empty when
+    /// the game has no auction.
+    pub fn auction_calls(&self) -> Vec<String> {
+        self.auction_calls_under("Auction")
+    }
+
+    /// Like `auction_calls`, for an auction written under another tag name
+    /// (`--auction-tag`).
+    pub fn auction_calls_under(&self, tag: &str) -> Vec<String> {
+        let mut calls = Vec::new();
+        let mut in_auction = false;
+        for line in self.text.lines() {
+            let line = line.trim();
+            if line.starts_with('[') {
+                in_auction = line_tags(line).last().is_some_and(|(_, (name, _))| name == tag);
+                continue;
+            }
+            if !in_auction || line.starts_with('%') {
+                continue;
+            }
+            let line = line.split(';').next().unwrap_or("");
+            let mut in_comment = false;
+            for (i, token) in line.split_whitespace().enumerate() {
+                if i == 0 && matches!(token, "N" | "E" | "S" | "W") {
+                    continue;
+                }
+                if token.starts_with('{') {
+                    in_comment = true;
+                }
+                if in_comment {
+                    in_comment = !token.ends_with('}');
+                    continue;
+                }
+                if token.eq_ignore_ascii_case("AP") {
+                    calls.extend(std::iter::repeat_n("Pass".to_string(), 3));
+                } else if let Some(call) = normalize_call(token) {
+                    calls.push(call);
+                }
+            }
+        }
+        calls
+    }
+}
+
+/// One auction token in EPBot's spelling, or None for tokens that aren't
+/// calls (notes, `*`, `-`, NAGs). `AP` is left to the caller.
+pub fn normalize_call(token: &str) -> Option<String> {
+    let token = token.trim_end_matches(['!', '?']);
+    match token.to_ascii_uppercase().as_str() {
+        "P" | "PASS" => Some("Pass".to_string()),
+        "X" | "D" => Some("X".to_string()),
+        "XX" | "R" => Some("XX".to_string()),
+        "*" | "-" | "+" | "" => None,
+        t if t.starts_with('=') || t.starts_with('$') => None,
+        t if t.len() == 2 && t.ends_with('N') => Some(format!("{}T", t)),
+        t => Some(t.to_string()),
+    }
+}
+
+/// An auction is over after three passes following a call, or four passes.
+pub fn auction_complete(calls: &[String]) -> bool {
+    calls.len() >= 4 && calls[calls.len() - 3..].iter().all(|c| c == "Pass")
+}
+
+/// Whether `call` (in EPBot's spelling) may be made after `calls`: a bid must
+/// outrank the last bid, a double needs an undoubled opposing bid, and a
+/// redouble an opposing double.
+pub fn check_call(calls: &[String], call: &str) -> std::result::Result<(), String> {
+    let code = epbot_core::try_encode_bid(call)?;
+    let last = calls.iter().enumerate().rev().find(|(_, c)| *c != "Pass");
+    // Calls an odd number of places back were made by an opponent.
+    let by_opponent = |i: usize| (calls.len() - i) % 2 == 1;
+    match (code, last) {
+        (0, _) => Ok(()),
+        (1, Some((i, c))) if c != "X" && c != "XX" && by_opponent(i) => Ok(()),
+        (1, _) => Err("nothing to double".to_string()),
+        (2, Some((i, c))) if c == "X" && by_opponent(i) => Ok(()),
+        (2, _) => Err("nothing to redouble".to_string()),
+        _ => {
+            let last_bid = calls.iter().rev().find(|c| !matches!(c.as_str(), "Pass" | "X" | "XX"));
+            match last_bid {
+                Some(bid) if epbot_core::encode_bid(bid) >= code => {
+                    Err(format!("{} is not higher than {}", call, bid))
+                }
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+/// A PBN file split into games without losing a byte: concatenating
+/// `preamble` and every game's `text` reproduces the input exactly.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RawFile {
+    /// Text before the first game (typically the `%` header block).
+    pub preamble: String,
+    pub games: Vec<RawGame>,
+    /// Lines that start like a tag but aren't one; they count as plain text.
+    pub malformed: Vec<TagError>,
+}
+
+impl RawFile {
+    /// Split PBN text into games. Games are separated by blank lines; a block
+    /// without any tag (e.g. the leading `%` header, or a stray comment block)
+    /// is not a game and stays attached to whatever precedes it. A tag value
+    /// may run over several lines (see `TagScanner`).
+    pub fn parse(text: &str) -> Self {
+        let mut file = RawFile::default();
+        let mut block = String::new();
+        let mut block_tags = Vec::new();
+        let mut in_trailing_blanks = false;
+        let mut scanner = TagScanner::default();
+
+        for (n, line) in text.split_inclusive('\n').enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                in_trailing_blanks = true;
+            } else if in_trailing_blanks && !scanner.in_value() {
+                file.push_block(&mut block, &mut block_tags);
+                in_trailing_blanks = false;
+            }
+            for result in scanner.feed(line, n + 1) {
+                match result {
+                    Ok(tag) => block_tags.push(tag),
+                    Err(e) => file.malformed.push(e),
+                }
+            }
+            block.push_str(line);
+        }
+        file.malformed.extend(scanner.finish());
+        file.push_block(&mut block, &mut block_tags);
+        file
+    }
+
+    fn push_block(&mut self, block: &mut String, tags: &mut Vec<(String, String)>) {
+        if tags.is_empty() {
+            match self.games.last_mut() {
+                Some(game) => game.text.push_str(block),
+                None => self.preamble.push_str(block),
+            }
+        } else {
+            self.games.push(RawGame {
+                tags: std::mem::take(tags),
+                text: block.clone(),
+            });
+        }
+        block.clear();
+    }
+
+    /// Reassemble the original text.
+    pub fn to_text(&self) -> String {
+        let mut out = self.preamble.clone();
+        for game in &self.games {
+            out.push_str(&game.text);
+        }
+        out
+    }
+}
+
+/// Games read one at a time from a PBN stream, split as `RawFile::parse`
+/// splits them, so a large file never has to be held in memory at once.
+///
+/// A game is yielded once the next one starts (or the input ends), since a
+/// tagless block after it, such as a commentary block, still belongs to it.
+/// A game holding a line that isn't valid UTF-8 is yielded as an error and
+/// iteration carries on with the next game, unless the reader decodes such
+/// lines as Latin-1 (`with_encoding`); a read error ends iteration.
+pub struct PbnReader<R> {
+    input: R,
+    encoding: InputEncoding,
+    /// Whether a line has been read as Latin-1 under `InputEncoding::Auto`.
+    fell_back: bool,
+    line: usize,
+    preamble: String,
+    /// The last complete block's game, waiting for any tagless blocks that
+    /// follow it.
+    pending: Option<Result<RawGame>>,
+    block: String,
+    block_tags: Vec<(String, String)>,
+    block_error: Option<anyhow::Error>,
+    in_trailing_blanks: bool,
+    scanner: TagScanner,
+    done: bool,
+}
+
+impl<R: BufRead> PbnReader<R> {
+    pub fn new(input: R) -> Self {
+        Self::with_encoding(input, InputEncoding::Utf8)
+    }
+
+    /// A reader decoding lines as `--encoding` says. `Auto` decides line by
+    /// line: a line that isn't valid UTF-8 is read as Latin-1.
+    pub fn with_encoding(input: R, encoding: InputEncoding) -> Self {
+        PbnReader {
+            input,
+            encoding,
+            fell_back: false,
+            line: 0,
+            preamble: String::new(),
+            pending: None,
+            block: String::new(),
+            block_tags: Vec::new(),
+            block_error: None,
+            in_trailing_blanks: false,
+            scanner: TagScanner::default(),
+            done: false,
+        }
+    }
+
+    /// Text before the first game; complete once the first game is yielded.
+    pub fn preamble(&self) -> &str {
+        &self.preamble
+    }
+
+    /// Add one line to the current block. Returns the previous game if the
+    /// line starts a new block.
+    fn push_line(&mut self, bytes: Vec<u8>) -> Option<Result<RawGame>> {
+        self.line += 1;
+        let line = match (self.encoding, String::from_utf8(bytes)) {
+            (InputEncoding::Latin1, Ok(line)) if line.is_ascii() => line,
+            (InputEncoding::Latin1, result) => {
+                decode_latin1(&result.map_or_else(|e| e.into_bytes(), String::into_bytes))
+            }
+            (_, Ok(line)) => line,
+            (InputEncoding::Auto, Err(e)) => {
+                if !std::mem::replace(&mut self.fell_back, true) {
+                    log::warn!("Line {} is not valid UTF-8; reading such lines as Latin-1", self.line);
+                }
+                decode_latin1(e.as_bytes())
+            }
+            (InputEncoding::Utf8, Err(e)) => {
+                if self.block_error.is_none() {
+                    self.block_error = Some(anyhow::anyhow!("line {}: not valid UTF-8", self.line));
+                }
+                String::from_utf8_lossy(e.as_bytes()).into_owned()
+            }
+        };
+        let trimmed = line.trim();
+        let mut finished = None;
+        if trimmed.is_empty() {
+            self.in_trailing_blanks = true;
+        } else if self.in_trailing_blanks && !self.scanner.in_value() {
+            finished = self.end_block();
+            self.in_trailing_blanks = false;
+        }
+        for result in self.scanner.feed(&line, self.line) {
+            match result {
+                Ok(tag) => self.block_tags.push(tag),
+                Err(e) => log::debug!("Not a tag: {}", e),
+            }
+        }
+        self.block.push_str(&line);
+        finished
+    }
+
+    /// Close the current block (see `RawFile::push_block`). A block with
+    /// tags is a new game, and the previous one is returned.
+    fn end_block(&mut self) -> Option<Result<RawGame>> {
+        let block = std::mem::take(&mut self.block);
+        let tags = std::mem::take(&mut self.block_tags);
+        let error = self.block_error.take();
+        if !tags.is_empty() {
+            let game = match error {
+                Some(e) => Err(e),
+                None => Ok(RawGame { tags, text: block }),
+            };
+            return self.pending.replace(game);
+        }
+        match (&mut self.pending, error) {
+            (Some(Ok(_)) | None, Some(e)) => self.pending = Some(Err(e)),
+            (Some(Ok(game)), None) => game.text.push_str(&block),
+            (None, None) => self.preamble.push_str(&block),
+            (Some(Err(_)), _) => {}
+        }
+        None
+    }
+}
+
+impl<R: BufRead> Iterator for PbnReader<R> {
+    type Item = Result<RawGame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let mut bytes = Vec::new();
+            let finished = match self.input.read_until(b'\n', &mut bytes) {
+                Ok(0) => {
+                    self.done = true;
+                    if let Some(e) = self.scanner.finish() {
+                        log::debug!("Not a tag: {}", e);
+                    }
+                    self.end_block()
+                }
+                Ok(_) => self.push_line(bytes),
+                Err(e) => {
+                    self.done = true;
+                    self.pending = None;
+                    let error = anyhow::Error::new(e).context(format!("Failed to read PBN after line {}", self.line));
+                    return Some(Err(error));
+                }
+            };
+            if finished.is_some() {
+                return finished;
+            }
+        }
+        self.pending.take()
+    }
+}
+
+/// Joins PBN files written by separate runs (`--threads` workers) into one: the first file whole, then the games of each later
+/// one without its leading `%` header, keeping a blank line between games.
+pub struct PbnJoiner<W> {
+    out: W,
+    first: bool,
+    has_games: bool,
+}
+
+impl<W: Write> PbnJoiner<W> {
+    pub fn new(out: W) -> Self {
+        PbnJoiner {
+            out,
+            first: true,
+            has_games: false,
+        }
+    }
+
+    pub fn append(&mut self, text: &str) -> std::io::Result<()> {
+        let mut games = text;
+        if std::mem::take(&mut self.first) {
+            self.has_games = text.lines().any(|l| !l.trim().is_empty() && !l.starts_with('%'));
+            return self.out.write_all(text.as_bytes());
+        }
+        while games.starts_with('%') {
+            games = games.split_once('\n').map_or("", |(_, rest)| rest);
+        }
+        if games.trim().is_empty() {
+            return Ok(());
+        }
+        if self.has_games {
+            self.out.write_all(b"\n")?;
+        }
+        self.has_games = true;
+        self.out.write_all(games.as_bytes())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Which tags of games copied from the input are written back
+/// (`--keep-tags` / `--drop-tags`). Tag names are case-sensitive.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum TagFilter {
+    #[default]
+    All,
+    Keep(Vec<String>),
+    Drop(Vec<String>),
+}
+
+impl TagFilter {
+    pub fn allows(&self, name: &str) -> bool {
+        match self {
+            TagFilter::All => true,
+            TagFilter::Keep(names) => names.iter().any(|n| n == name),
+            TagFilter::Drop(names) => !names.iter().any(|n| n == name),
+        }
+    }
+
+    /// `text` without the disallowed tags. A removed tag takes its section
+    /// data (e.g. the rows of an `[OptimumResultTable]`) with it; comments,
+    /// `%` lines and blank lines stay. Of a line with several tags, only
+    /// the allowed ones are kept.
+    pub fn apply(&self, text: &str) -> String {
+        if *self == TagFilter::All {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut dropping = false;
+        for line in text.split_inclusive('\n') {
+            let tags = line_tags(line);
+            if let Some((_, (last, _))) = tags.last() {
+                // Section data after the line belongs to its last tag.
+                dropping = !self.allows(last);
+                let kept: Vec<&str> = tags
+                    .iter()
+                    .filter(|(_, (name, _))| self.allows(name))
+                    .map(|(range, _)| &line[range.clone()])
+                    .collect();
+                if kept.len() == tags.len() {
+                    out.push_str(line);
+                } else if !kept.is_empty() {
+                    out.push_str(&kept.join(" "));
+                    out.push_str(&line[line.trim_end_matches(['\r', '\n']).len()..]);
+                }
+                continue;
+            }
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with(['{', '%', ';']) {
+                dropping = false;
+            }
+            if !dropping {
+                out.push_str(line);
+            }
+        }
+        out
+    }
+}
+
+/// Split PBN text into games (see `RawFile::parse`).
+pub fn split_games(text: &str) -> Vec<RawGame> {
+    RawFile::parse(text).games
+}
+
+/// The tags of a line of nothing but `[Name "value"]` tags, perhaps ending
+/// in a `;` comment (see `lex_tags`), each with its byte range in `line`.
+/// Empty for any other line.
+pub(crate) fn line_tags(line: &str) -> Vec<SpannedTag> {
+    match lex_tags(line) {
+        (tags, None) => tags,
+        _ => Vec::new(),
+    }
+}
+
+/// A line that starts like a tag but isn't one, and where it goes wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagError {
+    /// 1-based line and column.
+    pub line: usize,
+    pub column: usize,
+    pub message: &'static str,
+}
+
+impl TagError {
+    /// The error at byte `offset` of `text`, a tag starting on line `line`.
+    fn at(text: &str, offset: usize, line: usize, message: &'static str) -> Self {
+        let before = &text[..offset];
+        let start = before.rfind('\n').map_or(0, |i| i + 1);
+        TagError {
+            line: line + before.matches('\n').count(),
+            column: before[start..].chars().count() + 1,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for TagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Why `lex_tag` couldn't read a tag.
+#[derive(Debug, PartialEq, Eq)]
+enum TagLexError {
+    /// The text ends inside the value; more lines may close it.
+    Incomplete,
+    /// Not a tag, going wrong at this byte offset.
+    At(usize, &'static str),
+}
+
+/// Lex the tag pair at the start of `text`: `[`, a name of letters, digits
+/// and `_`, a value in double quotes, and `]`, with optional whitespace
+/// between them. In the value `\"` stands for a quote and `\\` for a
+/// backslash (other backslashes are kept as they are), and line breaks are
+/// part of the value. Returns the name, the unescaped value and the text
+/// after the `]`.
+fn lex_tag(text: &str) -> std::result::Result<((String, String), &str), TagLexError> {
+    use TagLexError::{At, Incomplete};
+    // Something missing at the end is reported after the last character.
+    let end = text.trim_end().len();
+    let mut chars = text.char_indices().peekable();
+    let skip_spaces = |chars: &mut std::iter::Peekable<std::str::CharIndices>| {
+        while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    };
+    let expect = |chars: &mut std::iter::Peekable<std::str::CharIndices>, want: char, message| {
+        skip_spaces(chars);
+        match chars.next() {
+            Some((_, c)) if c == want => Ok(()),
+            Some((i, _)) => Err(At(i, message)),
+            None => Err(At(end, message)),
+        }
+    };
+
+    expect(&mut chars, '[', "expected '['")?;
+    skip_spaces(&mut chars);
+    let mut name = String::new();
+    while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_') {
+        name.push(c);
+    }
+    if name.is_empty() {
+        return Err(At(chars.peek().map_or(end, |&(i, _)| i), "expected a tag name"));
+    }
+    expect(&mut chars, '"', "expected '\"' to open the tag value")?;
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            None => return Err(Incomplete),
+            Some((_, '"')) => break,
+            Some((_, '\\')) => match chars.next() {
+                None => return Err(Incomplete),
+                Some((_, c @ ('"' | '\\'))) => value.push(c),
+                Some((_, c)) => {
+                    value.push('\\');
+                    value.push(c);
+                }
+            },
+            Some((_, '\r')) => {}
+            Some((_, c)) => value.push(c),
+        }
+    }
+    expect(&mut chars, ']', "expected ']' after the tag value")?;
+    let rest = chars.peek().map_or("", |&(i, _)| &text[i..]);
+    Ok(((name, value), rest))
+}
+
+/// A tag with its byte range in the text it was lexed from.
+type SpannedTag = (std::ops::Range<usize>, (String, String));
+
+/// Lex the tags of `text` one after another, as PBN allows several on a
+/// line, up to its end or a `;` comment. Returns the tags read and why
+/// lexing stopped short of that, if it did (with the offset into `text`).
+fn lex_tags(text: &str) -> (Vec<SpannedTag>, Option<TagLexError>) {
+    let mut tags: Vec<SpannedTag> = Vec::new();
+    let mut start = 0;
+    loop {
+        let rest = text[start..].trim_start();
+        let from = text.len() - rest.len();
+        if rest.is_empty() || (!tags.is_empty() && rest.starts_with(';')) {
+            return (tags, None);
+        }
+        if !tags.is_empty() && !rest.starts_with('[') {
+            return (tags, Some(TagLexError::At(from, "unexpected text after the tag")));
+        }
+        match lex_tag(rest) {
+            Ok((tag, after)) => {
+                start = text.len() - after.len();
+                tags.push((from..start, tag));
+            }
+            Err(TagLexError::At(offset, message)) => return (tags, Some(TagLexError::At(from + offset, message))),
+            Err(TagLexError::Incomplete) => return (tags, Some(TagLexError::Incomplete)),
+        }
+    }
+}
+
+/// Reads tags from PBN text a line at a time. A line starting with `[`
+/// holds one or more tags; if the last one's value is still open at the
+/// end of the line, the following lines continue it, up to a blank line.
+#[derive(Debug, Default)]
+struct TagScanner {
+    /// The lines of a tag whose value is still open.
+    pending: String,
+    /// The 1-based line `pending` starts on.
+    pending_line: usize,
+    /// Where the open tag starts in `pending`, after the tags before it on
+    /// its first line.
+    start: usize,
+}
+
+impl TagScanner {
+    /// Whether a tag value is open, so the next line continues it.
+    fn in_value(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Feed the 1-based line `number`. Returns the tags it finishes, then
+    /// why the rest of the line isn't a tag, if it isn't; nothing for
+    /// other lines and for a value that goes on.
+    fn feed(&mut self, line: &str, number: usize) -> Vec<std::result::Result<(String, String), TagError>> {
+        if !self.in_value() {
+            if !line.trim_start().starts_with('[') {
+                return Vec::new();
+            }
+            self.pending_line = number;
+        } else if line.trim().is_empty() {
+            return self.finish().map(Err).into_iter().collect();
+        }
+        self.pending.push_str(line);
+        let (tags, error) = lex_tags(&self.pending[self.start..]);
+        let end = tags.last().map_or(0, |(range, _)| range.end);
+        let mut results: Vec<_> = tags.into_iter().map(|(_, tag)| Ok(tag)).collect();
+        match error {
+            Some(TagLexError::Incomplete) => {
+                self.start += end;
+                return results;
+            }
+            Some(TagLexError::At(offset, message)) => {
+                results.push(Err(TagError::at(&self.pending, self.start + offset, self.pending_line, message)))
+            }
+            None => {}
+        }
+        self.pending.clear();
+        self.start = 0;
+        results
+    }
+
+    /// The error for a tag value still open at the end of the input (or a
+    /// blank line).
+    fn finish(&mut self) -> Option<TagError> {
+        if !self.in_value() {
+            return None;
+        }
+        let text = std::mem::take(&mut self.pending);
+        self.start = 0;
+        let end = text.trim_end().len();
+        Some(TagError::at(&text, end, self.pending_line, "tag value has no closing '\"'"))
+    }
+}
+
+/// Validate a PBN Deal tag value ("N:S.H.D.C S.H.D.C S.H.D.C S.H.D.C"):
+/// four complete 13-card hands holding 52 distinct cards.
+pub fn check_deal(deal: &str) -> std::result::Result<(), String> {
+    let (seat, hands) = deal
+        .split_once(':')
+        .ok_or_else(|| "missing ':' after first seat".to_string())?;
+    if !matches!(seat.trim(), "N" | "E" | "S" | "W") {
+        return Err(format!("invalid first seat '{}'", seat.trim()));
+    }
+    let hands: Vec<&str> = hands.split_whitespace().collect();
+    if hands.len() != 4 {
+        return Err(format!("expected 4 hands, got {}", hands.len()));
+    }
+    let mut seen = [[false; 13]; 4];
+    for (h, hand) in hands.iter().enumerate() {
+        let suits: Vec<&str> = hand.split('.').collect();
+        if suits.len() != 4 {
+            return Err(format!("hand {} has {} suits, expected 4", h + 1, suits.len()));
+        }
+        let mut count = 0;
+        for (s, suit) in suits.iter().enumerate() {
+            for c in suit.chars() {
+                if c.eq_ignore_ascii_case(&'x') {
+                    return Err(format!(
+                        "unknown spot card '{}' in hand {} (--fill-spots assigns real cards)",
+                        c,
+                        h + 1
+                    ));
+                }
+                let r = RANKS
+                    .find(c.to_ascii_uppercase())
+                    .ok_or_else(|| format!("invalid card '{}' in hand {}", c, h + 1))?;
+                if seen[s][r] {
+                    return Err(format!("duplicate card '{}' in hand {}", c, h + 1));
+                }
+                seen[s][r] = true;
+                count += 1;
+            }
+        }
+        if count != 13 {
+            return Err(format!("hand {} has {} cards, expected 13", h + 1, count));
+        }
+    }
+    Ok(())
+}
+
+const SEATS: [&str; 4] = ["N", "E", "S", "W"];
+
+/// A Deal tag value with stray whitespace removed: none around the seat
+/// colon or inside a hand, and single spaces between the four hands
+/// ("N: AKQ. JT9.876.5432  ..." becomes "N:AKQ.JT9.876.5432 ..."). Spaces
+/// can't tell hands apart when a void sits next to them, so hands are
+/// found by count instead: each is 13 cards and three dots. None if there's
+/// no seat colon or the cards don't split into four such hands.
+pub fn normalize_deal_spacing(deal: &str) -> Option<String> {
+    let (seat, cards) = deal.split_once(':')?;
+    let mut hands = Vec::with_capacity(4);
+    let mut hand = String::new();
+    let (mut dots, mut count) = (0, 0);
+    for c in cards.chars().filter(|c| !c.is_whitespace()) {
+        hand.push(c);
+        if c == '.' {
+            dots += 1;
+        } else {
+            count += 1;
+        }
+        if dots == 3 && count == 13 {
+            hands.push(std::mem::take(&mut hand));
+            (dots, count) = (0, 0);
+        }
+    }
+    if hands.len() != 4 || !hand.is_empty() {
+        return None;
+    }
+    Some(format!("{}:{}", seat.trim(), hands.join(" ")))
+}
+
+/// Card ranks, highest first.
+const RANKS: &str = "AKQJT98765432";
+
+/// Replace every `x` (or `X`) spot card in a Deal tag value with a real card.
+///
+/// Rule: within each suit, the ranks no hand names are handed out lowest
+/// first, to the x's in the order they appear in the tag (first listed hand
+/// first). So `AKxxx` in the first hand becomes `AK432` when nobody holds
+/// those spots. Each suit is then written highest card first. Fails if a
+/// suit has more x's than free ranks; other problems are left for
+/// `check_deal`.
+pub fn fill_spots(deal: &str) -> std::result::Result<String, String> {
+    let (seat, hands) = deal
+        .split_once(':')
+        .ok_or_else(|| "missing ':' after first seat".to_string())?;
+    let mut hands: Vec<Vec<String>> = hands
+        .split_whitespace()
+        .map(|hand| hand.split('.').map(str::to_string).collect())
+        .collect();
+
+    for suit in 0..4 {
+        let holding = |hand: &Vec<String>| hand.get(suit).cloned().unwrap_or_default();
+        let named: String = hands.iter().map(holding).collect::<String>().to_ascii_uppercase();
+        let mut free = RANKS.chars().rev().filter(|&r| !named.contains(r));
+        for hand in hands.iter_mut() {
+            let Some(cards) = hand.get_mut(suit) else { continue };
+            if !cards.contains(['x', 'X']) {
+                continue;
+            }
+            let mut filled = String::with_capacity(cards.len());
+            for c in cards.chars() {
+                if c.eq_ignore_ascii_case(&'x') {
+                    let suit_name = ["spades", "hearts", "diamonds", "clubs"][suit];
+                    let spot = free
+                        .next()
+                        .ok_or_else(|| format!("more x spots than free cards in {}", suit_name))?;
+                    filled.push(spot);
+                } else {
+                    filled.push(c);
+                }
+            }
+            let mut sorted: Vec<char> = filled.chars().collect();
+            sorted.sort_by_key(|c| RANKS.find(c.to_ascii_uppercase()));
+            *cards = sorted.into_iter().collect();
+        }
+    }
+
+    let hands: Vec<String> = hands.iter().map(|hand| hand.join(".")).collect();
+    Ok(format!("{}:{}", seat, hands.join(" ")))
+}
+
+/// Rotate a Deal tag value to start from North ("E:e s w n" becomes
+/// "N:n e s w"), keeping every hand at its seat. None if the first seat or
+/// the hand count is invalid.
+pub fn deal_from_north(deal: &str) -> Option<String> {
+    let (seat, hands) = deal.split_once(':')?;
+    let first = SEATS.iter().position(|s| *s == seat.trim())?;
+    let hands: Vec<&str> = hands.split_whitespace().collect();
+    if hands.len() != 4 {
+        return None;
+    }
+    let rotated: Vec<&str> = (0..4).map(|seat| hands[(seat + 4 - first) % 4]).collect();
+    Some(format!("N:{}", rotated.join(" ")))
+}
+
+/// `text` with every `[Deal]` tag rewritten by `deal_from_north`
+/// (`--canonical-first-seat`). Deal tags that don't parse are left alone.
+pub fn canonicalize_deal_tags(text: &str) -> String {
+    map_deal_tags(text, deal_from_north)
+}
+
+/// The tags of PBN's mandatory set that name the game rather than describe
+/// the deal or its play, in export order.
+pub const ROSTER_TAGS: [&str; 8] = ["Event", "Site", "Date", "Board", "West", "North", "East", "South"];
+
+/// A game's `text` with each `ROSTER_TAGS` tag it lacks added as `"?"`
+/// (unknown, in the PBN spec) before its first tag. A game with all of them
+/// is returned as is.
+pub fn complete_roster_tags(text: &str) -> String {
+    let tags: Vec<SpannedTag> = text.lines().flat_map(line_tags).collect();
+    let missing: Vec<&str> = ROSTER_TAGS.into_iter().filter(|t| !tags.iter().any(|(_, (name, _))| name == t)).collect();
+    let first = text.split_inclusive('\n').position(|l| !line_tags(l).is_empty());
+    let Some(first) = first.filter(|_| !missing.is_empty()) else {
+        return text.to_string();
+    };
+    let mut out = String::with_capacity(text.len() + 16 * missing.len());
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        if i == first {
+            let eol = if line.ends_with("\r\n") { "\r\n" } else { "\n" };
+            for tag in &missing {
+                out.push_str(&format!("[{} \"?\"]{}", tag, eol));
+            }
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// `text` with each `[Deal]` tag value replaced by `f(value)`; tags where
+/// `f` returns None, and all other lines, are kept byte for byte.
+fn map_deal_tags(text: &str, mut f: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let mut rewritten = line.to_string();
+        // From the right, so the earlier ranges still hold.
+        for (range, (name, value)) in line_tags(line).into_iter().rev() {
+            if name != "Deal" {
+                continue;
+            }
+            if let Some(deal) = f(&value) {
+                rewritten.replace_range(range, &format!("[Deal \"{}\"]", deal));
+            }
+        }
+        out.push_str(&rewritten);
+    }
+    out
+}
+
+/// A calendar date from a PBN `[Date]` tag (`YYYY.MM.DD`). Ordered
+/// chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PbnDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl PbnDate {
+    /// Parse `YYYY.MM.DD` (PBN) or `YYYY-MM-DD` (ISO). Dates with unknown
+    /// parts (`2024.??.??`) or out-of-range fields return None.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split(['.', '-']);
+        let year = parts.next()?.parse().ok()?;
+        let month: u8 = parts.next()?.parse().ok()?;
+        let day: u8 = parts.next()?.parse().ok()?;
+        if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+        Some(PbnDate { year, month, day })
+    }
+}
+
+impl std::fmt::Display for PbnDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}.{:02}.{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A `[Board]` tag value as a board number: plain decimal digits, with
+/// surrounding whitespace allowed. None for board 0, signs, anything else
+/// that isn't a number, and numbers too large for a `u32`, so callers treat
+/// such boards as having no number.
+pub fn parse_board_number(text: &str) -> Option<u32> {
+    let text = text.trim();
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok().filter(|&n| n > 0)
+}
+
+/// Dealer of board `number` under the standard rotation (board 1 North,
+/// 2 East, ...), for any board number. None for board 0.
+pub fn board_dealer(number: u32) -> Option<Direction> {
+    let seats = [Direction::North, Direction::East, Direction::South, Direction::West];
+    number.checked_sub(1).map(|n| seats[(n % 4) as usize])
+}
+
+/// Vulnerability of board `number` under the standard 16-board cycle, for
+/// any board number. None for board 0.
+pub fn board_vulnerability(number: u32) -> Option<Vulnerability> {
+    use Vulnerability::*;
+    const CYCLE: [Vulnerability; 16] = [
+        None, NorthSouth, EastWest, Both, NorthSouth, EastWest, Both, None,
+        EastWest, Both, None, NorthSouth, Both, None, NorthSouth, EastWest,
+    ];
+    number.checked_sub(1).map(|n| CYCLE[(n % 16) as usize])
+}
+
+/// The PBN `[Dealer]` letter of board `number` (see `board_dealer`).
+pub fn board_dealer_tag(number: u32) -> char {
+    match board_dealer(number) {
+        Some(Direction::East) => 'E',
+        Some(Direction::South) => 'S',
+        Some(Direction::West) => 'W',
+        _ => 'N',
+    }
+}
+
+/// The PBN `[Vulnerable]` value of board `number` (see
+/// `board_vulnerability`).
+pub fn board_vulnerable_tag(number: u32) -> &'static str {
+    match board_vulnerability(number) {
+        Some(Vulnerability::NorthSouth) => "NS",
+        Some(Vulnerability::EastWest) => "EW",
+        Some(Vulnerability::Both) => "All",
+        _ => "None",
+    }
+}
+
+/// Parse a `[DoubleDummyTricks]` tag value: 20 hex digits giving tricks for
+/// declarers N, S, E, W, each in strain order NT, S, H, D, C (the layout
+/// written by Dealer and BridgeComposer).
+///
+/// Returns `tricks[declarer][strain]` with declarer 0=N, 1=E, 2=S, 3=W and
+/// strain C, D, H, S, NT, matching `score::par_score_ns`.
+pub fn parse_dd_tricks(text: &str) -> Option<[[u8; 5]; 4]> {
+    let digits: Vec<u8> = text
+        .trim()
+        .chars()
+        .map(|c| c.to_digit(16).filter(|&d| d <= 13).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    if digits.len() != 20 {
+        return None;
+    }
+    let mut tricks = [[0u8; 5]; 4];
+    for (row, seat) in [0, 2, 1, 3].into_iter().enumerate() {
+        for (col, strain) in [4, 3, 2, 1, 0].into_iter().enumerate() {
+            tricks[seat][strain] = digits[row * 5 + col];
+        }
+    }
+    Some(tricks)
+}
+
+/// Format tricks as a `[DoubleDummyTricks]` tag value; the inverse of
+/// `parse_dd_tricks`.
+pub fn format_dd_tricks(tricks: &[[u8; 5]; 4]) -> String {
+    let mut out = String::with_capacity(20);
+    for seat in [0, 2, 1, 3] {
+        for strain in [4, 3, 2, 1, 0] {
+            out.push(char::from_digit(u32::from(tricks[seat][strain]), 16).unwrap_or('?'));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calls(s: &str) -> Vec<String> {
+        s.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn auction_ends_after_three_passes() {
+        assert!(!auction_complete(&calls("Pass Pass Pass")));
+        assert!(auction_complete(&calls("Pass Pass Pass Pass")));
+        assert!(!auction_complete(&calls("1C Pass Pass")));
+        assert!(auction_complete(&calls("1C Pass Pass Pass")));
+        assert!(!auction_complete(&calls("1C X Pass Pass XX")));
+    }
+
+    #[test]
+    fn checks_calls_against_the_auction() {
+        assert!(check_call(&[], "1C").is_ok());
+        assert!(check_call(&calls("1NT"), "2C").is_ok());
+        assert!(check_call(&calls("1NT"), "1S").is_err());
+        assert!(check_call(&calls("1NT"), "1NT").is_err());
+        assert!(check_call(&calls("1NT"), "X").is_ok());
+        assert!(check_call(&calls("1NT Pass"), "X").is_err());
+        assert!(check_call(&calls("1NT Pass Pass"), "X").is_ok());
+        assert!(check_call(&calls("1NT X"), "X").is_err());
+        assert!(check_call(&calls("1NT X"), "XX").is_ok());
+        assert!(check_call(&calls("1NT X Pass"), "XX").is_err());
+        assert!(check_call(&[], "X").is_err());
+        assert!(check_call(&calls("1NT X XX"), "2C").is_ok());
+    }
+
+    #[test]
+    fn derives_dealer_and_vulnerability_from_board_number() {
+        assert_eq!(board_dealer(1), Some(Direction::North));
+        assert_eq!(board_dealer(7), Some(Direction::South));
+        assert_eq!(board_dealer(0), None);
+        assert_eq!(board_vulnerability(1), Some(Vulnerability::None));
+        assert_eq!(board_vulnerability(13), Some(Vulnerability::Both));
+        assert_eq!(board_vulnerability(18), Some(Vulnerability::NorthSouth));
+        assert_eq!(board_vulnerability(0), None);
+    }
+
+    #[test]
+    fn board_derivation_wraps_at_cycle_boundaries() {
+        for (number, dealer, vul) in [
+            (16, Direction::West, Vulnerability::EastWest),
+            (17, Direction::North, Vulnerability::None),
+            (32, Direction::West, Vulnerability::EastWest),
+            (33, Direction::North, Vulnerability::None),
+            (u32::MAX, Direction::South, Vulnerability::NorthSouth),
+        ] {
+            assert_eq!(board_dealer(number), Some(dealer), "board {number}");
+            assert_eq!(board_vulnerability(number), Some(vul), "board {number}");
+        }
+    }
+
+    #[test]
+    fn parses_board_numbers() {
+        assert_eq!(parse_board_number("1"), Some(1));
+        assert_eq!(parse_board_number(" 17 "), Some(17));
+        assert_eq!(parse_board_number("007"), Some(7));
+        assert_eq!(parse_board_number("4294967295"), Some(u32::MAX));
+        for bad in ["", "0", "000", "-3", "+3", "3a", "1.5", "4294967296", "99999999999999999999"] {
+            assert_eq!(parse_board_number(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn reads_auction_calls() {
+        let text = "[Dealer \"E\"]\n[Auction \"E\"]\n1N =1= pass 3n {to play} \nAP\n[Note \"1:15-17\"]\n";
+        let game = split_games(text).remove(0);
+        assert_eq!(game.auction_calls(), ["1NT", "Pass", "3NT", "Pass", "Pass", "Pass"]);
+    }
+
+    #[test]
+    fn reads_vertical_auction_calls() {
+        let text = "[Auction \"W\"]
+W 1S =1=
+N X
+E Pass
+S 2H
+W AP
+*
+";
+        let game = split_games(text).remove(0);
+        assert_eq!(game.auction_calls(), ["1S", "X", "Pass", "2H", "Pass", "Pass", "Pass"]);
+    }
+
+    #[test]
+    fn input_encoding_falls_back_to_latin1() {
+        let path = std::env::temp_dir().join(format!("bba-cli-latin1-{}.pbn", std::process::id()));
+        std::fs::write(&path, b"[North \"Jos\xe9\"]\n").unwrap();
+
+        let auto = InputFile::read(&path, InputEncoding::Auto).unwrap();
+        assert_eq!(auto.text, "[North \"Jos\u{e9}\"]\n");
+        assert!(auto.transcoded);
+        assert!(InputFile::read(&path, InputEncoding::Utf8).is_err());
+
+        std::fs::write(&path, "[North \"Jos\u{e9}\"]\n").unwrap();
+        let utf8 = InputFile::read(&path, InputEncoding::Auto).unwrap();
+        assert_eq!(utf8.text, auto.text);
+        assert!(!utf8.transcoded);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn completes_the_roster_tags() {
+        let text = "% note\r\n[Board \"3\"]\r\n[North \"Ann\"]\r\n[Deal \"N:a\"]\r\n\r\n";
+        assert_eq!(
+            complete_roster_tags(text),
+            "% note\r\n[Event \"?\"]\r\n[Site \"?\"]\r\n[Date \"?\"]\r\n[West \"?\"]\r\n[East \"?\"]\r\n\
+             [South \"?\"]\r\n[Board \"3\"]\r\n[North \"Ann\"]\r\n[Deal \"N:a\"]\r\n\r\n"
+        );
+        let full: String = ROSTER_TAGS.iter().map(|t| format!("[{} \"x\"]\n", t)).collect();
+        assert_eq!(complete_roster_tags(&full), full);
+        assert_eq!(complete_roster_tags("% only a comment\n"), "% only a comment\n");
+    }
+
+    #[test]
+    fn deal_from_north_keeps_every_hand_at_its_seat() {
+        let deal = "E:KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972";
+        let rotated = deal_from_north(deal).unwrap();
+        assert_eq!(
+            rotated,
+            "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT"
+        );
+
+        // Same seat -> hand map either way.
+        let seats = |d: &str| {
+            let (first, hands) = d.split_once(':').unwrap();
+            let first = SEATS.iter().position(|s| *s == first).unwrap();
+            let mut by_seat: Vec<(&str, String)> = hands
+                .split_whitespace()
+                .enumerate()
+                .map(|(i, h)| (SEATS[(first + i) % 4], h.to_string()))
+                .collect();
+            by_seat.sort();
+            by_seat
+        };
+        assert_eq!(seats(deal), seats(&rotated));
+
+        assert_eq!(deal_from_north(&rotated).unwrap(), rotated);
+        assert_eq!(deal_from_north("X:a b c d"), None);
+
+        let text = "[Board \"1\"]\r\n[Deal \"S:c d a b\"]\r\n";
+        assert_eq!(canonicalize_deal_tags(text), "[Board \"1\"]\r\n[Deal \"N:a b c d\"]\r\n");
+        let text = "[Board \"1\"] [Deal \"S:c d a b\"] ; rotated\n";
+        assert_eq!(canonicalize_deal_tags(text), "[Board \"1\"] [Deal \"N:a b c d\"] ; rotated\n");
+    }
+
+    #[test]
+    fn tag_filter_drops_tags_with_their_sections() {
+        let text = "[Board \"1\"]\n[OptimumResultTable \"Declarer;Denomination\"]\nN NT 9\nS NT 9\n{kept}\n[Deal \"N:x\"]\n";
+        let dropped = TagFilter::Drop(vec!["OptimumResultTable".into()]).apply(text);
+        assert_eq!(dropped, "[Board \"1\"]\n{kept}\n[Deal \"N:x\"]\n");
+        let kept = TagFilter::Keep(vec!["Deal".into()]).apply(text);
+        assert_eq!(kept, "{kept}\n[Deal \"N:x\"]\n");
+        assert_eq!(TagFilter::All.apply(text), text);
+
+        let text = "[Board \"1\"][Deal \"N:x\"]\r\n[Event \"e\"] [OptimumResultTable \"t\"]\r\nN NT 9\r\n";
+        let dropped = TagFilter::Drop(vec!["Event".into(), "OptimumResultTable".into()]).apply(text);
+        assert_eq!(dropped, text.split_inclusive('\n').next().unwrap());
+        let kept = TagFilter::Keep(vec!["Deal".into(), "Event".into()]).apply(text);
+        assert_eq!(kept, "[Deal \"N:x\"]\r\n[Event \"e\"]\r\n");
+    }
+
+    #[test]
+    fn parses_double_dummy_tricks() {
+        // N: NT=8 S=10 H=7 D=6 C=5; S same; E/W the complement.
+        assert_eq!(parse_dd_tricks("8a765"), None);
+        let t = parse_dd_tricks("8a7658a7655367853678").unwrap();
+        assert_eq!(t[0], [5, 6, 7, 10, 8]);
+        assert_eq!(t[2], [5, 6, 7, 10, 8]);
+        assert_eq!(t[1], [8, 7, 6, 3, 5]);
+        assert_eq!(t[3], [8, 7, 6, 3, 5]);
+        assert_eq!(parse_dd_tricks("ff7658a7655367853678"), None);
+        assert_eq!(format_dd_tricks(&t), "8a7658a7655367853678");
+    }
+
+    #[test]
+    fn parses_pbn_and_iso_dates() {
+        let d = PbnDate::parse("2024.03.07").unwrap();
+        assert_eq!(d, PbnDate::parse("2024-03-07").unwrap());
+        assert_eq!(d.to_string(), "2024.03.07");
+        assert!(PbnDate::parse("2024.03.06").unwrap() < d);
+        assert!(PbnDate::parse("2023.12.31").unwrap() < d);
+        assert_eq!(PbnDate::parse("2024.??.??"), None);
+        assert_eq!(PbnDate::parse("2024.13.01"), None);
+        assert_eq!(PbnDate::parse(""), None);
+    }
+
+    #[test]
+    fn parses_known_directives_and_keeps_the_rest() {
+        let text = "% PBN 2.1\n% EXPORT\n%HRSeed 614458 (offset 1)\n\n[Event \"x\"]\n% not header\n";
+        let header = PbnHeader::parse(text);
+        assert_eq!(header.version.as_deref(), Some("2.1"));
+        assert!(header.export);
+        assert!(header.is_strict());
+        assert_eq!(header.passthrough, vec!["%HRSeed 614458 (offset 1)".to_string()]);
+    }
+
+    #[test]
+    fn splits_games_and_reads_tags() {
+        let text = "% PBN 2.1\n\n[Board \"1\"]\n[Deal \"N:x\"]\n\n\n[Board \"2\"]\n{comment}\n";
+        let games = split_games(text);
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0].tag("Deal"), Some("N:x"));
+        assert_eq!(games[1].tag("Board"), Some("2"));
+        assert_eq!(games[1].tag("Deal"), None);
+    }
+
+    #[test]
+    fn raw_file_round_trips_byte_for_byte() {
+        let texts = [
+            "% PBN 2.1\r\n\r\n[Board \"1\"]\r\n{note}\r\n\r\n[Board \"2\"]\r\n",
+            "[Board \"1\"]\n  [Deal \"N:x\"]  \n\n\n% stray\n\n[Board \"2\"]",
+            "",
+            "\n\n",
+        ];
+        for text in texts {
+            assert_eq!(RawFile::parse(text).to_text(), text);
+        }
+        let file = RawFile::parse(texts[1]);
+        assert_eq!(file.games.len(), 2);
+        assert!(file.games[0].text.ends_with("% stray\n\n"));
+    }
+
+    #[test]
+    fn reader_yields_the_games_split_games_finds() {
+        let text = "% PBN 2.1\n\n[Board \"1\"]\n[Deal \"N:x\"]\n\n{comment}\n\n[Board \"2\"]\n\n\n[Board \"3\"]";
+        let mut reader = PbnReader::new(text.as_bytes());
+        let first = reader.next().unwrap().unwrap();
+        assert_eq!(reader.preamble(), "% PBN 2.1\n\n");
+        let mut games = vec![first];
+        games.extend(reader.map(Result::unwrap));
+        assert_eq!(games, split_games(text));
+        assert!(games[0].text.ends_with("{comment}\n\n"));
+    }
+
+    /// The tags `line_tags` finds on a line, without their ranges.
+    fn tags_of(line: &str) -> Vec<(String, String)> {
+        line_tags(line).into_iter().map(|(_, tag)| tag).collect()
+    }
+
+    #[test]
+    fn lexes_escapes_and_values_over_several_lines() {
+        assert_eq!(
+            tags_of(r#"[Event "The \"Big\" Game"]"#),
+            [("Event".to_string(), "The \"Big\" Game".to_string())]
+        );
+        assert_eq!(tags_of(r#"[Site"C:\\bridge\d"]"#)[0].1, r"C:\bridge\d");
+        assert_eq!(tags_of(r#"[Event "x"] {note}"#), []);
+
+        let text = "[Board \"1\"]\r\n[Note \"first line\r\n  second \\\"quoted\\\"\"]\r\n[Deal \"N:x\"]\r\n\r\n[Board \"2\"]\r\n";
+        let file = RawFile::parse(text);
+        assert_eq!(file.to_text(), text);
+        assert_eq!(file.malformed, []);
+        assert_eq!(file.games.len(), 2);
+        assert_eq!(file.games[0].tag("Note"), Some("first line\n  second \"quoted\""));
+        assert_eq!(file.games[0].tag("Deal"), Some("N:x"));
+        let read: Vec<RawGame> = PbnReader::new(text.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(read, file.games);
+    }
+
+    #[test]
+    fn reads_several_tags_on_a_line_and_a_closing_comment() {
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+        assert_eq!(tags_of(r#"[Event "x"][Site "y"]"#), [pair("Event", "x"), pair("Site", "y")]);
+        assert_eq!(tags_of(r#"  [Event "x"]  [Site "y"] ; both"#), [pair("Event", "x"), pair("Site", "y")]);
+        assert_eq!(tags_of(r#"[Event "x"] ; a comment [Site "y"]"#), [pair("Event", "x")]);
+
+        let text = "[Event \"x\"][Site \"y\"]\n[Board \"1\"] ; first\n[Note \"a\"] [Deal \"N:x\n\"]\n\n[Board \"2\"]\n";
+        let file = RawFile::parse(text);
+        assert_eq!(file.to_text(), text);
+        assert_eq!(file.malformed, []);
+        assert_eq!(file.games.len(), 2);
+        assert_eq!(
+            file.games[0].tags,
+            [pair("Event", "x"), pair("Site", "y"), pair("Board", "1"), pair("Note", "a"), pair("Deal", "N:x\n")]
+        );
+        let read: Vec<RawGame> = PbnReader::new(text.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(read, file.games);
+
+        let file = RawFile::parse("[Board \"1\"] [Deal \"N:x\"] junk\n");
+        assert_eq!(file.games[0].tags, [pair("Board", "1"), pair("Deal", "N:x")]);
+        let errors: Vec<String> = file.malformed.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["line 1, column 26: unexpected text after the tag"]);
+    }
+
+    #[test]
+    fn reports_where_malformed_tags_go_wrong() {
+        let text = "[Event \"x\"\n[Board 1]\n[Deal \"N:x\"]\n[Note \"open\n\n[Board \"2\"]\n[ \"3\"]\n";
+        let file = RawFile::parse(text);
+        assert_eq!(file.to_text(), text);
+        let errors: Vec<String> = file.malformed.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            errors,
+            [
+                "line 1, column 11: expected ']' after the tag value",
+                "line 2, column 8: expected '\"' to open the tag value",
+                "line 4, column 12: tag value has no closing '\"'",
+                "line 7, column 3: expected a tag name",
+            ]
+        );
+        assert_eq!(file.games.len(), 2);
+        assert_eq!(file.games[0].tags, [("Deal".to_string(), "N:x".to_string())]);
+        assert_eq!(file.games[1].tag("Board"), Some("2"));
+    }
+
+    #[test]
+    fn reader_reports_a_bad_game_and_carries_on() {
+        let mut bytes = b"[Board \"1\"]\n\n[Board \"2\"]\n{caf".to_vec();
+        bytes.extend_from_slice(&[0xe9]);
+        bytes.extend_from_slice(b"}\n\n[Board \"3\"]\n");
+        let games: Vec<Result<RawGame>> = PbnReader::new(bytes.as_slice()).collect();
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[0].as_ref().unwrap().tag("Board"), Some("1"));
+        assert_eq!(games[1].as_ref().unwrap_err().to_string(), "line 4: not valid UTF-8");
+        assert_eq!(games[2].as_ref().unwrap().tag("Board"), Some("3"));
+
+        let games: Vec<RawGame> = PbnReader::with_encoding(bytes.as_slice(), InputEncoding::Auto)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(games.len(), 3);
+        assert!(games[1].text.ends_with("{caf\u{e9}}\n\n"));
+        let latin1 = PbnReader::with_encoding(bytes.as_slice(), InputEncoding::Latin1);
+        assert_eq!(latin1.map(Result::unwrap).collect::<Vec<_>>(), games);
+    }
+
+    #[test]
+    fn joiner_keeps_games_and_drops_later_headers() {
+        let join = |texts: &[&str]| {
+            let mut joiner = PbnJoiner::new(Vec::new());
+            for text in texts {
+                joiner.append(text).unwrap();
+            }
+            String::from_utf8(joiner.into_inner()).unwrap()
+        };
+        assert_eq!(
+            join(&[
+                "% PBN 2.1\n% Generated by bba-cli\n[Board \"1\"]\n",
+                "% PBN 2.1\n% Generated by bba-cli\n",
+                "% PBN 2.1\n% Generated by bba-cli\n[Board \"2\"]\n\n[Board \"3\"]\n",
+            ]),
+            "% PBN 2.1\n% Generated by bba-cli\n[Board \"1\"]\n\n[Board \"2\"]\n\n[Board \"3\"]\n"
+        );
+        // A first file with no games leaves only its header.
+        assert_eq!(join(&["% PBN 2.1\n", "% PBN 2.1\n[Board \"2\"]\n"]), "% PBN 2.1\n[Board \"2\"]\n");
+    }
+
+    #[test]
+    fn check_deal_accepts_full_deal_and_reports_problems() {
+        let good = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+        assert_eq!(check_deal(good), Ok(()));
+        assert!(check_deal("N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63").is_err());
+        // A whole tag line instead of its value is caught, not passed on.
+        let tag = format!("[Deal \"{}\"]", good);
+        assert_eq!(check_deal(&tag).unwrap_err(), "invalid first seat '[Deal \"N'");
+        let ten = "N:8.AQ65.KJ93.K972 KJ62.J1098.54.63 AQ753.7.A86.A854 4.K432.Q72.QJT";
+        assert!(check_deal(ten).unwrap_err().starts_with("invalid card '1'"));
+        let short = "N:8.AQ65.KJ93.K97 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+        assert_eq!(check_deal(short).unwrap_err(), "hand 1 has 12 cards, expected 13");
+        let dup = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJ2";
+        assert!(check_deal(dup).unwrap_err().starts_with("duplicate card"));
+    }
+
+    #[test]
+    fn normalizes_deal_spacing() {
+        let good = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+        let sloppy = " N : 8. AQ65.KJ93 .K972   KJ62.JT98.T54.63\tAQ753.7.A86.A854 T94.K432.Q72.QJT ";
+        assert_eq!(normalize_deal_spacing(sloppy).as_deref(), Some(good));
+        assert_eq!(normalize_deal_spacing(good).as_deref(), Some(good));
+        // Voids next to the spaces between hands.
+        let voids = "N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984";
+        assert_eq!(normalize_deal_spacing(voids).as_deref(), Some(voids));
+        let one_suit_each = "N:AKQJT98765432... .AKQJT98765432.. ..AKQJT98765432. ...AKQJT98765432";
+        assert_eq!(normalize_deal_spacing(one_suit_each).as_deref(), Some(one_suit_each));
+        assert_eq!(normalize_deal_spacing("no seat"), None);
+        assert_eq!(normalize_deal_spacing("N:AKQ.JT9.876.5432"), None);
+    }
+
+    #[test]
+    fn fills_x_spots_lowest_first_in_tag_order() {
+        let deal = "N:AKxxx.AQ6.KJ9.Kx Q2.JT98.T54.xxxx J3.K7.A86.AQJT98 T987.5432.Q732.7";
+        let filled = fill_spots(deal).unwrap();
+        // Spades 4..7 are free: N takes 4, 5, 6. Clubs 2..6 are free: N
+        // takes 2, E takes 3, 4, 5, 6.
+        assert_eq!(
+            filled,
+            "N:AK654.AQ6.KJ9.K2 Q2.JT98.T54.6543 J3.K7.A86.AQJT98 T987.5432.Q732.7"
+        );
+        assert_eq!(check_deal(&filled), Ok(()));
+        assert!(check_deal(deal).unwrap_err().contains("--fill-spots"));
+
+        let crowded = "N:xxxxxxxxxxxxx... AKQJT98765432... ... ...";
+        assert!(fill_spots(crowded).unwrap_err().contains("more x spots"));
+    }
+
+    #[test]
+    fn defaults_without_directives() {
+        let header = PbnHeader::parse("[Event \"x\"]\n");
+        assert_eq!(header, PbnHeader::default());
+        assert_eq!(header.output_version(), DEFAULT_VERSION);
+        assert!(!header.is_strict());
+    }
+}
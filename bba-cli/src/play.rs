@@ -0,0 +1,176 @@
+//! `bba-cli repl --seat SEAT`: bid one seat yourself against EPBot.
+//!
+//! Each line read is a deal, as in the REPL, or `random` for a freshly
+//! shuffled one. You are shown your hand, then the auction goes round the
+//! table: EPBot's calls are printed as they are made, and on your turn you
+//! type a call. EPBot's next call is found by bidding the deal with the
+//! calls so far forced as an auction prefix, so the engine always sees the
+//! auction exactly as it stands. Once the auction is over it is printed in
+//! full, with the meaning EPBot gives each call.
+
+use anyhow::{Context, Result};
+use epbot_core::{AuctionOptions, Engine};
+use std::io::{BufRead, IsTerminal, Write};
+
+use crate::deal_gen::{clock_seed, Shuffler};
+use crate::pbn::{auction_complete, check_call, normalize_call};
+use crate::repl::{self, EngineArgs};
+
+const SEATS: [&str; 4] = ["N", "E", "S", "W"];
+const SUITS: [&str; 4] = ["S", "H", "D", "C"];
+
+/// Play deals from stdin with `seat` bid by the user, until EOF or `quit`.
+pub fn run(args: &EngineArgs, seat: i32) -> Result<()> {
+    let (ns_card, ew_card) = repl::load_cards(args)?;
+    let options = AuctionOptions {
+        scoring: args.scoring,
+        ns_card: ns_card.as_ref(),
+        ew_card: ew_card.as_ref(),
+        ..Default::default()
+    };
+    let mut engine = Engine::new().context("Failed to create EPBot engine")?;
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        eprintln!(
+            "You bid {}. Enter a deal as [DEALER VUL] N:<hands>, or 'random'. Ctrl-D or 'quit' to exit.",
+            SEATS[seat as usize]
+        );
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    let mut lines = stdin.lock().lines();
+    let mut shuffler = Shuffler::new(clock_seed());
+    loop {
+        if interactive {
+            eprint!("bba> ");
+        }
+        let Some(line) = lines.next() else { break };
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        let spec = if line.eq_ignore_ascii_case("random") {
+            repl::parse_deal_spec(&shuffler.deal().pbn())
+        } else {
+            repl::parse_deal_spec(line)
+        };
+        let spec = match spec {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                continue;
+            }
+        };
+
+        writeln!(
+            stdout,
+            "Dealer {}, {} vulnerable. You are {}: {}",
+            SEATS[spec.dealer as usize],
+            vulnerability_name(spec.vulnerability),
+            SEATS[seat as usize],
+            hand_of(&spec.deal, seat).unwrap_or_default()
+        )?;
+        stdout.flush()?;
+
+        let mut calls: Vec<String> = Vec::new();
+        while !auction_complete(&calls) {
+            let turn = (spec.dealer + calls.len() as i32) % 4;
+            let call = if turn == seat {
+                if interactive {
+                    eprint!("{}> ", SEATS[seat as usize]);
+                }
+                let Some(line) = lines.next() else { return Ok(()) };
+                let line = line?;
+                let line = line.trim();
+                if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
+                    return Ok(());
+                }
+                match normalize_call(line).ok_or_else(|| format!("'{}' is not a call", line)) {
+                    Ok(call) => match check_call(&calls, &call) {
+                        Ok(()) => call,
+                        Err(e) => {
+                            eprintln!("error: {}", e);
+                            continue;
+                        }
+                    },
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        continue;
+                    }
+                }
+            } else {
+                let prefix = AuctionOptions {
+                    auction_prefix: Some(calls.as_slice()),
+                    ..options
+                };
+                let result = engine.generate(&spec.deal, spec.dealer, spec.vulnerability, &prefix);
+                let next = result.success.then(|| result.bids.get(calls.len())).flatten();
+                let Some(bid) = next else {
+                    anyhow::bail!("EPBot gave no call for {}: {}", SEATS[turn as usize], result.error.unwrap_or_default());
+                };
+                match bid.meaning.as_deref().filter(|m| !m.is_empty()) {
+                    Some(meaning) => writeln!(stdout, "{}: {}  ({})", SEATS[turn as usize], bid.bid, meaning)?,
+                    None => writeln!(stdout, "{}: {}", SEATS[turn as usize], bid.bid)?,
+                }
+                stdout.flush()?;
+                bid.bid.clone()
+            };
+            calls.push(call);
+        }
+
+        // Replay the finished auction for EPBot's meaning of every call.
+        let replay = AuctionOptions {
+            auction_prefix: Some(calls.as_slice()),
+            ..options
+        };
+        let result = engine.generate(&spec.deal, spec.dealer, spec.vulnerability, &replay);
+        if !result.success {
+            anyhow::bail!("EPBot failed to replay the auction: {}", result.error.unwrap_or_default());
+        }
+        writeln!(stdout)?;
+        repl::write_auction(&mut stdout, &result.bids, spec.dealer)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+/// The hand `seat` holds in a PBN deal, as `S AKQ H JT9 D 876 C 5432`.
+fn hand_of(deal: &str, seat: i32) -> Option<String> {
+    let (first, hands) = deal.split_once(':')?;
+    let first = SEATS.iter().position(|s| s.eq_ignore_ascii_case(first.trim()))? as i32;
+    let hand = hands.split_whitespace().nth((seat - first).rem_euclid(4) as usize)?;
+    let suits: Vec<String> = SUITS
+        .iter()
+        .zip(hand.split('.'))
+        .map(|(suit, cards)| format!("{} {}", suit, if cards.is_empty() { "-" } else { cards }))
+        .collect();
+    Some(suits.join(" "))
+}
+
+fn vulnerability_name(vul: i32) -> &'static str {
+    match vul {
+        1 => "EW",
+        2 => "NS",
+        3 => "both",
+        _ => "nobody",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shows_the_seats_hand() {
+        let deal = "E:AKQ.JT9.876.5432 T98.AKQ.5432.J76 .8765432.AKQ.AKQ J765432..JT9.T98";
+        assert_eq!(hand_of(deal, 1).unwrap(), "S AKQ H JT9 D 876 C 5432");
+        assert_eq!(hand_of(deal, 3).unwrap(), "S - H 8765432 D AKQ C AKQ");
+    }
+}
@@ -0,0 +1,398 @@
+//! Interactive bidding: `bba-cli repl`, and `bba-cli bid` for deals given on
+//! the command line.
+//!
+//! The REPL reads one deal per line from stdin and prints EPBot's auction
+//! for it. A single `Engine` is kept alive for the whole session, so each
+//! line costs only the bidding itself. Handy for quick convention
+//! experiments.
+
+use anyhow::{Context, Result};
+use epbot_core::{AuctionOptions, ConventionCard, Engine, Scoring};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+
+use crate::batch::derive_contract_declarer;
+
+/// Arguments for `bba-cli repl`.
+#[derive(clap::Args, Debug)]
+pub struct ReplArgs {
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
+    /// Bid this seat (N, E, S or W) yourself: for each deal you are shown
+    /// your hand and type your calls, while EPBot bids the other three seats
+    #[arg(long, value_name = "SEAT", value_parser = parse_dealer_arg)]
+    pub seat: Option<i32>,
+}
+
+/// Engine settings shared by `bba-cli repl` and `bba-cli bid`.
+#[derive(clap::Args, Debug)]
+pub struct EngineArgs {
+    /// Convention file (.bbsa) for North-South partnership; EPBot's default
+    /// bidding if omitted
+    #[arg(long = "ns-conventions", value_name = "FILE")]
+    pub ns_conventions: Option<PathBuf>,
+
+    /// Convention file (.bbsa) for East-West partnership; EPBot's default
+    /// bidding if omitted
+    #[arg(long = "ew-conventions", value_name = "FILE")]
+    pub ew_conventions: Option<PathBuf>,
+
+    /// Scoring mode for the auction
+    #[arg(long, value_name = "MODE", default_value = "MP", value_parser = crate::parse_scoring_arg)]
+    pub scoring: Scoring,
+}
+
+/// Arguments for `bba-cli bid`.
+#[derive(clap::Args, Debug)]
+pub struct BidArgs {
+    /// Deal to bid, e.g. "N:AKQ.JT9.876.5432 ..."; repeat for several deals
+    #[arg(long = "deal", value_name = "DEAL", required = true, action = clap::ArgAction::Append)]
+    pub deals: Vec<String>,
+
+    /// Dealer (N, E, S, W); defaults to each deal's first seat
+    #[arg(long, value_name = "SEAT", value_parser = parse_dealer_arg)]
+    pub dealer: Option<i32>,
+
+    /// Vulnerability: None, NS, EW or Both
+    #[arg(
+        long,
+        visible_alias = "vul",
+        value_name = "VUL",
+        default_value = "None",
+        value_parser = parse_vulnerability_arg
+    )]
+    pub vulnerability: i32,
+
+    /// Bid each deal with every dealer and vulnerability (16 auctions) and
+    /// print a grid of the final contracts
+    #[arg(long, conflicts_with_all = ["dealer", "vulnerability"])]
+    pub matrix: bool,
+
+    #[command(flatten)]
+    pub engine: EngineArgs,
+}
+
+fn parse_dealer_arg(s: &str) -> std::result::Result<i32, String> {
+    parse_dealer(s).ok_or_else(|| format!("unknown dealer '{}'; expected N, E, S or W", s))
+}
+
+fn parse_vulnerability_arg(s: &str) -> std::result::Result<i32, String> {
+    parse_vulnerability(s).ok_or_else(|| format!("unknown vulnerability '{}'; expected None, NS, EW or Both", s))
+}
+
+/// One deal to bid: PBN deal string plus EPBot dealer/vulnerability codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DealSpec {
+    pub deal: String,
+    /// 0=N, 1=E, 2=S, 3=W
+    pub dealer: i32,
+    /// EPBot convention: 0=None, 1=EW, 2=NS, 3=Both
+    pub vulnerability: i32,
+}
+
+/// Parse a dealer name: N/E/S/W or the full seat name, any case.
+pub fn parse_dealer(s: &str) -> Option<i32> {
+    match s.to_uppercase().as_str() {
+        "N" | "NORTH" => Some(0),
+        "E" | "EAST" => Some(1),
+        "S" | "SOUTH" => Some(2),
+        "W" | "WEST" => Some(3),
+        _ => None,
+    }
+}
+
+/// Parse a vulnerability name into EPBot's code (0=None, 1=EW, 2=NS, 3=Both).
+pub fn parse_vulnerability(s: &str) -> Option<i32> {
+    match s.to_uppercase().replace(['-', '/'], "").as_str() {
+        "NONE" | "LOVE" => Some(0),
+        "EW" | "EASTWEST" => Some(1),
+        "NS" | "NORTHSOUTH" => Some(2),
+        "ALL" | "BOTH" => Some(3),
+        _ => None,
+    }
+}
+
+/// Parse a REPL line: either `DEALER VUL DEAL` (e.g. `S NS N:AKQ.. ...`) or
+/// just `DEAL`, in which case the deal's first seat deals and nobody is
+/// vulnerable.
+pub fn parse_deal_spec(line: &str) -> std::result::Result<DealSpec, String> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let (dealer, vulnerability, deal_tokens) = match tokens.first() {
+        Some(first) if first.contains(':') => {
+            let seat = first.split(':').next().unwrap_or("");
+            let dealer = parse_dealer(seat).ok_or_else(|| format!("invalid first seat '{}'", seat))?;
+            (dealer, 0, &tokens[..])
+        }
+        Some(first) if tokens.len() >= 3 => {
+            let dealer = parse_dealer(first).ok_or_else(|| format!("invalid dealer '{}'", first))?;
+            let vul = parse_vulnerability(tokens[1])
+                .ok_or_else(|| format!("invalid vulnerability '{}'", tokens[1]))?;
+            (dealer, vul, &tokens[2..])
+        }
+        _ => return Err("expected [DEALER VUL] DEAL".to_string()),
+    };
+    let deal = deal_tokens.join(" ");
+    crate::pbn::check_deal(&deal)?;
+    Ok(DealSpec {
+        deal,
+        dealer,
+        vulnerability,
+    })
+}
+
+/// Render an auction as a four-column N/E/S/W table starting at the dealer,
+/// with alert meanings listed underneath.
+pub fn format_auction_table(bids: &[epbot_core::BidInfo], dealer: i32) -> Vec<String> {
+    let mut cells: Vec<String> = vec!["-".to_string(); dealer.rem_euclid(4) as usize];
+    let mut notes = Vec::new();
+    for bid in bids {
+        match bid.meaning.as_deref().filter(|m| !m.is_empty()) {
+            Some(meaning) => {
+                notes.push(meaning.to_string());
+                cells.push(format!("{}({})", bid.bid, notes.len()));
+            }
+            None => cells.push(bid.bid.clone()),
+        }
+    }
+
+    let mut lines = vec!["N       E       S       W".to_string()];
+    for row in cells.chunks(4) {
+        let line: String = row.iter().map(|c| format!("{:<8}", c)).collect();
+        lines.push(line.trim_end().to_string());
+    }
+    for (i, note) in notes.iter().enumerate() {
+        lines.push(format!("  ({}) {}", i + 1, note));
+    }
+    lines
+}
+
+/// The NS and EW cards named in `args`; None bids with EPBot's defaults.
+pub(crate) fn load_cards(args: &EngineArgs) -> Result<(Option<ConventionCard>, Option<ConventionCard>)> {
+    let read_card = |path: &Option<PathBuf>, side: &str| -> Result<Option<ConventionCard>> {
+        path.as_ref()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read {} conventions file", side))
+                    .map(|content| ConventionCard::from_content(&content))
+            })
+            .transpose()
+    };
+    Ok((read_card(&args.ns_conventions, "NS")?, read_card(&args.ew_conventions, "EW")?))
+}
+
+/// Print the contract line and auction table for one successful auction.
+pub(crate) fn write_auction(out: &mut impl Write, bids: &[epbot_core::BidInfo], dealer: i32) -> Result<()> {
+    let bid_strs: Vec<&str> = bids.iter().map(|b| b.bid.as_str()).collect();
+    let (contract, declarer) = derive_contract_declarer(&bid_strs, dealer);
+    if contract == "Pass" {
+        writeln!(out, "Passed out")?;
+    } else {
+        writeln!(out, "Contract: {} by {}", contract, declarer)?;
+    }
+    for l in format_auction_table(bids, dealer) {
+        writeln!(out, "{}", l)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Vulnerability columns of the `--matrix` grid, as (EPBot code, label).
+const MATRIX_VULS: [(i32, &str); 4] = [(0, "None"), (2, "NS"), (1, "EW"), (3, "Both")];
+
+/// The 16 specs for `--matrix`: `deal` with each dealer (N, E, S, W) crossed
+/// with each vulnerability, in grid order (row by row).
+fn matrix_specs(deal: &str) -> Vec<DealSpec> {
+    (0..4)
+        .flat_map(|dealer| {
+            MATRIX_VULS.iter().map(move |&(vulnerability, _)| DealSpec {
+                deal: deal.to_string(),
+                dealer,
+                vulnerability,
+            })
+        })
+        .collect()
+}
+
+/// Lay out 16 contract cells (in `matrix_specs` order) as a dealer by
+/// vulnerability grid.
+fn format_matrix(cells: &[String]) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{:<8}{}",
+        "Dealer",
+        MATRIX_VULS.iter().map(|(_, label)| format!("{:<10}", label)).collect::<String>()
+    )];
+    for (dealer, row) in cells.chunks(4).enumerate() {
+        let row: String = row.iter().map(|cell| format!("{:<10}", cell)).collect();
+        lines.push(format!("{:<8}{}", ["N", "E", "S", "W"][dealer], row));
+    }
+    lines.iter().map(|l| l.trim_end().to_string()).collect()
+}
+
+/// `--matrix`: bid `deal` under all 16 dealer/vulnerability combinations.
+fn write_matrix(
+    out: &mut impl Write,
+    engine: &mut Engine,
+    deal: &str,
+    options: &AuctionOptions,
+) -> Result<()> {
+    let cells: Vec<String> = matrix_specs(deal)
+        .iter()
+        .map(|spec| {
+            let result = engine.generate(&spec.deal, spec.dealer, spec.vulnerability, options);
+            if !result.success {
+                return "error".to_string();
+            }
+            let bids: Vec<&str> = result.bids.iter().map(|b| b.bid.as_str()).collect();
+            match derive_contract_declarer(&bids, spec.dealer) {
+                (contract, _) if contract == "Pass" => contract,
+                (contract, declarer) => format!("{} {}", contract, declarer),
+            }
+        })
+        .collect();
+    for l in format_matrix(&cells) {
+        writeln!(out, "{}", l)?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// `bba-cli bid`: bid each `--deal` on one engine and print the auctions.
+pub fn run_bid(args: &BidArgs) -> Result<()> {
+    let (ns_card, ew_card) = load_cards(&args.engine)?;
+    let options = AuctionOptions {
+        scoring: args.engine.scoring,
+        ns_card: ns_card.as_ref(),
+        ew_card: ew_card.as_ref(),
+        ..Default::default()
+    };
+    let mut engine = Engine::new().context("Failed to create EPBot engine")?;
+
+    let mut stdout = std::io::stdout().lock();
+    let mut failed = 0;
+    for (n, deal) in args.deals.iter().enumerate() {
+        let deal = deal.trim();
+        let first_seat = deal.split(':').next().and_then(parse_dealer);
+        let Some(dealer) = args.dealer.or(first_seat) else {
+            eprintln!("error: deal {}: invalid first seat in '{}'", n + 1, deal);
+            failed += 1;
+            continue;
+        };
+        if let Err(e) = crate::pbn::check_deal(deal) {
+            eprintln!("error: deal {}: {}", n + 1, e);
+            failed += 1;
+            continue;
+        }
+
+        if args.deals.len() > 1 {
+            writeln!(stdout, "Deal {}: {}", n + 1, deal)?;
+        }
+        if args.matrix {
+            write_matrix(&mut stdout, &mut engine, deal, &options)?;
+            continue;
+        }
+        let result = engine.generate(deal, dealer, args.vulnerability, &options);
+        if !result.success {
+            eprintln!("error: deal {}: {}", n + 1, result.error.unwrap_or_default());
+            failed += 1;
+            continue;
+        }
+        write_auction(&mut stdout, &result.bids, dealer)?;
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} deals could not be bid", failed, args.deals.len());
+    }
+    Ok(())
+}
+
+/// Run the REPL until EOF (or `quit`).
+pub fn run(args: &ReplArgs) -> Result<()> {
+    if let Some(seat) = args.seat {
+        return crate::play::run(&args.engine, seat);
+    }
+    let (ns_card, ew_card) = load_cards(&args.engine)?;
+    let options = AuctionOptions {
+        scoring: args.engine.scoring,
+        ns_card: ns_card.as_ref(),
+        ew_card: ew_card.as_ref(),
+        ..Default::default()
+    };
+
+    let mut engine = Engine::new().context("Failed to create EPBot engine")?;
+
+    let stdin = std::io::stdin();
+    let interactive = stdin.is_terminal();
+    if interactive {
+        eprintln!("Enter deals as [DEALER VUL] N:<hands>, one per line. Ctrl-D or 'quit' to exit.");
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            eprint!("bba> ");
+        }
+        let Some(line) = lines.next() else { break };
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.eq_ignore_ascii_case("quit") || line.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        let spec = match parse_deal_spec(line) {
+            Ok(spec) => spec,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                continue;
+            }
+        };
+
+        let result = engine.generate(&spec.deal, spec.dealer, spec.vulnerability, &options);
+        if !result.success {
+            eprintln!("error: {}", result.error.unwrap_or_default());
+            continue;
+        }
+
+        write_auction(&mut stdout, &result.bids, spec.dealer)?;
+        stdout.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEAL: &str = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+
+    #[test]
+    fn parses_full_and_bare_deal_specs() {
+        let spec = parse_deal_spec(&format!("s ns {}", DEAL)).unwrap();
+        assert_eq!((spec.dealer, spec.vulnerability), (2, 2));
+        assert_eq!(spec.deal, DEAL);
+
+        let spec = parse_deal_spec(&DEAL.replacen("N:", "E:", 1)).unwrap();
+        assert_eq!((spec.dealer, spec.vulnerability), (1, 0));
+
+        assert!(parse_deal_spec("N").is_err());
+        assert!(parse_deal_spec(&format!("Q None {}", DEAL)).is_err());
+    }
+
+    #[test]
+    fn matrix_covers_every_dealer_and_vulnerability() {
+        let specs = matrix_specs(DEAL);
+        assert_eq!(specs.len(), 16);
+        assert_eq!((specs[1].dealer, specs[1].vulnerability), (0, 2));
+        assert_eq!((specs[15].dealer, specs[15].vulnerability), (3, 3));
+
+        let cells: Vec<String> = (0..16).map(|i| if i == 5 { "4S S".into() } else { "Pass".into() }).collect();
+        let lines = format_matrix(&cells);
+        assert_eq!(lines[0], "Dealer  None      NS        EW        Both");
+        assert_eq!(lines[2], "E       Pass      4S S      Pass      Pass");
+        assert_eq!(lines.len(), 5);
+    }
+}
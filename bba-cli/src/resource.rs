@@ -0,0 +1,75 @@
+//! Best-effort resource usage reporting for `--resource-report`.
+//!
+//! Purely observational: nothing here affects processing. Peak RSS is read
+//! from `/proc/self/status` on Linux; other platforms report "n/a".
+
+use std::time::{Duration, Instant, SystemTime};
+
+/// Wall-clock bookkeeping for one run.
+pub struct ResourceReport {
+    started_at: SystemTime,
+    started: Instant,
+}
+
+impl ResourceReport {
+    pub fn start() -> Self {
+        Self {
+            started_at: SystemTime::now(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Wall-clock time since `start`.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Render the report lines for a run that processed `deals` deals.
+    pub fn finish(&self, deals: usize) -> Vec<String> {
+        let elapsed = self.started.elapsed();
+        let ended_at = SystemTime::now();
+        vec![
+            format!("Start time: {}", unix_secs(self.started_at)),
+            format!("End time: {}", unix_secs(ended_at)),
+            format!("Elapsed: {:.3}s", elapsed.as_secs_f64()),
+            format!("Throughput: {}", format_throughput(deals, elapsed)),
+            format!(
+                "Peak RSS: {}",
+                peak_rss_kb()
+                    .map(|kb| format!("{:.1} MiB", kb as f64 / 1024.0))
+                    .unwrap_or_else(|| "n/a".to_string())
+            ),
+        ]
+    }
+}
+
+fn unix_secs(t: SystemTime) -> String {
+    match t.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(d) => format!("{}.{:03} (unix)", d.as_secs(), d.subsec_millis()),
+        Err(_) => "n/a".to_string(),
+    }
+}
+
+fn format_throughput(deals: usize, elapsed: Duration) -> String {
+    let secs = elapsed.as_secs_f64();
+    if secs > 0.0 {
+        format!("{:.1} deals/s", deals as f64 / secs)
+    } else {
+        "n/a".to_string()
+    }
+}
+
+/// Peak resident set size in KiB, if the platform exposes it cheaply.
+#[cfg(target_os = "linux")]
+fn peak_rss_kb() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix("VmHWM:"))
+        .and_then(|v| v.trim().trim_end_matches("kB").trim().parse().ok())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn peak_rss_kb() -> Option<u64> {
+    None
+}
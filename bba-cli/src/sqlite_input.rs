@@ -0,0 +1,347 @@
+//! `--input-sqlite` (feature `sqlite`): bid deals selected from a SQLite
+//! database.
+//!
+//! The rows of `--query` are turned into a PBN deal set and bid by the
+//! normal batch pipeline, so every bidding option applies. Columns are found
+//! by name: `pbn` (or `deal`) holds the Deal tag value and is required;
+//! `dealer`, `vulnerability` and `board` are optional and fill the tags of
+//! the same meaning. The auctions go to `--output` as PBN, or as NDJSON when
+//! the file name ends in `.ndjson`, or with `--results-table` into a table
+//! of the same database.
+
+use crate::batch::{self, BoardOutput, OutputConfig, ProcessingStats};
+use crate::pbn;
+use anyhow::{Context, Result};
+use log::{info, warn};
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Connection, Row};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Where the auctions of a `--input-sqlite` run are written.
+pub enum Sink {
+    Pbn(PathBuf),
+    Ndjson(PathBuf),
+    Table(String),
+}
+
+impl Sink {
+    /// `--results-table` if given, otherwise the output file by extension.
+    pub fn new(output: Option<&Path>, table: Option<&str>) -> Result<Self> {
+        match (table, output) {
+            (Some(table), _) => Ok(Sink::Table(table.to_string())),
+            (None, Some(path)) if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("ndjson")) => {
+                Ok(Sink::Ndjson(path.to_path_buf()))
+            }
+            (None, Some(path)) => Ok(Sink::Pbn(path.to_path_buf())),
+            (None, None) => anyhow::bail!("--input-sqlite needs --output or --results-table"),
+        }
+    }
+}
+
+/// One query row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DealRow {
+    /// 1-based position in the query result.
+    row: usize,
+    deal: String,
+    dealer: Option<String>,
+    vulnerability: Option<String>,
+    board: Option<String>,
+}
+
+impl DealRow {
+    /// The row as a PBN game.
+    fn to_pbn(&self) -> String {
+        let mut game = String::new();
+        let tags = [
+            ("Board", self.board.as_deref()),
+            ("Dealer", self.dealer.as_deref()),
+            ("Vulnerable", self.vulnerability.as_deref()),
+            ("Deal", Some(self.deal.as_str())),
+        ];
+        for (name, value) in tags {
+            if let Some(value) = value {
+                game.push_str(&format!("[{} \"{}\"]\n", name, value.replace('"', "")));
+            }
+        }
+        game
+    }
+}
+
+/// One auction, as written to NDJSON or the results table.
+#[derive(Debug, serde::Serialize)]
+struct ResultRecord<'a> {
+    row: usize,
+    board: Option<&'a str>,
+    deal: &'a str,
+    room: Option<&'a str>,
+    dealer: &'static str,
+    auction: String,
+    contract: Option<String>,
+    declarer: Option<String>,
+    error: Option<&'a str>,
+}
+
+/// Column positions of the fields read from the query.
+struct Columns {
+    deal: usize,
+    dealer: Option<usize>,
+    vulnerability: Option<usize>,
+    board: Option<usize>,
+}
+
+impl Columns {
+    fn find(names: &[&str]) -> Result<Self> {
+        let position = |wanted: &[&str]| {
+            names
+                .iter()
+                .position(|name| wanted.iter().any(|w| name.eq_ignore_ascii_case(w)))
+        };
+        Ok(Columns {
+            deal: position(&["pbn", "deal"]).context("--query must return a `pbn` (or `deal`) column")?,
+            dealer: position(&["dealer"]),
+            vulnerability: position(&["vulnerability", "vulnerable"]),
+            board: position(&["board"]),
+        })
+    }
+}
+
+/// A column value as trimmed text; None for NULL and empty text.
+fn text(row: &Row, idx: usize) -> rusqlite::Result<Option<String>> {
+    let text = match row.get_ref(idx)? {
+        ValueRef::Null => return Ok(None),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) | ValueRef::Blob(t) => String::from_utf8_lossy(t).trim().to_string(),
+    };
+    Ok(Some(text).filter(|t| !t.is_empty()))
+}
+
+/// Run `query` and keep the rows with a well-formed deal. Rows without one
+/// are reported and left out, so every kept row becomes a bid game.
+fn read_rows(conn: &Connection, query: &str) -> Result<Vec<DealRow>> {
+    let mut stmt = conn.prepare(query).context("Failed to prepare --query")?;
+    let names: Vec<&str> = stmt.column_names();
+    let columns = Columns::find(&names)?;
+
+    let mut rows = Vec::new();
+    let mut result = stmt.query([]).context("Failed to run --query")?;
+    let mut n = 0;
+    while let Some(row) = result.next()? {
+        n += 1;
+        let optional = |idx: Option<usize>| idx.map_or(Ok(None), |idx| text(row, idx));
+        let Some(deal) = text(row, columns.deal)? else {
+            warn!("Row {}: no deal; skipped", n);
+            continue;
+        };
+        let deal = pbn::normalize_deal_spacing(&deal).unwrap_or(deal);
+        if let Err(e) = pbn::check_deal(&deal) {
+            warn!("Row {}: invalid deal '{}' ({}); skipped", n, deal, e);
+            continue;
+        }
+        rows.push(DealRow {
+            row: n,
+            deal,
+            dealer: optional(columns.dealer)?,
+            vulnerability: optional(columns.vulnerability)?,
+            board: optional(columns.board)?,
+        });
+    }
+    info!("Query returned {} rows, {} with a deal to bid", n, rows.len());
+    Ok(rows)
+}
+
+/// Bid the deals `query` selects from `db` and write the auctions to `sink`.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    db: &Path,
+    query: &str,
+    sink: &Sink,
+    ns_conventions: Option<&str>,
+    ew_conventions: Option<&str>,
+    dry_run: bool,
+    config: &OutputConfig,
+    auction_prefix: Option<&[String]>,
+) -> Result<ProcessingStats> {
+    let conn = Connection::open(db).with_context(|| format!("Failed to open database {:?}", db))?;
+    let rows = read_rows(&conn, query)?;
+
+    let games = rows.iter().map(DealRow::to_pbn).collect::<Vec<_>>().join("\n");
+    let input = std::env::temp_dir().join(format!("bba-cli-sqlite-{}.pbn", std::process::id()));
+    std::fs::write(&input, games).context("Failed to write deals from the database")?;
+
+    // Only a PBN sink takes the batch output itself; the others are written
+    // from the returned results.
+    let (output, batch_dry_run) = match sink {
+        Sink::Pbn(path) => (path.clone(), dry_run),
+        _ => (input.with_extension("out.pbn"), true),
+    };
+    let processed = batch::process_pbn_file_with_results(
+        &input,
+        &output,
+        ns_conventions,
+        ew_conventions,
+        batch_dry_run,
+        config,
+        auction_prefix,
+    );
+    let _ = std::fs::remove_file(&input);
+    let (stats, results) = processed?;
+
+    let records: Vec<ResultRecord> = results
+        .iter()
+        .filter_map(|(idx, output)| Some((rows.get(idx - 1)?, output)))
+        .flat_map(|(row, output)| records_for(row, output))
+        .collect();
+    if dry_run {
+        return Ok(stats);
+    }
+    match sink {
+        Sink::Pbn(path) => info!("Output written to {:?}", path),
+        Sink::Ndjson(path) => {
+            write_ndjson(path, &records)?;
+            info!("Output written to {:?}", path);
+        }
+        Sink::Table(table) => {
+            write_table(&conn, table, &records)?;
+            info!("Wrote {} auctions to table {}", records.len(), table);
+        }
+    }
+    Ok(stats)
+}
+
+/// The records for one row: one per auction run.
+fn records_for<'a>(row: &'a DealRow, output: &'a BoardOutput) -> Vec<ResultRecord<'a>> {
+    let BoardOutput::Bid(runs) = output else {
+        return Vec::new();
+    };
+    runs.iter()
+        .map(|run| {
+            let calls: Vec<&str> = run.result.bids.iter().map(|b| b.bid.as_str()).collect();
+            let dealer = batch::direction_to_int(run.dealer);
+            let (contract, declarer) = if run.result.success && !calls.is_empty() {
+                let (contract, declarer) = batch::derive_contract_declarer(&calls, dealer);
+                (Some(contract), Some(declarer))
+            } else {
+                (None, None)
+            };
+            ResultRecord {
+                row: row.row,
+                board: row.board.as_deref(),
+                deal: &row.deal,
+                room: run.label.as_deref(),
+                dealer: batch::direction_char(run.dealer),
+                auction: calls.join(" "),
+                contract,
+                declarer,
+                error: run.result.error.as_deref(),
+            }
+        })
+        .collect()
+}
+
+fn write_ndjson(path: &Path, records: &[ResultRecord]) -> Result<()> {
+    let file = std::fs::File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let mut writer = std::io::BufWriter::new(file);
+    for record in records {
+        serde_json::to_writer(&mut writer, record)?;
+        writeln!(writer)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Append the records to `table`, creating it if needed. `table` is a
+/// plain identifier (checked when parsing the arguments).
+fn write_table(conn: &Connection, table: &str, records: &[ResultRecord]) -> Result<()> {
+    conn.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS \"{table}\" (
+            row INTEGER NOT NULL,
+            board TEXT,
+            deal TEXT NOT NULL,
+            room TEXT,
+            dealer TEXT NOT NULL,
+            auction TEXT NOT NULL,
+            contract TEXT,
+            declarer TEXT,
+            error TEXT
+        )"
+    ))
+    .with_context(|| format!("Failed to create results table {}", table))?;
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert = tx.prepare(&format!(
+            "INSERT INTO \"{table}\" (row, board, deal, room, dealer, auction, contract, declarer, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+        ))?;
+        for r in records {
+            insert.execute(params![
+                r.row as i64,
+                r.board,
+                r.deal,
+                r.room,
+                r.dealer,
+                r.auction,
+                r.contract,
+                r.declarer,
+                r.error
+            ])?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEAL: &str = "N:AKQ5.KQ7.A95.K87 T98.T652.Q84.T94 J43.AJ8.KJT2.A65 762.943.763.QJ32";
+
+    #[test]
+    fn maps_query_rows_to_games() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(&format!(
+            "CREATE TABLE deals (board INTEGER, dealer TEXT, vul TEXT, pbn TEXT);
+             INSERT INTO deals VALUES (3, 'S', 'EW', '{DEAL}');
+             INSERT INTO deals VALUES (4, 'W', NULL, 'N:AKQ');
+             INSERT INTO deals VALUES (NULL, NULL, NULL, NULL);"
+        ))
+        .unwrap();
+
+        let rows = read_rows(&conn, "SELECT board, dealer, vul AS vulnerability, pbn FROM deals").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].row, 1);
+        assert_eq!(
+            rows[0].to_pbn(),
+            format!("[Board \"3\"]\n[Dealer \"S\"]\n[Vulnerable \"EW\"]\n[Deal \"{DEAL}\"]\n")
+        );
+
+        let err = read_rows(&conn, "SELECT board FROM deals").unwrap_err();
+        assert!(err.to_string().contains("`pbn`"), "{err}");
+    }
+
+    #[test]
+    fn results_table_is_created_and_appended() {
+        let conn = Connection::open_in_memory().unwrap();
+        let record = ResultRecord {
+            row: 1,
+            board: Some("3"),
+            deal: DEAL,
+            room: None,
+            dealer: "S",
+            auction: "1NT Pass 3NT Pass Pass Pass".to_string(),
+            contract: Some("3NT".to_string()),
+            declarer: Some("S".to_string()),
+            error: None,
+        };
+        write_table(&conn, "bids", std::slice::from_ref(&record)).unwrap();
+        write_table(&conn, "bids", &[record]).unwrap();
+        let (count, contract): (i64, String) = conn
+            .query_row("SELECT COUNT(*), MAX(contract) FROM bids", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!((count, contract.as_str()), (2, "3NT"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Reduce a PBN file to bare deals: `bba-cli strip`.
+//!
+//! Keeps only the `[Board]`, `[Dealer]`, `[Vulnerable]` and `[Deal]` tags of
+//! each game, dropping auctions, contracts, notes and commentary, so a bid
+//! file can be re-bid from scratch or handed out as problems. Reads the file
+//! only; the engine is not involved.
+
+use crate::pbn::{split_games, InputEncoding, InputFile, PbnHeader, RawGame};
+use anyhow::{Context, Result};
+use log::info;
+use std::path::PathBuf;
+
+/// Arguments for `bba-cli strip`.
+#[derive(clap::Args, Debug)]
+pub struct StripArgs {
+    /// PBN file to strip
+    #[arg(short, long, value_name = "FILE")]
+    pub input: PathBuf,
+
+    /// File to write the bare deals to
+    #[arg(short, long, value_name = "FILE")]
+    pub output: PathBuf,
+}
+
+/// The tags kept, in the order they are written.
+const KEPT_TAGS: [&str; 4] = ["Board", "Dealer", "Vulnerable", "Deal"];
+
+/// One game reduced to its kept tags, or None if it has no Deal tag.
+fn strip_game(game: &RawGame) -> Option<String> {
+    game.tag("Deal")?;
+    let mut out = String::new();
+    for name in KEPT_TAGS {
+        if let Some(value) = game.tag(name) {
+            out.push_str(&format!("[{} \"{}\"]\n", name, value));
+        }
+    }
+    Some(out)
+}
+
+/// The stripped file: the `%` version directives, then one block per game
+/// with a Deal tag.
+fn render(text: &str) -> (String, usize) {
+    let header = PbnHeader::parse(text);
+    let mut out = format!("% PBN {}\n", header.output_version());
+    if header.export {
+        out.push_str("% EXPORT\n");
+    }
+    let mut games = 0;
+    for game in split_games(text).iter().filter_map(strip_game) {
+        out.push('\n');
+        out.push_str(&game);
+        games += 1;
+    }
+    (out, games)
+}
+
+pub fn run(args: &StripArgs) -> Result<()> {
+    let input = InputFile::read(&args.input, InputEncoding::Auto)?;
+    let (text, games) = render(&input.text);
+    std::fs::write(&args.output, text).with_context(|| format!("Failed to write {:?}", args.output))?;
+    info!("Wrote {} deals to {:?}", games, args.output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_deal_tags() {
+        let text = "\
+% PBN 2.1
+% EXPORT
+% Generated by bba-cli
+% CC1 - sayc.bbsa
+
+[Event \"Club\"]
+[Board \"1\"]
+[Deal \"N:a\"]
+[Vulnerable \"None\"]
+[Dealer \"N\"]
+[Declarer \"S\"]
+[Contract \"4S\"]
+[Auction \"N\"]
+1S Pass 3S Pass
+4S AP
+[Note \"1:limit\"]
+{Nice hand}
+
+[Event \"Club\"]
+{no deal here}
+
+[Board \"2\"]
+[Deal \"E:b\"]
+";
+        let (out, games) = render(text);
+        assert_eq!(games, 2);
+        assert_eq!(
+            out,
+            "\
+% PBN 2.1
+% EXPORT
+
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:a\"]
+
+[Board \"2\"]
+[Deal \"E:b\"]
+"
+        );
+    }
+}
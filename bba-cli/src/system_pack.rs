@@ -0,0 +1,98 @@
+//! Convention "system packs": one zip holding several `.bbsa` files.
+//!
+//! A pack may carry a `manifest.json` at its root mapping system names to
+//! archive paths, e.g. `{"2/1": "cards/21GF-DEFAULT.bbsa"}`. Without a
+//! manifest (or for names it doesn't list), a name matches any `.bbsa` entry
+//! whose file stem equals it.
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+const MANIFEST: &str = "manifest.json";
+
+pub struct SystemPack {
+    path: PathBuf,
+    archive: ZipArchive<File>,
+    manifest: BTreeMap<String, String>,
+}
+
+impl SystemPack {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open system pack {:?}", path))?;
+        let mut archive = ZipArchive::new(file)
+            .with_context(|| format!("{:?} is not a valid zip archive", path))?;
+
+        let manifest = match archive.by_name(MANIFEST) {
+            Ok(mut entry) => {
+                let mut text = String::new();
+                entry.read_to_string(&mut text)?;
+                serde_json::from_str(&text)
+                    .with_context(|| format!("Invalid {} in {:?}", MANIFEST, path))?
+            }
+            Err(_) => BTreeMap::new(),
+        };
+
+        Ok(SystemPack {
+            path: path.to_path_buf(),
+            archive,
+            manifest,
+        })
+    }
+
+    /// The convention card text for `name`, plus a label naming its source
+    /// for the `% CC1`/`% CC2` header lines.
+    pub fn read(&mut self, name: &str) -> Result<(String, String)> {
+        let entry_name = self.entry_for(name).with_context(|| {
+            format!(
+                "System '{}' not found in {:?}; available: {}",
+                name,
+                self.path,
+                self.names().join(", ")
+            )
+        })?;
+
+        let mut entry = self
+            .archive
+            .by_name(&entry_name)
+            .with_context(|| format!("Manifest entry '{}' missing from {:?}", entry_name, self.path))?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .with_context(|| format!("Failed to read '{}' from {:?}", entry_name, self.path))?;
+
+        Ok((content, format!("{}:{}", self.path.display(), entry_name)))
+    }
+
+    fn entry_for(&self, name: &str) -> Option<String> {
+        if let Some(entry) = self.manifest.get(name) {
+            return Some(entry.clone());
+        }
+        self.archive
+            .file_names()
+            .find(|entry| {
+                let path = Path::new(entry);
+                path.extension().is_some_and(|e| e.eq_ignore_ascii_case("bbsa"))
+                    && path.file_stem().is_some_and(|stem| stem == name)
+            })
+            .map(str::to_string)
+    }
+
+    /// Every name `read` accepts: manifest keys, then `.bbsa` file stems.
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.manifest.keys().cloned().collect();
+        let mut stems: Vec<String> = self
+            .archive
+            .file_names()
+            .map(Path::new)
+            .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("bbsa")))
+            .filter_map(|p| p.file_stem()?.to_str().map(str::to_string))
+            .collect();
+        stems.sort();
+        names.extend(stems);
+        names
+    }
+}
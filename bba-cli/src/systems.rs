@@ -0,0 +1,352 @@
+//! `--systems`: bid the same deals under several convention cards and
+//! tabulate the contracts they reach.
+//!
+//! Each listed card is used by both partnerships in turn. The inputs are bid
+//! once per card without writing any output, then a table per deal lists the
+//! contract each card reached, for ranking candidate systems against a test
+//! set. With `--double-dummy` each contract is scored and matchpointed
+//! against the other cards', and the tables end with each card's total.
+//!
+//! `--compare` is the two-way version: the deals are bid once with the
+//! usual NS/EW cards and once with another pair, and only the deals whose
+//! auctions differ are listed, with both auctions side by side (and the
+//! IMP swing, with `--double-dummy`).
+
+use crate::batch::{self, BoardOutput, BoardRun, OutputConfig, ProcessingStats};
+use crate::compare::{self, Outcome};
+use crate::pbn::{self, InputFile, RawGame};
+use anyhow::{Context, Result};
+use epbot_core::score;
+use log::info;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// One deal's table: its heading, then (system, outcome, double-dummy NS
+/// score) per auction.
+struct DealTable {
+    heading: String,
+    rows: Vec<(String, String, Option<i32>)>,
+}
+
+/// Bid every input once per system and print the tables to stdout.
+/// Returns the stats of all runs together.
+pub fn run(
+    inputs: &[PathBuf],
+    systems: &[PathBuf],
+    config: &OutputConfig,
+    auction_prefix: Option<&[String]>,
+) -> Result<ProcessingStats> {
+    let mut cards = Vec::with_capacity(systems.len());
+    for path in systems {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read convention file {:?}", path))?;
+        cards.push((system_name(path), content));
+    }
+
+    let mut stats = ProcessingStats::default();
+    let mut tables = Vec::new();
+    for input in inputs {
+        let heading_suffix = if inputs.len() > 1 {
+            format!(" ({})", input.display())
+        } else {
+            String::new()
+        };
+        let games = batch::deal_games(&InputFile::read(input, config.encoding)?.text);
+
+        // Deal index (1-based) -> its table, filled in one system at a time.
+        let mut deals: BTreeMap<usize, DealTable> = BTreeMap::new();
+        for (name, card) in &cards {
+            let (file_stats, results) = batch::process_pbn_file_with_results(
+                input,
+                &input.with_extension("out.pbn"),
+                Some(card.as_str()),
+                Some(card.as_str()),
+                true,
+                config,
+                auction_prefix,
+            )
+            .with_context(|| format!("Failed to bid {:?} with {}", input, name))?;
+            info!(
+                "{}: {} deals, {} auctions, {} errors",
+                name, file_stats.deals_processed, file_stats.auctions_generated, file_stats.errors
+            );
+            stats.add(&file_stats);
+
+            for (idx, output) in &results {
+                let BoardOutput::Bid(runs) = output else {
+                    continue;
+                };
+                let table = deals.entry(*idx).or_insert_with(|| DealTable {
+                    heading: deal_heading(&games, *idx, &heading_suffix),
+                    rows: Vec::new(),
+                });
+                for run in runs {
+                    let system = match run.label {
+                        Some(ref label) => format!("{} ({})", name, label),
+                        None => name.clone(),
+                    };
+                    table.rows.push((system, batch::run_outcome(run), run.dd_score));
+                }
+            }
+        }
+        tables.extend(deals.into_values());
+    }
+
+    print!("{}", render(&tables));
+    Ok(stats)
+}
+
+/// One side of a `--compare` run: the NS and EW cards, and how the
+/// report names them.
+pub struct CardSet<'a> {
+    pub name: String,
+    pub ns: Option<&'a str>,
+    pub ew: Option<&'a str>,
+}
+
+/// Bid every input with both card sets and print the deals whose auctions
+/// differ, side by side, to stdout. Returns the stats of all runs together.
+pub fn compare(
+    inputs: &[PathBuf],
+    sets: &[CardSet; 2],
+    config: &OutputConfig,
+    auction_prefix: Option<&[String]>,
+) -> Result<ProcessingStats> {
+    let mut stats = ProcessingStats::default();
+    let mut out = format!("A: {}\nB: {}\n\n", sets[0].name, sets[1].name);
+    let mut compared = 0;
+    let mut differ = 0;
+    let mut imps = 0;
+    let mut scored = 0;
+    for input in inputs {
+        let heading_suffix = if inputs.len() > 1 {
+            format!(" ({})", input.display())
+        } else {
+            String::new()
+        };
+        let games = batch::deal_games(&InputFile::read(input, config.encoding)?.text);
+
+        // Per set: deal index (1-based) -> its auctions.
+        let mut bid: Vec<BTreeMap<usize, Vec<BoardRun>>> = Vec::with_capacity(2);
+        for (set, label) in sets.iter().zip(["A", "B"]) {
+            let (file_stats, results) = batch::process_pbn_file_with_results(
+                input,
+                &input.with_extension("out.pbn"),
+                set.ns,
+                set.ew,
+                true,
+                config,
+                auction_prefix,
+            )
+            .with_context(|| format!("Failed to bid {:?} with set {}", input, label))?;
+            info!(
+                "{}: {} deals, {} auctions, {} errors",
+                label, file_stats.deals_processed, file_stats.auctions_generated, file_stats.errors
+            );
+            stats.add(&file_stats);
+            bid.push(
+                results
+                    .into_iter()
+                    .filter_map(|(idx, output)| match output {
+                        BoardOutput::Bid(runs) => Some((idx, runs)),
+                        _ => None,
+                    })
+                    .collect(),
+            );
+        }
+
+        for (idx, runs_a) in &bid[0] {
+            let Some(runs_b) = bid[1].get(idx) else {
+                continue;
+            };
+            for (a, b) in runs_a.iter().zip(runs_b) {
+                compared += 1;
+                let (left, right) = (run_outcome(a), run_outcome(b));
+                if let Some(swing) = compare::swing(&left, &right) {
+                    imps += swing;
+                    scored += 1;
+                }
+                if left == right {
+                    continue;
+                }
+                differ += 1;
+                let mut heading = deal_heading(&games, *idx, &heading_suffix);
+                if let Some(ref label) = a.label {
+                    heading.push_str(&format!(" ({})", label));
+                }
+                compare::write_difference(&mut out, &heading, &left, &right);
+            }
+        }
+    }
+    out.push_str(&format!("{} of {} deals differ\n", differ, compared));
+    if scored > 0 {
+        out.push_str(&compare::imps_line(imps, scored));
+    }
+    print!("{}", out);
+    Ok(stats)
+}
+
+/// A run's contract and calls, for comparing with another run.
+fn run_outcome(run: &BoardRun) -> Outcome {
+    Outcome {
+        contract: batch::run_outcome(run),
+        calls: run.result.bids.iter().filter_map(|b| pbn::normalize_call(&b.bid)).collect(),
+        score: run.dd_score,
+    }
+}
+
+/// How deal `idx` (1-based) is headed in a report: by its board number
+/// when it has one.
+fn deal_heading(games: &[RawGame], idx: usize, suffix: &str) -> String {
+    match games.get(idx - 1).and_then(|g| g.tag("Board")) {
+        Some(board) => format!("Board {}{}", board, suffix),
+        None => format!("Deal {}{}", idx, suffix),
+    }
+}
+
+/// How a system is named in the table: its file name.
+fn system_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// The tables, separated by blank lines, with the outcomes aligned in one
+/// column across all deals. Scored tables also give each outcome's NS score
+/// and matchpoints, and the report ends with each system's total.
+fn render(tables: &[DealTable]) -> String {
+    let width = tables
+        .iter()
+        .flat_map(|t| &t.rows)
+        .map(|(system, _, _)| system.chars().count())
+        .max()
+        .unwrap_or(0);
+    let outcome_width = tables
+        .iter()
+        .flat_map(|t| &t.rows)
+        .map(|(_, outcome, _)| outcome.chars().count())
+        .max()
+        .unwrap_or(0);
+    // (system, matchpoints, top) in the order the systems first appear.
+    let mut totals: Vec<(&str, f64, usize)> = Vec::new();
+    let mut out = String::new();
+    for (i, table) in tables.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(&table.heading);
+        out.push('\n');
+        let scores: Option<Vec<i32>> = table.rows.iter().map(|(_, _, score)| *score).collect();
+        let mps = scores.map(|scores| score::matchpoints(&scores));
+        for (row, (system, outcome, score)) in table.rows.iter().enumerate() {
+            let line = match (score, &mps) {
+                (Some(score), Some(mps)) => {
+                    let top = table.rows.len() - 1;
+                    match totals.iter_mut().find(|(name, _, _)| *name == system.as_str()) {
+                        Some(total) => {
+                            total.1 += mps[row];
+                            total.2 += top;
+                        }
+                        None => totals.push((system.as_str(), mps[row], top)),
+                    }
+                    format!(
+                        "  {:<width$}  {:<outcome_width$}  NS {:<6}  {:.1} MP",
+                        system,
+                        outcome,
+                        score,
+                        mps[row],
+                        width = width,
+                        outcome_width = outcome_width
+                    )
+                }
+                _ => format!("  {:<width$}  {}", system, outcome, width = width),
+            };
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+    }
+    if !totals.is_empty() {
+        out.push_str("\nMatchpoints (NS, double dummy)\n");
+        for (system, mps, top) in totals {
+            let percent = if top > 0 { mps * 100.0 / top as f64 } else { 0.0 };
+            out.push_str(&format!("  {:<width$}  {:.1} of {} ({:.1}%)\n", system, mps, top, percent, width = width));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligns_outcomes_across_deals() {
+        let tables = vec![
+            DealTable {
+                heading: "Board 1".to_string(),
+                rows: vec![
+                    ("sayc.bbsa".to_string(), "4S by S".to_string(), None),
+                    ("21GF-DEFAULT.bbsa".to_string(), "3NT by N".to_string(), None),
+                ],
+            },
+            DealTable {
+                heading: "Deal 2".to_string(),
+                rows: vec![("sayc.bbsa".to_string(), "passed out".to_string(), None)],
+            },
+        ];
+        assert_eq!(
+            render(&tables),
+            "\
+Board 1
+  sayc.bbsa          4S by S
+  21GF-DEFAULT.bbsa  3NT by N
+
+Deal 2
+  sayc.bbsa          passed out
+"
+        );
+    }
+
+    #[test]
+    fn matchpoints_scored_tables() {
+        let row = |system: &str, outcome: &str, score| (system.to_string(), outcome.to_string(), Some(score));
+        let tables = vec![
+            DealTable {
+                heading: "Board 1".to_string(),
+                rows: vec![row("sayc.bbsa", "4S by S", 420), row("acol.bbsa", "3NT by N", 400)],
+            },
+            DealTable {
+                heading: "Board 2".to_string(),
+                rows: vec![row("sayc.bbsa", "2H by E", -110), row("acol.bbsa", "2H by E", -110)],
+            },
+        ];
+        assert_eq!(
+            render(&tables),
+            "\
+Board 1
+  sayc.bbsa  4S by S   NS 420     1.0 MP
+  acol.bbsa  3NT by N  NS 400     0.0 MP
+
+Board 2
+  sayc.bbsa  2H by E   NS -110    0.5 MP
+  acol.bbsa  2H by E   NS -110    0.5 MP
+
+Matchpoints (NS, double dummy)
+  sayc.bbsa  1.5 of 2 (75.0%)
+  acol.bbsa  0.5 of 2 (25.0%)
+"
+        );
+    }
+
+    #[test]
+    fn heads_deals_by_board_number() {
+        let games = pbn::split_games("[Board \"7\"]\n[Deal \"N:a\"]\n\n[Deal \"N:b\"]\n");
+        assert_eq!(deal_heading(&games, 1, ""), "Board 7");
+        assert_eq!(deal_heading(&games, 2, " (b.pbn)"), "Deal 2 (b.pbn)");
+    }
+
+    #[test]
+    fn names_systems_by_file_name() {
+        assert_eq!(system_name(Path::new("cards/sayc.bbsa")), "sayc.bbsa");
+    }
+}
@@ -0,0 +1,197 @@
+//! Check PBN files without bidding them: `bba-cli validate`.
+//!
+//! Every game with a `[Deal]` is checked for a complete deal, a known
+//! dealer and vulnerability, and, if it has an auction, one that is legal,
+//! finished, and agrees with the game's `[Contract]` and `[Declarer]`.
+//! `--strict` also asks for every tag of the PBN 2.1 mandatory tag set.
+//! Lines that start like a tag but aren't one are reported too.
+//! Problems are listed one per line, with the line of the tag they concern;
+//! the command fails if there are any.
+
+use crate::batch::derive_contract_declarer;
+use crate::pbn::{auction_complete, check_call, check_deal, line_tags, InputEncoding, InputFile, RawFile, RawGame};
+use crate::repl::{parse_dealer, parse_vulnerability};
+use anyhow::Result;
+use log::info;
+use std::path::PathBuf;
+
+/// Arguments for `bba-cli validate`.
+#[derive(clap::Args, Debug)]
+pub struct ValidateArgs {
+    /// PBN file to check (repeat to check several)
+    #[arg(short, long, value_name = "FILE", required = true)]
+    pub input: Vec<PathBuf>,
+
+    /// Also require every tag of the PBN 2.1 mandatory tag set: Event,
+    /// Site, Date, Board, West, North, East, South, Dealer, Vulnerable,
+    /// Deal, Scoring, Declarer, Contract and Result
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// The PBN 2.1 mandatory tags `--strict` asks for, less Deal (every
+/// checked game has one) and Dealer (always checked).
+const MANDATORY_TAGS: [&str; 13] = [
+    "Event", "Site", "Date", "Board", "West", "North", "East", "South", "Vulnerable", "Scoring", "Declarer",
+    "Contract", "Result",
+];
+
+/// A problem with a game, and the line of the game's text it concerns
+/// (0 for the first).
+type Problem = (usize, String);
+
+/// The line of `game`'s text holding tag `name`, or its first line.
+fn tag_line(game: &RawGame, name: &str) -> usize {
+    game.text
+        .lines()
+        .position(|l| line_tags(l).iter().any(|(_, (tag, _))| tag == name))
+        .unwrap_or(0)
+}
+
+/// Everything wrong with one game; empty if it is sound.
+fn check_game(game: &RawGame, strict: bool) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let Some(deal) = game.tag("Deal") else {
+        return problems;
+    };
+    let at = |name| tag_line(game, name);
+    if let Err(e) = check_deal(deal) {
+        problems.push((at("Deal"), format!("bad deal: {}", e)));
+    }
+    let dealer = game.tag("Dealer").and_then(parse_dealer);
+    match game.tag("Dealer") {
+        None => problems.push((0, "no Dealer tag".to_string())),
+        Some(d) if dealer.is_none() => problems.push((at("Dealer"), format!("unknown dealer '{}'", d))),
+        Some(_) => {}
+    }
+    if let Some(v) = game.tag("Vulnerable").filter(|v| parse_vulnerability(v).is_none()) {
+        problems.push((at("Vulnerable"), format!("unknown vulnerability '{}'", v)));
+    }
+    if strict {
+        let missing: Vec<&str> = MANDATORY_TAGS.into_iter().filter(|t| game.tag(t).is_none()).collect();
+        if !missing.is_empty() {
+            problems.push((0, format!("missing mandatory PBN 2.1 tags: {}", missing.join(", "))));
+        }
+    }
+
+    let Some(first) = game.tag("Auction") else {
+        return problems;
+    };
+    let auction = at("Auction");
+    if dealer.is_some() && parse_dealer(first) != dealer {
+        let message = format!("auction starts with {} but the dealer is {}", first, game.tag("Dealer").unwrap_or_default());
+        problems.push((auction, message));
+    }
+    let calls = game.auction_calls();
+    for (i, call) in calls.iter().enumerate() {
+        if let Err(e) = check_call(&calls[..i], call) {
+            problems.push((auction, format!("call {} ({}): {}", i + 1, call, e)));
+            return problems;
+        }
+    }
+    if !auction_complete(&calls) {
+        problems.push((auction, "auction is not finished".to_string()));
+        return problems;
+    }
+
+    let bids: Vec<&str> = calls.iter().map(String::as_str).collect();
+    let (contract, declarer) = derive_contract_declarer(&bids, dealer.unwrap_or(0));
+    if let Some(tag) = game.tag("Contract").filter(|&c| c != contract) {
+        problems.push((at("Contract"), format!("Contract is {} but the auction ends in {}", tag, contract)));
+    } else if let Some(tag) = game.tag("Declarer").filter(|&d| contract != "Pass" && d != declarer) {
+        problems.push((at("Declarer"), format!("Declarer is {} but the auction makes it {}", tag, declarer)));
+    }
+    problems
+}
+
+pub fn run(args: &ValidateArgs) -> Result<()> {
+    let mut games = 0;
+    let mut problems = 0;
+    for path in &args.input {
+        let input = InputFile::read(path, InputEncoding::Auto)?;
+        let file = RawFile::parse(&input.text);
+        for e in &file.malformed {
+            problems += 1;
+            println!("{}:{}: not a tag (column {}): {}", path.display(), e.line, e.column, e.message);
+        }
+        // 1-based line of the file the current game starts on.
+        let mut start = file.preamble.lines().count() + 1;
+        for (n, game) in file.games.iter().enumerate() {
+            let lines = game.text.lines().count();
+            if game.tag("Deal").is_some() {
+                games += 1;
+                for (line, problem) in check_game(game, args.strict) {
+                    problems += 1;
+                    let board = game.tag("Board").unwrap_or("?");
+                    println!("{}:{}: game {} (board {}): {}", path.display(), start + line, n + 1, board, problem);
+                }
+            }
+            start += lines;
+        }
+    }
+    info!("Checked {} games in {} files", games, args.input.len());
+    if problems > 0 {
+        anyhow::bail!("{} problems found in {} games", problems, games);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pbn::split_games;
+
+    const DEAL: &str = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+
+    fn game(tags: &str) -> RawGame {
+        split_games(&format!("[Board \"1\"]\n[Deal \"{}\"]\n{}", DEAL, tags)).remove(0)
+    }
+
+    /// The problems `check_game` finds, without their lines.
+    fn messages(game: &RawGame) -> Vec<String> {
+        check_game(game, false).into_iter().map(|(_, m)| m).collect()
+    }
+
+    #[test]
+    fn accepts_a_sound_game() {
+        let g = game("[Dealer \"S\"]\n[Vulnerable \"NS\"]\n[Declarer \"S\"]\n[Contract \"3NT\"]\n[Auction \"S\"]\n1S Pass 2C Pass\n2NT Pass 3NT AP\n");
+        assert_eq!(messages(&g), Vec::<String>::new());
+        let bare = game("[Dealer \"N\"]\n");
+        assert_eq!(messages(&bare), Vec::<String>::new());
+    }
+
+    #[test]
+    fn reports_what_is_wrong() {
+        let g = game("[Dealer \"Q\"]\n[Vulnerable \"Some\"]\n");
+        assert_eq!(messages(&g), ["unknown dealer 'Q'", "unknown vulnerability 'Some'"]);
+
+        let g = game("[Dealer \"S\"]\n[Auction \"S\"]\n1S Pass 1H\n");
+        assert_eq!(messages(&g), ["call 3 (1H): 1H is not higher than 1S"]);
+
+        let g = game("[Dealer \"S\"]\n[Auction \"S\"]\n1S Pass Pass\n");
+        assert_eq!(messages(&g), ["auction is not finished"]);
+
+        let g = game("[Dealer \"S\"]\n[Contract \"4S\"]\n[Auction \"S\"]\n1S AP\n");
+        assert_eq!(messages(&g), ["Contract is 4S but the auction ends in 1S"]);
+
+        let g = game("[Dealer \"S\"]\n[Auction \"N\"]\nPass AP\n");
+        assert_eq!(messages(&g), ["auction starts with N but the dealer is S"]);
+    }
+
+    #[test]
+    fn points_at_the_tag_line() {
+        let g = game("[Dealer \"Q\"]\n");
+        assert_eq!(check_game(&g, false), [(2, "unknown dealer 'Q'".to_string())]);
+        let g = game("[Dealer \"S\"]\n[Contract \"4S\"]\n[Auction \"S\"]\n1S AP\n");
+        assert_eq!(check_game(&g, false), [(3, "Contract is 4S but the auction ends in 1S".to_string())]);
+    }
+
+    #[test]
+    fn strict_asks_for_the_mandatory_tags() {
+        let g = game("[Dealer \"N\"]\n[Vulnerable \"None\"]\n[Event \"\"]\n[Site \"\"]\n[Date \"\"]\n");
+        assert_eq!(
+            check_game(&g, true),
+            [(0, "missing mandatory PBN 2.1 tags: West, North, East, South, Scoring, Declarer, Contract, Result".to_string())]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! `--watch`: re-run the batch whenever an input or convention file changes.
+//!
+//! Each file's directory is watched rather than the file itself, so editors
+//! that save by writing a new file and renaming it over the old one are
+//! still seen. Changes arriving within `DEBOUNCE` of each other trigger a
+//! single run. The convention cards are re-read on every run; while they
+//! stay the same, EPBot's per-thread engine is reused from run to run.
+
+use crate::batch::ProcessingStats;
+use anyhow::{Context, Result};
+use log::{error, info};
+use notify::{RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Quiet time after the last change before re-running.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Run `run_once` now and again after every change to one of `paths`, until
+/// the process is interrupted. A failed run is reported and the watch goes
+/// on.
+pub fn run(paths: &[PathBuf], mut run_once: impl FnMut() -> Result<ProcessingStats>) -> Result<()> {
+    let files = paths
+        .iter()
+        .map(|p| std::fs::canonicalize(p).with_context(|| format!("Failed to resolve {:?}", p)))
+        .collect::<Result<BTreeSet<PathBuf>>>()?;
+    let dirs: BTreeSet<&Path> = files.iter().filter_map(|f| f.parent()).collect();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to start file watcher")?;
+    for dir in &dirs {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .with_context(|| format!("Failed to watch {:?}", dir))?;
+    }
+
+    let relevant = |event: notify::Result<notify::Event>| match event {
+        Ok(event) => !event.kind.is_access() && event.paths.iter().any(|p| files.contains(p)),
+        Err(e) => {
+            error!("File watcher error: {}", e);
+            false
+        }
+    };
+
+    loop {
+        report(&mut run_once);
+        info!("Watching {} file(s) for changes; press Ctrl-C to stop", files.len());
+
+        // Wait for a change, then for the changes to settle.
+        loop {
+            let event = rx.recv().context("File watcher stopped")?;
+            if relevant(event) {
+                break;
+            }
+        }
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => anyhow::bail!("File watcher stopped"),
+            }
+        }
+    }
+}
+
+/// One run, summarized on a single timestamped line.
+fn report(run_once: &mut impl FnMut() -> Result<ProcessingStats>) {
+    let started = Instant::now();
+    let stamp = time_of_day(SystemTime::now());
+    match run_once() {
+        Ok(stats) => println!(
+            "[{}] {} deals, {} auctions, {} errors in {:.2}s",
+            stamp,
+            stats.deals_processed,
+            stats.auctions_generated,
+            stats.errors,
+            started.elapsed().as_secs_f64()
+        ),
+        Err(e) => println!("[{}] run failed: {:#}", stamp, e),
+    }
+}
+
+/// `HH:MM:SS` in UTC.
+fn time_of_day(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        % 86_400;
+    format!("{:02}:{:02}:{:02}Z", secs / 3600, secs / 60 % 60, secs % 60)
+}
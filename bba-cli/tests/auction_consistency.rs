@@ -0,0 +1,57 @@
+//! Existing auctions whose seats disagree with the Dealer tag are skipped,
+//! or rejected under `--strict`.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string, write_input};
+
+/// The Dealer tag says E but the auction was recorded from N.
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"E\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+[Declarer \"S\"]
+[Contract \"4S\"]
+[Auction \"N\"]
+Pass Pass 1S Pass
+4S AP
+
+[Board \"2\"]
+[Dealer \"N\"]
+[Vulnerable \"NS\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+";
+
+#[test]
+fn inconsistent_game_is_copied_unchanged() {
+    let input = write_input("consistency", INPUT);
+    let out = run_to_string("consistency", &input, &[]);
+
+    let board1 = INPUT.split("\n\n").next().unwrap();
+    assert!(out.contains(board1), "board 1 not copied verbatim:\n{out}");
+    // Only board 2 gets a generated auction (board 1 keeps its own).
+    assert_eq!(out.matches("[Auction ").count(), 2);
+    assert_eq!(out.matches("[BidSystemNS ").count(), 1);
+}
+
+#[test]
+fn strict_rejects_inconsistent_game() {
+    let input = write_input("consistency-strict", INPUT);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let output = std::env::temp_dir().join("bba-cli-consistency-strict-out.pbn");
+
+    let result = bba_cli()
+        .args([
+            "--input", input.to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--strict",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("Game 1: auction starts with N but Dealer is E"), "{stderr}");
+}
@@ -0,0 +1,78 @@
+//! `bba-cli bid`: deals passed as arguments are bid and printed to stdout.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+const DEAL1: &str = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+const DEAL2: &str = "N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984";
+
+#[test]
+fn bids_each_deal() {
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let out = bba_cli()
+        .args([
+            "bid", "--deal", DEAL1, "--deal", DEAL2,
+            "--dealer", "S", "--vulnerability", "NS",
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains(&format!("Deal 1: {DEAL1}")), "{stdout}");
+    assert!(stdout.contains(&format!("Deal 2: {DEAL2}")), "{stdout}");
+    let results = stdout
+        .lines()
+        .filter(|l| l.starts_with("Contract: ") || *l == "Passed out")
+        .count();
+    assert_eq!(results, 2, "{stdout}");
+}
+
+#[test]
+fn vul_is_short_for_vulnerability() {
+    let run = |vul_arg: &str| {
+        let out = bba_cli()
+            .args(["bid", "--deal", DEAL1, "--dealer", "E", vul_arg, "EW"])
+            .output()
+            .expect("failed to spawn bba-cli");
+        assert!(out.status.success(), "bba-cli exited with {}", out.status);
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    };
+    assert_eq!(run("--vul"), run("--vulnerability"));
+}
+
+#[test]
+fn bad_deal_fails_but_others_are_bid() {
+    let out = bba_cli()
+        .args(["bid", "--deal", "N:AKQ.JT9", "--deal", DEAL1])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains(&format!("Deal 2: {DEAL1}")), "{stdout}");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("deal 1:"), "{stderr}");
+}
+
+#[test]
+fn matrix_prints_a_contract_grid() {
+    let out = bba_cli()
+        .args(["bid", "--deal", DEAL1, "--matrix"])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.lines().filter(|l| !l.is_empty()).collect();
+    assert_eq!(lines.len(), 5, "{stdout}");
+    assert!(lines[0].starts_with("Dealer"), "{stdout}");
+    for (line, seat) in lines[1..].iter().zip(["N", "E", "S", "W"]) {
+        assert!(line.starts_with(seat), "{stdout}");
+        assert!(line.split_whitespace().count() >= 5, "{stdout}");
+        assert!(!line.contains("error"), "{stdout}");
+    }
+}
@@ -0,0 +1,36 @@
+//! Board files from dealing machines (`.bri`, `.dup`) are read like a PBN
+//! file: their boards are bid and written out as PBN games.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const DEAL: &str = "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63";
+
+/// North, East and South's card numbers (1 = ace of spades ... 52 = two of
+/// clubs), two digits each, as BRI writes them.
+fn bri_block() -> String {
+    let mut block = String::new();
+    for hand in DEAL[2..].split(' ').take(3) {
+        for (suit, cards) in hand.split('.').enumerate() {
+            for c in cards.chars() {
+                block.push_str(&format!("{:02}", suit * 13 + "AKQJT98765432".find(c).unwrap() + 1));
+            }
+        }
+    }
+    block
+}
+
+#[test]
+fn bri_and_dup_boards_are_bid() {
+    let bri = format!("{}{}", bri_block(), " ".repeat(50)).repeat(2);
+    let dup = format!("{}{}{}", " ".repeat(68), bri_block(), " ".repeat(10)).repeat(2);
+    for (ext, bytes) in [("bri", bri), ("dup", dup)] {
+        let input = write_input(&format!("board-file.{ext}"), bytes);
+        let out = run_to_string(&format!("board-file-{ext}"), &input, &[]);
+        assert!(out.contains(&format!("[Deal \"{}\"]", DEAL)), "{ext}:\n{out}");
+        assert!(out.contains("[Board \"2\"]"), "{ext}:\n{out}");
+        assert!(out.contains("[Dealer \"E\"]\n[Vulnerable \"NS\"]"), "{ext}:\n{out}");
+        assert_eq!(out.matches("[Auction ").count(), 2, "{ext}:\n{out}");
+    }
+}
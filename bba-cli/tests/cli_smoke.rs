@@ -5,40 +5,11 @@
 //! BBA hash encoding, score derivation, SD trick lookup, or PBN writer changes.
 //! It also exercises the cross-platform dynamic-loader path setup.
 
+mod common;
+
+use common::{bba_cli, fixture_path};
 use std::fs;
 use std::path::PathBuf;
-use std::process::Command;
-
-fn manifest_dir() -> PathBuf {
-    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-}
-
-fn fixture_path(rel: &str) -> PathBuf {
-    let mut p = manifest_dir();
-    p.pop(); // bba-cli -> repo root
-    p.push("tests/fixtures");
-    p.push(rel);
-    p
-}
-
-fn epbot_libs_dir() -> PathBuf {
-    let mut p = manifest_dir();
-    p.pop(); // repo root
-    let triple = if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
-        "macos/arm64"
-    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
-        "linux/x64"
-    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
-        "linux/arm64"
-    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
-        "windows/x64"
-    } else {
-        "unsupported"
-    };
-    p.push("epbot-libs");
-    p.push(triple);
-    p
-}
 
 /// Drop content that's deterministic on the algorithm but not on the run:
 /// today's date, and the absolute paths to the convention files.
@@ -56,21 +27,10 @@ fn normalize(s: &str) -> String {
 /// Spawn bba-cli on `input` with single-dummy enabled and compare its
 /// normalized output to `golden`. Panics on divergence with a diff path.
 fn run_and_compare(label: &str, input: PathBuf, golden: PathBuf, ns_card: PathBuf, ew_card: PathBuf) {
-    let bin = env!("CARGO_BIN_EXE_bba-cli");
     let tmp = std::env::temp_dir().join(format!("bba-cli-smoke-{label}.pbn"));
     let _ = fs::remove_file(&tmp);
 
-    // Cross-platform dynamic-loader env. Windows finds the dll via PATH.
-    let lib_var = if cfg!(target_os = "macos") {
-        "DYLD_LIBRARY_PATH"
-    } else if cfg!(target_os = "linux") {
-        "LD_LIBRARY_PATH"
-    } else {
-        "PATH"
-    };
-
-    let status = Command::new(bin)
-        .env(lib_var, epbot_libs_dir())
+    let status = bba_cli()
         .args([
             "--input", input.to_str().unwrap(),
             "--output", tmp.to_str().unwrap(),
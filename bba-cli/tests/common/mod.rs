@@ -0,0 +1,88 @@
+//! Helpers shared by the bba-cli integration tests.
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+pub fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+pub fn fixture_path(rel: &str) -> PathBuf {
+    let mut p = manifest_dir();
+    p.pop(); // bba-cli -> repo root
+    p.push("tests/fixtures");
+    p.push(rel);
+    p
+}
+
+pub fn epbot_libs_dir() -> PathBuf {
+    let mut p = manifest_dir();
+    p.pop(); // repo root
+    let triple = if cfg!(all(target_os = "macos", target_arch = "aarch64")) {
+        "macos/arm64"
+    } else if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+        "linux/x64"
+    } else if cfg!(all(target_os = "linux", target_arch = "aarch64")) {
+        "linux/arm64"
+    } else if cfg!(all(target_os = "windows", target_arch = "x86_64")) {
+        "windows/x64"
+    } else {
+        "unsupported"
+    };
+    p.push("epbot-libs");
+    p.push(triple);
+    p
+}
+
+/// A `bba-cli` command with the cross-platform dynamic-loader env set up.
+/// Windows finds the dll via PATH. A developer's own `.bbarc` is ignored.
+pub fn bba_cli() -> Command {
+    let lib_var = if cfg!(target_os = "macos") {
+        "DYLD_LIBRARY_PATH"
+    } else if cfg!(target_os = "linux") {
+        "LD_LIBRARY_PATH"
+    } else {
+        "PATH"
+    };
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_bba-cli"));
+    cmd.env(lib_var, epbot_libs_dir());
+    cmd.env("BBA_NO_CONFIG", "1");
+    cmd
+}
+
+/// Write a test's input to `bba-cli-{label}-in.pbn` in the temp dir and
+/// return its path. A label with an extension (`lin-input.lin`) keeps it,
+/// for inputs read by their file type.
+pub fn write_input(label: &str, text: impl AsRef<[u8]>) -> PathBuf {
+    let name = match label.rsplit_once('.') {
+        Some((stem, ext)) => format!("bba-cli-{stem}-in.{ext}"),
+        None => format!("bba-cli-{label}-in.pbn"),
+    };
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, text).expect("write input");
+    path
+}
+
+/// Run bba-cli on `input` with the default convention card for both sides
+/// plus `extra` arguments, and return the produced PBN.
+pub fn run_to_string(label: &str, input: &std::path::Path, extra: &[&str]) -> String {
+    let output = std::env::temp_dir().join(format!("bba-cli-{label}-out.pbn"));
+    let _ = std::fs::remove_file(&output);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let status = bba_cli()
+        .args([
+            "--input", input.to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+        ])
+        .args(extra)
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli ({label}) exited with {status}");
+
+    std::fs::read_to_string(&output).expect("read produced PBN")
+}
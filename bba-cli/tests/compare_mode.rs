@@ -0,0 +1,53 @@
+//! `--compare`: every deal bid with two convention sets, listing the deals
+//! whose auctions differ and writing no output file.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+/// Bid deals.pbn with 21GF-DEFAULT on both sides, compared with `cards`.
+fn compare(cards: &str) -> String {
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let run = bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--compare", cards,
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(run.status.success(), "bba-cli exited with {}", run.status);
+    String::from_utf8(run.stdout).unwrap()
+}
+
+#[test]
+fn identical_sets_bid_alike() {
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let stdout = compare(card.to_str().unwrap());
+    assert!(stdout.starts_with("A: NS "), "{stdout}");
+    assert!(stdout.ends_with("\n\n0 of 8 deals differ\n"), "{stdout}");
+}
+
+#[test]
+fn lists_the_deals_that_differ() {
+    let gib = fixture_path("21GF-GIB.bbsa");
+    let stdout = compare(&format!("{},{}", gib.to_str().unwrap(), gib.to_str().unwrap()));
+    assert!(stdout.lines().nth(1).unwrap().starts_with("B: NS "), "{stdout}");
+    let summary = stdout.lines().last().unwrap();
+    assert!(summary.ends_with(" of 8 deals differ"), "{stdout}");
+    for block in stdout.split("\n\n").skip(1).filter(|b| b.starts_with("Board ")) {
+        assert!(block.lines().next().unwrap().contains(" | "), "{block}");
+        assert!(block.lines().skip(1).all(|l| l.contains(" | ")), "{block}");
+    }
+}
+
+#[test]
+fn rejects_more_than_two_cards() {
+    let run = bba_cli()
+        .args(["--input", fixture_path("deals.pbn").to_str().unwrap(), "--compare", "sayc,sayc,sayc"])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!run.status.success());
+    assert!(String::from_utf8_lossy(&run.stderr).contains("--compare takes one card"));
+}
@@ -0,0 +1,36 @@
+//! `--continue-auctions`: the calls already in a game's Auction section are
+//! kept, and EPBot bids on from where they stop.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+[Auction \"N\"]
+7C X
+*
+
+[Board \"2\"]
+[Dealer \"N\"]
+[Vulnerable \"NS\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+";
+
+#[test]
+fn recorded_calls_are_continued() {
+    let input = write_input("continue", INPUT);
+    let continued = run_to_string("continue", &input, &["--continue-auctions"]);
+    assert!(continued.contains("[Auction \"N\"]\n7C X"), "{continued}");
+    assert!(continued.contains("[Contract \"7C"), "{continued}");
+    assert_eq!(continued.matches("[Auction ").count(), 2, "{continued}");
+
+    // Without the flag the recorded calls are ignored.
+    let fresh = run_to_string("continue-fresh", &input, &[]);
+    assert!(!fresh.contains("[Auction \"N\"]\n7C X"), "{fresh}");
+    let second_game = |out: &str| out.split("[Board \"2\"]").nth(1).map(str::to_string);
+    assert_eq!(second_game(&continued), second_game(&fresh));
+}
@@ -0,0 +1,44 @@
+//! `bba-cli convert`: a bid PBN file becomes the CSV that
+//! `--output-format csv` would have written, and deals pass between the
+//! PBN, LIN, GIB and dot formats unchanged.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string, write_input};
+
+#[test]
+fn converts_to_the_csv_a_run_would_write() {
+    let input = fixture_path("deals.pbn");
+    let pbn = run_to_string("convert-pbn", &input, &[]);
+    let csv = run_to_string("convert-csv", &input, &["--output-format", "csv"]);
+
+    let path = write_input("convert", pbn);
+    let out = bba_cli()
+        .args(["convert", "--input", path.to_str().unwrap(), "--output", "-"])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), csv);
+}
+
+fn convert(input: &std::path::Path, output: &std::path::Path) -> String {
+    let out = bba_cli()
+        .args(["convert", "--input", input.to_str().unwrap(), "--output", output.to_str().unwrap()])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+    std::fs::read_to_string(output).unwrap()
+}
+
+#[test]
+fn deals_round_trip_between_formats() {
+    let dir = std::env::temp_dir();
+    let dot = convert(&fixture_path("deals.pbn"), &dir.join("bba-cli-convert.dot"));
+    assert_eq!(dot.lines().count(), 8, "{dot}");
+    for format in ["gib", "lin", "pbn"] {
+        let path = dir.join(format!("bba-cli-convert.{format}"));
+        convert(&fixture_path("deals.pbn"), &path);
+        let again = convert(&path, &dir.join(format!("bba-cli-convert-from-{format}.dot")));
+        assert_eq!(again, dot, "{format}");
+    }
+}
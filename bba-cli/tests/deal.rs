@@ -0,0 +1,67 @@
+//! `bba-cli deal`: seeded, constrained deals that bid like any other input.
+
+mod common;
+
+use common::{bba_cli, run_to_string, write_input};
+
+fn deal(args: &[&str]) -> String {
+    let out = bba_cli().arg("deal").args(args).output().expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+    String::from_utf8(out.stdout).unwrap()
+}
+
+/// HCP and suit lengths of the hand at `seat` (0 = first in the deal).
+fn hand(deal: &str, seat: usize) -> (u32, Vec<usize>) {
+    let hand = deal[2..].split(' ').nth(seat).unwrap();
+    let hcp = hand.chars().map(|c| match c { 'A' => 4, 'K' => 3, 'Q' => 2, 'J' => 1, _ => 0 }).sum();
+    (hcp, hand.split('.').map(str::len).collect())
+}
+
+#[test]
+fn same_seed_same_deals() {
+    let a = deal(&["--count", "4", "--seed", "42"]);
+    assert_eq!(a, deal(&["--count", "4", "--seed", "42"]));
+    assert_ne!(a, deal(&["--count", "4", "--seed", "43"]));
+    assert_eq!(a.matches("[Deal \"N:").count(), 4, "{a}");
+    assert!(a.contains("[Board \"4\"]\n[Dealer \"W\"]\n[Vulnerable \"All\"]\n"), "{a}");
+}
+
+#[test]
+fn constrained_deals_fit_and_bid() {
+    let text = deal(&["-n", "5", "--seed", "7", "-c", "South 15-17 balanced", "-c", "N 5+S"]);
+    for line in text.lines().filter_map(|l| l.strip_prefix("[Deal \"")) {
+        let deal = line.trim_end_matches("\"]");
+        let (hcp, mut lengths) = hand(deal, 2);
+        assert!((15..=17).contains(&hcp), "{deal}");
+        lengths.sort_unstable();
+        assert!(matches!(lengths[..], [3, 3, 3, 4] | [2, 3, 4, 4] | [2, 3, 3, 5]), "{deal}");
+        assert!(hand(deal, 0).1[0] >= 5, "{deal}");
+    }
+
+    let path = write_input("deal", &text);
+    let bid = run_to_string("deal", &path, &[]);
+    assert_eq!(bid.matches("[Auction ").count(), 5, "{bid}");
+}
+
+#[test]
+fn rejects_unknown_conditions() {
+    let out = bba_cli().args(["deal", "-c", "South strong"]).output().expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown condition 'strong'"));
+}
+
+#[test]
+fn where_expressions_filter_deals() {
+    let text = deal(&["--count", "5", "--seed", "9", "--where", "hcp(S) >= 20", "--where", "spades(N) + spades(S) >= 8"]);
+    let deals: Vec<&str> = text.lines().filter_map(|l| l.strip_prefix("[Deal \"")).collect();
+    assert_eq!(deals.len(), 5, "{text}");
+    for deal in deals {
+        let deal = deal.trim_end_matches("\"]");
+        assert!(hand(deal, 2).0 >= 20, "{deal}");
+        assert!(hand(deal, 0).1[0] + hand(deal, 2).1[0] >= 8, "{deal}");
+    }
+
+    let out = bba_cli().args(["deal", "--where", "hcp(Q) > 1"]).output().expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown seat 'q'"));
+}
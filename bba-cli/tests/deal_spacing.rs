@@ -0,0 +1,41 @@
+//! Deal tags with stray whitespace are tidied before bidding, unless
+//! `--strict-parse` rejects them.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N: 8. AQ65.KJ93.K972  KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+";
+
+#[test]
+fn sloppy_deal_tag_is_bid() {
+    let out = run_to_string("deal-spacing", &write_input("deal-spacing", INPUT), &[]);
+    assert!(
+        out.contains("[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]"),
+        "{out}"
+    );
+    assert_eq!(out.matches("[Auction ").count(), 1, "{out}");
+}
+
+#[test]
+fn strict_parse_rejects_sloppy_deal_tag() {
+    let input = write_input("deal-spacing-strict", INPUT);
+    let output = std::env::temp_dir().join("bba-cli-deal-spacing-strict.pbn");
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let result = bba_cli()
+        .args([
+            "--input", input.to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--strict-parse",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Game 1"));
+}
@@ -0,0 +1,26 @@
+//! Without --ns-conventions/--ew-conventions, both sides bid with EPBot's
+//! built-in defaults.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+use std::fs;
+
+#[test]
+fn bids_without_convention_files() {
+    let output = std::env::temp_dir().join("bba-cli-default-conventions.pbn");
+    let _ = fs::remove_file(&output);
+
+    let status = bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+        ])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli exited with {status}");
+
+    let out = fs::read_to_string(&output).expect("read produced PBN");
+    assert_eq!(out.matches("[Auction ").count(), 8);
+    assert!(!out.contains("% CC1"), "no card source to name:\n{out}");
+}
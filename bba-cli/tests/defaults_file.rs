@@ -0,0 +1,63 @@
+//! `.bbarc` default arguments for `run`: discovered in the working
+//! directory, read from `--config`, overridden by the command line, and
+//! skipped with BBA_NO_CONFIG.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+use std::fs;
+use std::path::Path;
+
+fn run(label: &str, dir: &Path, extra: &[&str], discover: bool) -> String {
+    let output = dir.join(format!("{label}.pbn"));
+    let mut cmd = bba_cli();
+    if discover {
+        cmd.env_remove("BBA_NO_CONFIG");
+    }
+    let status = cmd
+        .current_dir(dir)
+        .env("HOME", dir)
+        .args(["--input", fixture_path("deals.pbn").to_str().unwrap(), "--output", output.to_str().unwrap()])
+        .args(extra)
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli ({label}) exited with {status}");
+    fs::read_to_string(&output).expect("read produced PBN")
+}
+
+#[test]
+fn bbarc_supplies_defaults_the_command_line_overrides() {
+    let dir = std::env::temp_dir().join("bba-cli-defaults-file");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    fs::write(
+        dir.join(".bbarc"),
+        format!("# test defaults\n--ns-conventions={}\n--event=From bbarc\n", card.display()),
+    )
+    .unwrap();
+    fs::write(dir.join("other.rc"), "--event=From other\n").unwrap();
+
+    let out = run("discovered", &dir, &[], true);
+    assert!(out.contains("[Event \"From bbarc\"]"), "{out}");
+    assert!(out.contains("% CC1"), "{out}");
+
+    let out = run("overridden", &dir, &["--event", "From CLI"], true);
+    assert!(out.contains("[Event \"From CLI\"]"), "{out}");
+
+    let out = run("explicit", &dir, &["--config", "other.rc"], true);
+    assert!(out.contains("[Event \"From other\"]"), "{out}");
+
+    let out = run("disabled", &dir, &[], false);
+    assert!(!out.contains("From bbarc"), "{out}");
+
+    // Only `run` reads the file, so other subcommands don't trip on its flags.
+    let status = bba_cli()
+        .env_remove("BBA_NO_CONFIG")
+        .current_dir(&dir)
+        .env("HOME", &dir)
+        .args(["validate", "--input", fixture_path("deals.pbn").to_str().unwrap()])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli validate exited with {status}");
+}
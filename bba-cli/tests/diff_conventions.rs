@@ -0,0 +1,21 @@
+//! `bba-cli diff-conventions`: the settings two cards set differently.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+#[test]
+fn lists_the_differing_settings() {
+    let out = bba_cli()
+        .args(["diff-conventions"])
+        .arg(fixture_path("21GF-DEFAULT.bbsa"))
+        .arg(fixture_path("21GF-GIB.bbsa"))
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("Setting"), "{stdout}");
+    assert!(stdout.ends_with("23 settings differ\n"), "{stdout}");
+    let drury = stdout.lines().find(|l| l.starts_with("Drury ")).expect("Drury differs");
+    assert_eq!(drury.split_whitespace().collect::<Vec<_>>(), ["Drury", "off", "on"]);
+}
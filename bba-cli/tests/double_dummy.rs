@@ -0,0 +1,38 @@
+//! `--double-dummy`: each deal is solved and its tricks, par score and par
+//! contracts written.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+";
+
+#[test]
+fn writes_tricks_and_par() {
+    let input = write_input("dd", INPUT);
+    let out = run_to_string("dd", &input, &["--double-dummy"]);
+    // NS make 11 in NT and spades, 10 in hearts, 12 in either minor.
+    assert!(out.contains("[DoubleDummyTricks \"bbaccbbacc2231122311\"]"), "{out}");
+    assert!(out.contains("[OptimumScore \"NS 920\"]"), "{out}");
+    assert!(out.contains("[ParScore \"NS 920\"]"), "{out}");
+    assert!(out.contains("[ParContract \"NS 6C, NS 6D\"]"), "{out}");
+    assert!(out.contains("[OptimumResultTable \"Declarer;Denomination\\2R;Result\\2R\"]\nN NT 11\nN S 11\nN H 10\nN D 12\nN C 12\nS NT 11\n"), "{out}");
+    assert!(out.contains("\nW C 1\n"), "{out}");
+
+    // The bid contract is scored as played double dummy.
+    let tag = |name: &str| {
+        let start = out.find(&format!("[{} \"", name)).unwrap_or_else(|| panic!("no {name} tag in {out}")) + name.len() + 3;
+        out[start..].split('"').next().unwrap().to_string()
+    };
+    let (contract, declarer) = (tag("Contract"), tag("Declarer"));
+    let strain = contract.trim_end_matches('X').chars().nth(1).unwrap();
+    let row = if "NS".contains(declarer.as_str()) { "11 11 10 12 12" } else { "2 2 3 1 1" };
+    let col = "NSHDC".find(strain).unwrap();
+    assert_eq!(tag("Result"), row.split(' ').nth(col).unwrap(), "{out}");
+    assert!(out.contains("[Score \"NS "), "{out}");
+}
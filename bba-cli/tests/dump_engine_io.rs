@@ -0,0 +1,19 @@
+//! `--dump-engine-io`: one JSON file per auction, traceable to its game.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+use std::fs;
+
+#[test]
+fn dumps_name_their_game_and_board() {
+    let dir = std::env::temp_dir().join("bba-cli-dump-engine-io");
+    let _ = fs::remove_dir_all(&dir);
+    run_to_string("dump-engine-io", &fixture_path("deals.pbn"), &["--dump-engine-io", dir.to_str().unwrap()]);
+
+    let text = fs::read_to_string(dir.join("game-0003.json")).expect("read dump");
+    let dump: serde_json::Value = serde_json::from_str(&text).expect("dump is JSON");
+    assert_eq!(dump["game"], 3);
+    assert_eq!(dump["board"], 3);
+    assert_eq!(dump["response"]["success"], true);
+}
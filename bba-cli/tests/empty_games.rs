@@ -0,0 +1,73 @@
+//! Inputs with nothing to bid: an empty file, a file of header games
+//! without a Deal tag, and a header game alongside a real deal.
+
+mod common;
+
+use common::{bba_cli, fixture_path, write_input};
+use std::fs;
+
+const DEAL: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:AKQ5.KQ7.A95.K87 T98.T652.Q84.T94 J43.AJ8.KJT2.A65 762.943.763.QJ32\"]
+";
+
+const HEADER: &str = "\
+% PBN 2.1
+[Event \"Club night\"]
+[Site \"Library\"]
+[Date \"2024.05.01\"]
+";
+
+/// Bid `input` and return the output file and the `--summary-json` stats.
+fn run(label: &str, input: &str) -> (String, serde_json::Value) {
+    let in_path = write_input(label, input);
+    let out_path = std::env::temp_dir().join(format!("bba-cli-{label}-out.pbn"));
+    let _ = fs::remove_file(&out_path);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let result = bba_cli()
+        .args([
+            "--input", in_path.to_str().unwrap(),
+            "--output", out_path.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--summary-json",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(result.status.success(), "bba-cli ({label}) exited with {}", result.status);
+
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    let summary = serde_json::from_str(stdout.trim()).expect("stdout is JSON");
+    (fs::read_to_string(&out_path).expect("read produced PBN"), summary)
+}
+
+#[test]
+fn empty_file_gives_empty_output() {
+    let (out, summary) = run("empty-file", "");
+    assert_eq!(out, "");
+    assert_eq!(summary["deals_processed"], 0);
+    assert_eq!(summary["deals_skipped"], 0);
+}
+
+#[test]
+fn header_game_alone_is_copied() {
+    let (out, summary) = run("header-only", HEADER);
+    assert_eq!(out, HEADER);
+    assert_eq!(summary["deals_processed"], 0);
+    assert_eq!(summary["auctions_generated"], 0);
+    assert_eq!(summary["deals_skipped"], 1);
+}
+
+#[test]
+fn game_without_deal_is_not_bid() {
+    let input = format!("{HEADER}\n{DEAL}");
+    let (out, summary) = run("header-and-deal", &input);
+    assert_eq!(summary["deals_processed"], 1);
+    assert_eq!(summary["auctions_generated"], 1);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(out.matches("[Deal ").count(), 1, "{out}");
+    assert_eq!(out.matches("[Auction ").count(), 1, "{out}");
+}
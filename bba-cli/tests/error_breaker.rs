@@ -0,0 +1,68 @@
+//! `--max-consecutive-errors`: abort once N deals in a row fail.
+
+mod common;
+
+use common::{bba_cli, fixture_path, write_input};
+use std::fs;
+
+/// Under `% EXPORT` a missing Dealer tag is an error, so boards 2 and 3 fail.
+const INPUT: &str = "\
+% EXPORT
+
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+
+[Board \"2\"]
+[Vulnerable \"NS\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+
+[Board \"3\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+";
+
+fn run(label: &str, limit: &str) -> std::process::Output {
+    let input = write_input(&format!("breaker-{label}"), INPUT);
+    let output = std::env::temp_dir().join(format!("bba-cli-breaker-{label}-out.pbn"));
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    bba_cli()
+        .args([
+            "--input", input.to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--max-consecutive-errors", limit,
+        ])
+        .output()
+        .expect("failed to spawn bba-cli")
+}
+
+#[test]
+fn trips_after_n_failures_in_a_row() {
+    let result = run("trip", "2");
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("2 consecutive deals failed (games 2-3); 1 deals succeeded before stopping"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn tripping_leaves_the_games_already_bid() {
+    let result = run("partial", "2");
+    assert!(!result.status.success());
+    let out_path = std::env::temp_dir().join("bba-cli-breaker-partial-out.pbn");
+    let out = fs::read_to_string(out_path).expect("read partial output");
+    assert!(out.starts_with("% PBN "), "{out}");
+    assert_eq!(out.matches("[Auction ").count(), 1, "{out}");
+}
+
+#[test]
+fn shorter_streaks_do_not_trip() {
+    let result = run("no-trip", "3");
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+}
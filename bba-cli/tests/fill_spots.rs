@@ -0,0 +1,29 @@
+//! `--fill-spots`: `x` spot cards in a Deal tag are filled with real cards;
+//! without it the deal is rejected.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:AKxxx.AQ6.KJ9.Kx Q2.JT98.T54.xxxx J3.K7.A86.AQJT98 T987.5432.Q732.7\"]
+";
+
+#[test]
+fn fill_spots_bids_the_completed_deal() {
+    let out = run_to_string("fill-spots", &write_input("fill-spots", INPUT), &["--fill-spots"]);
+    assert!(
+        out.contains("[Deal \"N:AK654.AQ6.KJ9.K2 Q2.JT98.T54.6543 J3.K7.A86.AQJT98 T987.5432.Q732.7\"]"),
+        "{out}"
+    );
+    assert_eq!(out.matches("[Auction ").count(), 1, "{out}");
+}
+
+#[test]
+fn x_spots_are_rejected_by_default() {
+    let out = run_to_string("fill-spots-off", &write_input("fill-spots-off", INPUT), &[]);
+    assert!(!out.contains("[Auction "), "{out}");
+}
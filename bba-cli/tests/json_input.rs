@@ -0,0 +1,29 @@
+//! A `.json` array of deals is read like a PBN file: its deals are bid and
+//! written out as PBN games with the tags they give.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = r#"[
+  {"board": 5, "dealer": "N", "vulnerability": "NS",
+   "deal": "N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63"},
+  {"board": "6", "dealer": "E", "vulnerability": "EW",
+   "deal": "E:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63"}
+]
+"#;
+
+#[test]
+fn json_deals_are_bid() {
+    let input = write_input("json.json", INPUT);
+    let out = run_to_string("json", &input, &[]);
+    assert!(out.contains("[Board \"5\"]"), "{out}");
+    assert!(out.contains("[Dealer \"N\"]\n[Vulnerable \"NS\"]"), "{out}");
+    assert!(out.contains("[Board \"6\"]"), "{out}");
+    assert!(out.contains("[Dealer \"E\"]\n[Vulnerable \"EW\"]"), "{out}");
+    assert!(
+        out.contains("[Deal \"N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63\"]"),
+        "{out}"
+    );
+    assert_eq!(out.matches("[Auction ").count(), 2, "{out}");
+}
@@ -0,0 +1,28 @@
+//! A BBO `.lin` file is read like a PBN file: its boards are bid and
+//! written out as PBN games.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+pn|Sam,Wes,Nora,Ed|st||md|3S8HAQ65DKJ93CK972,SKJ62HJT98DT54C63,SAQ753H7DA86CA854,|rh||ah|Board 5|sv|n|
+mb|p|mb|1S|mb|p|mb|2N!|mb|p|mb|4S|mb|p|mb|p|mb|p|pc|HA|pg||
+pn|Sam,Wes,Nora,Ed|st||md|2S8HAQ65DKJ93CK972,SKJ62HJT98DT54C63,SAQ753H7DA86CA854,|rh||ah|Board 6|sv|e|pg||
+";
+
+#[test]
+fn lin_boards_are_bid() {
+    let input = write_input("lin.lin", INPUT);
+    let out = run_to_string("lin", &input, &[]);
+    assert!(out.contains("[Board \"5\"]"), "{out}");
+    assert!(out.contains("[North \"Nora\"]"), "{out}");
+    assert!(out.contains("[Dealer \"N\"]\n[Vulnerable \"NS\"]"), "{out}");
+    assert!(
+        out.contains("[Deal \"N:AQ753.7.A86.A854 T94.K432.Q72.QJT 8.AQ65.KJ93.K972 KJ62.JT98.T54.63\"]"),
+        "{out}"
+    );
+    assert!(out.contains("[Board \"6\"]"), "{out}");
+    assert!(out.contains("[Dealer \"W\"]\n[Vulnerable \"EW\"]"), "{out}");
+    assert_eq!(out.matches("[Auction ").count(), 2, "{out}");
+}
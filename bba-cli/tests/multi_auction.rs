@@ -0,0 +1,58 @@
+//! `--rotate-dealers` / `--swap-sides`: every run of a board is written as a
+//! separate game with its own `[Room]` label.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+fn tag_values<'a>(pbn: &'a str, tag: &str) -> Vec<&'a str> {
+    let prefix = format!("[{tag} \"");
+    pbn.lines()
+        .filter_map(|l| l.strip_prefix(prefix.as_str()))
+        .map(|rest| rest.trim_end_matches("\"]"))
+        .collect()
+}
+
+#[test]
+fn rotate_dealers_writes_one_game_per_dealer() {
+    let out = run_to_string("multi-rotate", &fixture_path("deals.pbn"), &["--rotate-dealers"]);
+
+    assert_eq!(tag_values(&out, "Auction").len(), 32);
+    let rooms = tag_values(&out, "Room");
+    assert_eq!(rooms.len(), 32);
+
+    // Each board's four games carry distinct dealers matching their labels.
+    let dealers = tag_values(&out, "Dealer");
+    for (room, dealer) in rooms.iter().zip(&dealers) {
+        assert_eq!(*room, format!("Dealer {dealer}"));
+    }
+    for chunk in dealers.chunks(4) {
+        let mut seats = chunk.to_vec();
+        seats.sort_unstable();
+        assert_eq!(seats, ["E", "N", "S", "W"]);
+    }
+}
+
+#[test]
+fn swap_sides_exchanges_system_names() {
+    let out = run_to_string(
+        "multi-swap",
+        &fixture_path("deals.pbn"),
+        &["--swap-sides", "--ns-system-name", "Ours", "--ew-system-name", "Theirs"],
+    );
+
+    let rooms = tag_values(&out, "Room");
+    assert_eq!(rooms.len(), 16);
+    let ns = tag_values(&out, "BidSystemNS");
+    for (room, system) in rooms.iter().zip(&ns) {
+        let expected = if room.ends_with("sides swapped") { "Theirs" } else { "Ours" };
+        assert_eq!(*system, expected, "room {room}");
+    }
+}
+
+#[test]
+fn single_run_omits_room_tag() {
+    let out = run_to_string("multi-single", &fixture_path("deals.pbn"), &[]);
+    assert!(tag_values(&out, "Room").is_empty());
+    assert_eq!(tag_values(&out, "Auction").len(), 8);
+}
@@ -0,0 +1,88 @@
+//! Several `--input` files in one run, paired with `--output` files in order
+//! or written under `--output-dir`.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+use std::fs;
+
+#[test]
+fn output_dir_names_outputs_after_inputs() {
+    let dir = std::env::temp_dir().join("bba-cli-multi-file");
+    let _ = fs::remove_dir_all(&dir);
+    let inputs = dir.join("in");
+    fs::create_dir_all(&inputs).unwrap();
+    let deals = fs::read_to_string(fixture_path("deals.pbn")).unwrap();
+    fs::write(inputs.join("a.pbn"), &deals).unwrap();
+    fs::write(inputs.join("b.pbn"), &deals).unwrap();
+    let out_dir = dir.join("out");
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let status = bba_cli()
+        .args([
+            "-i", inputs.join("a.pbn").to_str().unwrap(),
+            "-i", inputs.join("b.pbn").to_str().unwrap(),
+            "--output-dir", out_dir.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+        ])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli exited with {status}");
+
+    let a = fs::read_to_string(out_dir.join("a.pbn")).expect("read a.pbn");
+    let b = fs::read_to_string(out_dir.join("b.pbn")).expect("read b.pbn");
+    assert_eq!(a.matches("[Auction ").count(), 8);
+    assert_eq!(a, b);
+}
+
+#[test]
+fn mismatched_output_count_is_rejected() {
+    let deals = fixture_path("deals.pbn");
+    let output = bba_cli()
+        .args([
+            "-i", deals.to_str().unwrap(),
+            "-i", deals.to_str().unwrap(),
+            "-o", std::env::temp_dir().join("bba-cli-multi-one.pbn").to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--output-dir"));
+}
+
+#[test]
+fn manifest_lists_every_output() {
+    let dir = std::env::temp_dir().join("bba-cli-manifest");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let deals = fixture_path("deals.pbn");
+    let manifest = dir.join("manifest.json");
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let status = bba_cli()
+        .args([
+            "-i", deals.to_str().unwrap(),
+            "-i", deals.to_str().unwrap(),
+            "-o", dir.join("one.pbn").to_str().unwrap(),
+            "-o", dir.join("two.pbn").to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--manifest", manifest.to_str().unwrap(),
+        ])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli exited with {status}");
+
+    let text = fs::read_to_string(&manifest).expect("read manifest");
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&text).expect("manifest is a JSON list");
+    assert_eq!(entries.len(), 2);
+    for (entry, name) in entries.iter().zip(["one.pbn", "two.pbn"]) {
+        assert_eq!(entry["input"], deals.to_str().unwrap());
+        assert_eq!(entry["output"], dir.join(name).to_str().unwrap());
+        assert_eq!(entry["deals"], 8);
+        assert_eq!(entry["auctions"], 8);
+        assert_eq!(entry["errors"], 0);
+        assert!(dir.join(name).exists());
+    }
+}
@@ -0,0 +1,21 @@
+//! A file whose games all have unparseable deals is copied through without
+//! bidding, instead of coming out as an empty file.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Deal \"N:not a deal\"]
+
+[Board \"2\"]
+[Deal \"N:AKQ.JT9\"]
+";
+
+#[test]
+fn unparseable_deals_are_copied_unchanged() {
+    let input = write_input("no-deals", INPUT);
+    let out = run_to_string("no-deals", &input, &[]);
+    assert_eq!(out, INPUT);
+}
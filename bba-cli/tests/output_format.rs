@@ -0,0 +1,62 @@
+//! `--output-format csv` and `lin`: one row or line per auction, agreeing
+//! with the PBN output.
+
+mod common;
+
+use common::{fixture_path, run_to_string, write_input};
+
+/// Values of every `[name "..."]` tag in a PBN text, in order.
+fn tag_values(pbn: &str, name: &str) -> Vec<String> {
+    let prefix = format!("[{} \"", name);
+    pbn.lines()
+        .filter_map(|l| l.strip_prefix(&prefix))
+        .map(|rest| rest.trim_end_matches("\"]").to_string())
+        .collect()
+}
+
+#[test]
+fn csv_rows_match_the_pbn_games() {
+    let input = fixture_path("deals.pbn");
+    let pbn = run_to_string("format-pbn", &input, &[]);
+    let csv = run_to_string("format-csv", &input, &["--output-format", "csv"]);
+
+    let mut lines = csv.lines();
+    assert_eq!(
+        lines.next(),
+        Some("Board,Dealer,Vulnerable,Deal,Auction,Contract,Declarer")
+    );
+    let rows: Vec<Vec<&str>> = lines.map(|l| l.split(',').collect()).collect();
+    assert_eq!(rows.len(), 8);
+    assert!(rows.iter().all(|r| r.len() == 7));
+
+    let column = |i: usize| rows.iter().map(|r| r[i].to_string()).collect::<Vec<_>>();
+    assert_eq!(column(0), tag_values(&pbn, "Board"));
+    assert_eq!(column(1), tag_values(&pbn, "Dealer"));
+    assert_eq!(column(2), tag_values(&pbn, "Vulnerable"));
+    assert_eq!(column(3), tag_values(&pbn, "Deal"));
+    assert_eq!(column(5), tag_values(&pbn, "Contract"));
+    assert_eq!(column(6), tag_values(&pbn, "Declarer"));
+    assert!(rows.iter().all(|r| !r[4].is_empty()));
+}
+
+#[test]
+fn lin_lines_read_back_as_the_pbn_games() {
+    let input = fixture_path("deals.pbn");
+    let pbn = run_to_string("format-pbn-lin", &input, &[]);
+    let lin = run_to_string("format-lin", &input, &["--output-format", "lin"]);
+
+    let lines: Vec<&str> = lin.lines().collect();
+    assert_eq!(lines.len(), 8, "{lin}");
+    for (line, board) in lines.iter().zip(tag_values(&pbn, "Board")) {
+        assert!(line.starts_with("pn|EPBot,EPBot,EPBot,EPBot|st||md|"), "{line}");
+        assert!(line.contains(&format!("|ah|Board {}|", board)), "{line}");
+        assert!(line.contains("|mb|") && line.ends_with("|pg||"), "{line}");
+    }
+
+    // Bid again from the LIN file, the same deals reach the same contracts.
+    let path = write_input("format-lin.lin", &lin);
+    let again = run_to_string("format-lin-again", &path, &[]);
+    assert_eq!(tag_values(&again, "Deal"), tag_values(&pbn, "Deal"));
+    assert_eq!(tag_values(&again, "Dealer"), tag_values(&pbn, "Dealer"));
+    assert_eq!(tag_values(&again, "Contract"), tag_values(&pbn, "Contract"));
+}
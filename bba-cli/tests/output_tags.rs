@@ -0,0 +1,184 @@
+//! Options that change which tags the PBN writer emits.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+#[test]
+fn custom_tag_names_replace_defaults() {
+    let out = run_to_string(
+        "tags-renamed",
+        &fixture_path("deals.pbn"),
+        &[
+            "--contract-tag", "FinalContract",
+            "--declarer-tag", "FinalDeclarer",
+            "--auction-tag", "Bidding",
+        ],
+    );
+    assert_eq!(out.matches("[FinalContract ").count(), 8);
+    assert_eq!(out.matches("[FinalDeclarer ").count(), 8);
+    assert_eq!(out.matches("[Bidding ").count(), 8);
+    assert!(!out.contains("[Contract "));
+    assert!(!out.contains("[Auction "));
+}
+
+#[test]
+fn annotate_index_numbers_games_from_one() {
+    let out = run_to_string("tags-index", &fixture_path("deals.pbn"), &["--annotate-index"]);
+    let indexes: Vec<&str> = out
+        .lines()
+        .filter_map(|l| l.strip_prefix("[BBAIndex \""))
+        .map(|rest| rest.trim_end_matches("\"]"))
+        .collect();
+    assert_eq!(indexes, ["1", "2", "3", "4", "5", "6", "7", "8"]);
+}
+
+#[test]
+fn repeat_reports_agreement() {
+    let out = run_to_string("tags-repeat", &fixture_path("deals.pbn"), &["--repeat", "3"]);
+    // EPBot is deterministic: every deal bids the same auction each time.
+    assert_eq!(out.matches("{Bid 3 times: all identical}").count(), 8);
+}
+
+#[test]
+fn stamp_conventions_records_card_file_names() {
+    let out = run_to_string(
+        "tags-stamp",
+        &fixture_path("deals.pbn"),
+        &["--stamp-conventions", "--ew-stamp-tag", "EWCard"],
+    );
+    assert_eq!(out.matches("[BBANsSystem \"21GF-DEFAULT.bbsa\"]").count(), 8);
+    assert_eq!(out.matches("[EWCard \"21GF-DEFAULT.bbsa\"]").count(), 8);
+}
+
+#[test]
+fn vulnerability_style_respells_the_vulnerable_tag() {
+    let vuls = |out: &str| -> Vec<String> {
+        out.lines()
+            .filter_map(|l| l.strip_prefix("[Vulnerable \""))
+            .map(|rest| rest.trim_end_matches("\"]").to_string())
+            .collect()
+    };
+    let deals = fixture_path("deals.pbn");
+
+    let out = run_to_string("tags-vul-hyphen", &deals, &["--vulnerability-style", "hyphen"]);
+    assert_eq!(vuls(&out)[..4], ["None", "N/S", "E/W", "All"]);
+
+    let out = run_to_string("tags-vul-british", &deals, &["--vulnerability-style", "british"]);
+    assert_eq!(vuls(&out)[..4], ["Love All", "N/S", "E/W", "Game All"]);
+}
+
+#[test]
+fn pad_auction_grid_fills_the_last_line() {
+    let out = run_to_string(
+        "tags-pad-grid",
+        &fixture_path("deals.pbn"),
+        &["--pad-auction-grid", "--verify-output"],
+    );
+    let mut auctions = 0;
+    let mut in_auction = false;
+    for line in out.lines() {
+        if line.starts_with('[') {
+            in_auction = line.starts_with("[Auction ");
+            auctions += in_auction as usize;
+            continue;
+        }
+        if in_auction && !line.is_empty() {
+            let cells = line.split_whitespace().filter(|t| !t.starts_with('=')).count();
+            assert_eq!(cells, 4, "ragged line {line:?}:\n{out}");
+        }
+    }
+    assert_eq!(auctions, 8);
+    assert!(out.contains(" -\n"), "no placeholder written:\n{out}");
+}
+
+#[test]
+fn vertical_auction_layout_lists_one_call_per_line() {
+    let out = run_to_string(
+        "tags-vertical",
+        &fixture_path("deals.pbn"),
+        &["--auction-layout", "vertical", "--verify-output"],
+    );
+    let mut in_auction = false;
+    let mut calls = 0;
+    for line in out.lines() {
+        if line.starts_with('[') {
+            in_auction = line.starts_with("[Auction ");
+            continue;
+        }
+        if in_auction && !line.is_empty() {
+            let mut tokens = line.split_whitespace().filter(|t| !t.starts_with('='));
+            let seat = tokens.next().unwrap();
+            assert!(["N", "E", "S", "W"].contains(&seat), "no seat on {line:?}:\n{out}");
+            assert_eq!(tokens.count(), 1, "not one call on {line:?}:\n{out}");
+            calls += 1;
+        }
+    }
+    assert!(calls >= 8 * 4, "too few calls:\n{out}");
+}
+
+#[test]
+fn auction_column_width_aligns_calls() {
+    let out = run_to_string(
+        "tags-column-width",
+        &fixture_path("deals.pbn"),
+        &["--auction-column-width", "8", "--verify-output"],
+    );
+    let mut in_auction = false;
+    let mut lines = 0;
+    for line in out.lines() {
+        if line.starts_with('[') {
+            in_auction = line.starts_with("[Auction ");
+            continue;
+        }
+        if in_auction && !line.is_empty() && !line.contains('=') {
+            for (i, c) in line.char_indices().skip(1) {
+                let starts_call = c != ' ' && line.as_bytes()[i - 1] == b' ';
+                assert!(!starts_call || i % 8 == 0, "call off the 8-column grid in {line:?}");
+            }
+            lines += 1;
+        }
+    }
+    assert!(lines > 0, "no auction lines:\n{out}");
+}
+
+#[test]
+fn stamp_metadata_writes_a_provenance_header() {
+    let out = run_to_string("tags-metadata", &fixture_path("deals.pbn"), &["--stamp-metadata"]);
+    let header: Vec<&str> = out.lines().take_while(|l| !l.starts_with('[')).collect();
+    let find = |prefix: &str| header.iter().find(|l| l.starts_with(prefix)).copied();
+
+    let generated_by = format!("% Generated-by: bba-cli v{}", env!("CARGO_PKG_VERSION"));
+    assert_eq!(find("% Generated-by: "), Some(generated_by.as_str()), "{out}");
+    assert!(find("% Engine: EPBot ").is_some(), "{out}");
+    let conventions = find("% Conventions: ").expect("conventions line");
+    assert!(conventions.contains("ns=") && conventions.contains(", ew="), "{conventions}");
+    assert!(find("% Generated-at: ").is_some_and(|l| l.ends_with(" UTC")), "{out}");
+}
+
+#[test]
+fn annotate_strength_notes_every_call() {
+    let out = run_to_string(
+        "tags-strength",
+        &fixture_path("deals.pbn"),
+        &["--annotate-strength", "--verify-output"],
+    );
+    let mut in_auction = false;
+    let mut calls = 0;
+    for line in out.lines() {
+        if line.starts_with('[') {
+            in_auction = line.starts_with("[Auction ");
+            continue;
+        }
+        if in_auction && !line.is_empty() {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let notes = tokens.iter().filter(|t| t.starts_with('=')).count();
+            assert_eq!(notes * 2, tokens.len(), "call without a note in {line:?}:\n{out}");
+            calls += notes;
+        }
+    }
+    assert_eq!(out.matches("[Note ").count(), calls);
+    // Board 1: North deals with 8.AQ65.KJ93.K972.
+    let first_note = out.lines().find(|l| l.starts_with("[Note \"1:")).unwrap();
+    assert!(first_note.ends_with("N: 13 HCP, 1-4-4-4\"]"), "{first_note}");
+}
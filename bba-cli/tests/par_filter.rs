@@ -0,0 +1,31 @@
+//! `--par-disagreements`: only auctions off double-dummy par survive.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+/// Board 1 claims NS take all 13 tricks in every strain, so par is 7NT
+/// (+1520 non-vulnerable) and any lesser contract is far off par. Board 2
+/// has no DoubleDummyTricks tag and is dropped.
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+[DoubleDummyTricks \"dddddddddd0000000000\"]
+
+[Board \"2\"]
+[Dealer \"N\"]
+[Vulnerable \"NS\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+";
+
+#[test]
+fn keeps_only_off_par_deals() {
+    let input = write_input("par", INPUT);
+    let out = run_to_string("par", &input, &["--par-disagreements"]);
+    assert_eq!(out.matches("[Board ").count(), 1, "{out}");
+    assert!(out.contains("[Board \"1\"]"));
+    assert!(out.contains("[OptimumScore \"NS 1520\"]"));
+    assert!(out.contains("[ParContract \"NS 7NT\"]"));
+}
@@ -0,0 +1,76 @@
+//! `--passout-handling`: a passed-out deal recorded, left out, or counted
+//! as an error, with the passouts counted in every mode.
+
+mod common;
+
+use common::{bba_cli, fixture_path, write_input};
+use std::fs;
+
+/// A deal that is bid to game, then four flat ten-counts nobody opens.
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:AKQ5.KQ7.A95.K87 T98.T652.Q84.T94 J43.AJ8.KJT2.A65 762.943.763.QJ32\"]
+
+[Board \"2\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:AT98.KT9.QT9.JT9 K76.Q876.J87.A87 Q54.J54.A654.K65 J32.A32.K32.Q432\"]
+";
+
+/// Bid `INPUT` under `mode` and return the output file and the
+/// `--summary-json` stats.
+fn run(mode: &str) -> (String, serde_json::Value) {
+    let in_path = write_input(&format!("passout-{mode}"), INPUT);
+    let out_path = std::env::temp_dir().join(format!("bba-cli-passout-{mode}-out.pbn"));
+    let _ = fs::remove_file(&out_path);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let result = bba_cli()
+        .args([
+            "--input", in_path.to_str().unwrap(),
+            "--output", out_path.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--passout-handling", mode,
+            "--summary-json",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(result.status.success(), "bba-cli ({mode}) exited with {}", result.status);
+
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    let summary = serde_json::from_str(stdout.trim()).expect("stdout is JSON");
+    (fs::read_to_string(&out_path).expect("read produced PBN"), summary)
+}
+
+#[test]
+fn record_writes_the_passout() {
+    let (out, summary) = run("record");
+    assert_eq!(summary["passouts"], 1);
+    assert_eq!(summary["auctions_generated"], 2);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(out.matches("[Auction ").count(), 2, "{out}");
+    assert!(out.contains("[Contract \"Pass\"]"), "{out}");
+}
+
+#[test]
+fn skip_leaves_the_passout_out() {
+    let (out, summary) = run("skip");
+    assert_eq!(summary["passouts"], 1);
+    assert_eq!(summary["auctions_generated"], 1);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(out.matches("[Board ").count(), 1, "{out}");
+    assert!(out.contains("[Board \"1\"]"), "{out}");
+    assert!(!out.contains("[Contract \"Pass\"]"), "{out}");
+}
+
+#[test]
+fn error_counts_the_passout_as_failed() {
+    let (out, summary) = run("error");
+    assert_eq!(summary["passouts"], 1);
+    assert_eq!(summary["auctions_generated"], 1);
+    assert_eq!(summary["errors"], 1);
+    assert!(!out.contains("[Contract \"Pass\"]"), "{out}");
+}
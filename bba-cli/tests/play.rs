@@ -0,0 +1,45 @@
+//! `bba-cli repl --seat`: one seat's calls come from stdin, EPBot makes the
+//! rest.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+use std::io::Write;
+use std::process::Stdio;
+
+const DEAL: &str = "N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT";
+
+/// Run `repl --seat S` with `input` on stdin; returns (stdout, stderr).
+fn play(input: &str) -> (String, String) {
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let mut child = bba_cli()
+        .args(["repl", "--seat", "S"])
+        .args(["--ns-conventions", card.to_str().unwrap()])
+        .args(["--ew-conventions", card.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn bba-cli");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let out = child.wait_with_output().unwrap();
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+    (
+        String::from_utf8(out.stdout).unwrap(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn bids_the_other_seats_around_the_user() {
+    // South deals and passes throughout; an illegal double is asked again.
+    let input = format!("S None {DEAL}\nX\n{}", "Pass\n".repeat(20));
+    let (stdout, stderr) = play(&input);
+    assert!(stdout.contains("You are S: S AQ753 H 7 D A86 C A854"), "{stdout}");
+    assert!(stderr.contains("nothing to double"), "{stderr}");
+    assert!(stdout.lines().any(|l| l.starts_with("W: ")), "{stdout}");
+    assert!(
+        stdout.lines().any(|l| l.starts_with("Contract: ") || l == "Passed out"),
+        "{stdout}"
+    );
+}
@@ -0,0 +1,32 @@
+//! `--dry-run --print-contracts`: a per-board contract preview on stdout,
+//! with nothing written to disk.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+#[test]
+fn dry_run_prints_contracts_without_writing() {
+    let output = std::env::temp_dir().join("bba-cli-print-contracts.pbn");
+    let _ = std::fs::remove_file(&output);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let run = bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--dry-run",
+            "--print-contracts",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(run.status.success(), "bba-cli exited with {}", run.status);
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 8, "{stdout}");
+    assert!(lines.iter().all(|l| l.starts_with("Board ") && (l.contains(" by ") || l.ends_with("passed out"))));
+    assert!(!output.exists(), "dry run wrote {:?}", output);
+}
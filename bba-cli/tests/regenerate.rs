@@ -0,0 +1,31 @@
+//! A game that already carries an auction is regenerated from the deal:
+//! the old Auction block and its contract never reach the output.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+[Declarer \"N\"]
+[Contract \"7CX\"]
+[Auction \"N\"]
+7C X Pass Pass
+Pass
+";
+
+#[test]
+fn existing_auction_is_replaced() {
+    let input = write_input("regenerate", INPUT);
+
+    for (label, extra) in [("regenerate", &[][..]), ("regenerate-tag", &["--auction-tag", "BBAAuction"][..])] {
+        let out = run_to_string(label, &input, extra);
+        let auction_tag = extra.last().copied().unwrap_or("Auction");
+        assert_eq!(out.matches(&format!("[{auction_tag} ")).count(), 1, "{label}:\n{out}");
+        assert!(!out.contains("[Auction \"N\"]\n7C X"), "{label}: stale auction kept:\n{out}");
+        assert!(!out.contains("7CX"), "{label}: stale contract kept:\n{out}");
+    }
+}
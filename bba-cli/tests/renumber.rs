@@ -0,0 +1,46 @@
+//! Board numbers: kept from the input by default, or rewritten in order
+//! with `--renumber-from`, which also fills missing Dealer/Vulnerable tags.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+/// Board 7 has no Dealer or Vulnerable tag; board 3 has both.
+const INPUT: &str = "\
+[Board \"7\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+
+[Board \"3\"]
+[Dealer \"W\"]
+[Vulnerable \"None\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+";
+
+fn games(out: &str) -> Vec<&str> {
+    out.split("[Event ").skip(1).collect()
+}
+
+#[test]
+fn board_numbers_are_kept() {
+    let input = write_input("renumber-keep", INPUT);
+    let out = run_to_string("renumber-keep", &input, &[]);
+    let games = games(&out);
+    assert_eq!(games.len(), 2, "{out}");
+    assert!(games[0].contains("[Board \"7\"]"), "{out}");
+    assert!(games[1].contains("[Board \"3\"]"), "{out}");
+}
+
+#[test]
+fn renumbered_boards_take_their_standard_tags() {
+    let input = write_input("renumber", INPUT);
+    let out = run_to_string("renumber", &input, &["--renumber-from", "6"]);
+    let games = games(&out);
+    assert_eq!(games.len(), 2, "{out}");
+    // Board 6: dealer East, East-West vulnerable.
+    assert!(games[0].contains("[Board \"6\"]"), "{out}");
+    assert!(games[0].contains("[Dealer \"E\"]\n[Vulnerable \"EW\"]"), "{out}");
+    assert!(games[0].contains("[Auction \"E\"]"), "{out}");
+    // Board 7 keeps the tags it has.
+    assert!(games[1].contains("[Board \"7\"]"), "{out}");
+    assert!(games[1].contains("[Dealer \"W\"]\n[Vulnerable \"None\"]"), "{out}");
+}
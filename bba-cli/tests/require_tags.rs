@@ -0,0 +1,48 @@
+//! A deal missing its Dealer or Vulnerable tag takes the standard value for
+//! its board number; with `--require-dealer` / `--require-vulnerability` it
+//! fails if it has no number either.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+/// Game 1 has neither a board number nor a Dealer/Vulnerable tag. Game 2
+/// lacks both tags but is board 7: dealer South, both vulnerable.
+const INPUT: &str = "\
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+
+[Board \"7\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+";
+
+fn games(out: &str) -> Vec<&str> {
+    out.split("[Event ").skip(1).collect()
+}
+
+#[test]
+fn missing_tags_fail_unless_board_number_supplies_them() {
+    let input = write_input("require-tags", INPUT);
+    let out = run_to_string(
+        "require-tags",
+        &input,
+        &["--require-dealer", "--require-vulnerability"],
+    );
+    let games = games(&out);
+    assert_eq!(games.len(), 2, "{out}");
+    assert!(!games[0].contains("[Auction "), "game 1 should fail:\n{out}");
+    assert!(games[1].contains("[Dealer \"S\"]"), "{out}");
+    assert!(games[1].contains("[Vulnerable \"All\"]"), "{out}");
+    assert!(games[1].contains("[Auction \"S\"]"), "{out}");
+}
+
+#[test]
+fn without_flags_missing_tags_come_from_the_board_or_default() {
+    let input = write_input("require-none", INPUT);
+    let out = run_to_string("require-none", &input, &[]);
+    let games = games(&out);
+    assert_eq!(games.len(), 2, "{out}");
+    assert!(games[0].contains("[Dealer \"N\"]\n[Vulnerable \"None\"]"), "{out}");
+    assert!(games[0].contains("[Auction \"N\"]"), "{out}");
+    assert!(games[1].contains("[Dealer \"S\"]\n[Vulnerable \"All\"]"), "{out}");
+    assert!(games[1].contains("[Auction \"S\"]"), "{out}");
+}
@@ -0,0 +1,53 @@
+//! Round-trip fidelity: `bba-cli --passthrough` must reproduce every input
+//! byte-for-byte — tag spacing, comments, blank lines, and line endings.
+
+mod common;
+
+use common::{bba_cli, fixture_path, write_input};
+use std::fs;
+use std::path::PathBuf;
+
+fn assert_passthrough_identical(label: &str, input: PathBuf) {
+    let tmp = std::env::temp_dir().join(format!("bba-cli-roundtrip-{label}.pbn"));
+    let _ = fs::remove_file(&tmp);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+
+    let status = bba_cli()
+        .args([
+            "--input", input.to_str().unwrap(),
+            "--output", tmp.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--passthrough",
+        ])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli ({label}) exited with {status}");
+
+    let expected = fs::read(&input).expect("read input PBN");
+    let actual = fs::read(&tmp).expect("read produced PBN");
+    assert!(actual == expected, "{label}: passthrough output differs from input");
+}
+
+#[test]
+fn passthrough_round_trips_fixture_corpus() {
+    for (label, rel) in [
+        ("deals", "deals.pbn"),
+        ("golden", "expected/deals-with-sd.pbn"),
+        ("1n", "slow/1N.pbn"),
+        ("fsf", "slow/Fourth_Suit_Forcing.pbn"),
+    ] {
+        assert_passthrough_identical(label, fixture_path(rel));
+    }
+}
+
+#[test]
+fn passthrough_round_trips_irregular_whitespace() {
+    let input = write_input(
+        "roundtrip-irregular",
+        "% PBN 2.1\r\n% EXPORT\r\n\r\n[Event  \"x\"]\r\n{ a comment }\r\n\r\n\r\n\
+         [Board \"2\"]\n[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]   \n\
+         ; trailing comment\n",
+    );
+    assert_passthrough_identical("irregular", input);
+}
@@ -0,0 +1,28 @@
+//! `bba-cli run`: bidding PBN files as a subcommand, and the bare invocation
+//! that stands for it.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string};
+
+#[test]
+fn bare_invocation_is_run() {
+    let input = fixture_path("deals.pbn");
+    let bare = run_to_string("run-bare", &input, &[]);
+
+    let output = std::env::temp_dir().join("bba-cli-run-explicit-out.pbn");
+    let _ = std::fs::remove_file(&output);
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let status = bba_cli()
+        .args([
+            "-v", "run",
+            "--input", input.to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+        ])
+        .status()
+        .expect("failed to spawn bba-cli");
+    assert!(status.success(), "bba-cli run exited with {status}");
+    assert_eq!(std::fs::read_to_string(&output).expect("read produced PBN"), bare);
+}
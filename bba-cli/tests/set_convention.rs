@@ -0,0 +1,35 @@
+//! `--set-convention`: single convention keys set on top of the cards.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string};
+
+#[test]
+fn setting_a_key_to_the_cards_value_changes_nothing() {
+    let input = fixture_path("deals.pbn");
+    let plain = run_to_string("set-convention-plain", &input, &[]);
+    let set = ["--set-convention", "NS:1N-2S Minor Suit Stayman=0", "--set-convention", "ew:1N-2S Minor Suit Stayman=off"];
+    assert_eq!(run_to_string("set-convention-same", &input, &set), plain);
+
+    let worker: Vec<&str> = set.iter().copied().chain(["--timeout", "60"]).collect();
+    assert_eq!(run_to_string("set-convention-worker", &input, &worker), plain);
+}
+
+#[test]
+fn unknown_keys_stop_the_run() {
+    let out = bba_cli()
+        .args(["--input", fixture_path("deals.pbn").to_str().unwrap()])
+        .args(["--output", std::env::temp_dir().join("bba-cli-set-convention-unknown.pbn").to_str().unwrap()])
+        .args(["--set-convention", "NS:No such convention=1"])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("No such convention"));
+
+    let out = bba_cli()
+        .args(["--input", "x.pbn", "--output", "y.pbn", "--set-convention", "NW:Stayman=1"])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("expected NS or EW"));
+}
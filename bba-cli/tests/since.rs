@@ -0,0 +1,64 @@
+//! `--since`: games dated before the cutoff are copied through unchanged,
+//! newer (and, by default, undated) games are bid. Copied games gain "?"
+//! placeholders for the roster tags they lack.
+
+mod common;
+
+use common::{run_to_string, write_input};
+
+const INPUT: &str = "\
+[Board \"1\"]
+[Date \"2024.01.15\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+[Contract \"?\"]
+
+[Board \"2\"]
+[Date \"2024.06.01\"]
+[Dealer \"N\"]
+[Vulnerable \"NS\"]
+[Deal \"N:AT65.8.AK98.AJ62 Q972.43.JT2.T753 KJ843.AQJT5.75.K .K9762.Q643.Q984\"]
+
+[Board \"3\"]
+[Date \"2024.??.??\"]
+[Dealer \"N\"]
+[Vulnerable \"None\"]
+[Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]
+";
+
+#[test]
+fn older_games_are_copied_unchanged() {
+    let input = write_input("since", INPUT);
+    let out = run_to_string("since", &input, &["--since", "2024-03-01"]);
+
+    // Board 1 is older: written exactly as read, with no auction.
+    let board1 = INPUT.split("\n\n").next().unwrap();
+    assert!(out.contains(board1), "board 1 not copied verbatim:\n{out}");
+    // Board 2 is newer and board 3 is undated: both are bid.
+    assert_eq!(out.matches("[Auction ").count(), 2);
+}
+
+#[test]
+fn since_strict_skips_undated_games() {
+    let input = write_input("since-strict", INPUT);
+    let out = run_to_string("since-strict", &input, &["--since", "2024.03.01", "--since-strict"]);
+    assert_eq!(out.matches("[Auction ").count(), 1);
+    assert!(out.contains("[Date \"2024.??.??\"]"));
+}
+
+#[test]
+fn copied_games_get_placeholder_roster_tags() {
+    let board1 = INPUT.split("\n\n").next().unwrap();
+    let input = write_input("since-placeholders", INPUT);
+    let out = run_to_string("since-placeholders", &input, &["--since", "2024-03-01"]);
+    let expected = format!(
+        "[Event \"?\"]\n[Site \"?\"]\n[West \"?\"]\n[North \"?\"]\n[East \"?\"]\n[South \"?\"]\n{}",
+        board1
+    );
+    assert!(out.contains(&expected), "{out}");
+
+    let out = run_to_string("since-no-placeholders", &input, &["--since", "2024-03-01", "--no-placeholder-tags"]);
+    assert!(out.contains(board1), "{out}");
+    assert!(!out.contains("[Event \"?\"]"), "{out}");
+}
@@ -0,0 +1,46 @@
+//! `--input -` / `--output -`: deals piped through stdin come out on stdout
+//! exactly as they would be written to a file.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string};
+use std::process::Stdio;
+
+/// Bid deals.pbn piped through stdin to stdout, with `extra` arguments.
+fn run_piped(extra: &[&str]) -> String {
+    let input = std::fs::File::open(fixture_path("deals.pbn")).unwrap();
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let out = bba_cli()
+        .args(["--input", "-", "--output", "-"])
+        .args(["--ns-conventions", card.to_str().unwrap()])
+        .args(["--ew-conventions", card.to_str().unwrap()])
+        .args(extra)
+        .stdin(Stdio::from(input))
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "bba-cli exited with {}", out.status);
+    String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn stdin_to_stdout_matches_a_file_run() {
+    let file = run_to_string("stdio-file", &fixture_path("deals.pbn"), &[]);
+    assert_eq!(run_piped(&[]), file);
+}
+
+#[test]
+fn streams_from_stdin() {
+    let file = run_to_string("stdio-file-indexed", &fixture_path("deals.pbn"), &["--annotate-index"]);
+    assert_eq!(run_piped(&["--annotate-index", "--stream"]), file);
+}
+
+#[test]
+fn rejects_other_output_on_stdout() {
+    let out = bba_cli()
+        .args(["--input", "-", "--output", "-", "--print-contracts"])
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--print-contracts"));
+}
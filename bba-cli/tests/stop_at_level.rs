@@ -0,0 +1,20 @@
+//! `--stop-at-level`: auctions cut off at a level are written up to that
+//! call and closed with `*`, with no contract or score tags.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+#[test]
+fn truncated_auctions_have_no_contract_or_score_tags() {
+    let input = fixture_path("deals.pbn");
+    let out = run_to_string("stop-at-level", &input, &["--stop-at-level", "1", "--single-dummy"]);
+    assert_eq!(out.matches("[Auction ").count(), 8, "{out}");
+    for tag in ["[Declarer ", "[Contract ", "[Result ", "[Score ", "[Scoring "] {
+        assert!(!out.contains(tag), "{tag} written for a truncated auction:\n{out}");
+    }
+    for game in out.split("[Auction ").skip(1) {
+        let auction = game.split("\n\n").next().unwrap();
+        assert!(auction.trim_end().ends_with('*'), "auction not closed with *:\n{auction}");
+    }
+}
@@ -0,0 +1,40 @@
+//! `--stream`: bidding an input a game at a time as it is read writes the
+//! same output as bidding it whole.
+
+mod common;
+
+use common::{fixture_path, run_to_string, write_input};
+
+#[test]
+fn streaming_matches_a_whole_file_run() {
+    let input = fixture_path("deals.pbn");
+    let whole = run_to_string("stream-whole", &input, &["--annotate-index"]);
+    let streamed = run_to_string("stream-games", &input, &["--annotate-index", "--stream"]);
+    assert_eq!(streamed, whole);
+}
+
+#[test]
+fn streaming_bids_every_run_of_a_deal() {
+    let input = fixture_path("deals.pbn");
+    let whole = run_to_string("stream-whole-rotated", &input, &["--rotate-dealers"]);
+    let streamed = run_to_string("stream-rotated", &input, &["--rotate-dealers", "--stream"]);
+    assert_eq!(streamed, whole);
+}
+
+#[test]
+fn streaming_writes_csv_too() {
+    let input = fixture_path("deals.pbn");
+    let whole = run_to_string("stream-whole-csv", &input, &["--output-format", "csv"]);
+    let streamed = run_to_string("stream-csv", &input, &["--output-format", "csv", "--stream"]);
+    assert_eq!(streamed, whole);
+}
+
+#[test]
+fn streaming_reads_latin1_input() {
+    let mut text = b"{Caf\xe9 duplicate}\n".to_vec();
+    text.extend(std::fs::read(fixture_path("deals.pbn")).expect("read deals.pbn"));
+    let input = write_input("stream-latin1", text);
+    let whole = run_to_string("stream-whole-latin1", &input, &[]);
+    let streamed = run_to_string("stream-latin1", &input, &["--stream"]);
+    assert_eq!(streamed, whole);
+}
@@ -0,0 +1,30 @@
+//! `--summary-json`: one machine-readable line on stdout.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+#[test]
+fn prints_stats_as_json() {
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let output = std::env::temp_dir().join("bba-cli-summary-out.pbn");
+    let result = bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+            "--ns-conventions", card.to_str().unwrap(),
+            "--ew-conventions", card.to_str().unwrap(),
+            "--summary-json",
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(result.status.success());
+
+    let stdout = String::from_utf8(result.stdout).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(stdout.trim()).expect("stdout is JSON");
+    assert_eq!(summary["deals_processed"], 8);
+    assert_eq!(summary["auctions_generated"], 8);
+    assert_eq!(summary["errors"], 0);
+    assert_eq!(summary["deals_skipped"], 0);
+    assert!(summary["elapsed_secs"].as_f64().unwrap() > 0.0);
+}
@@ -0,0 +1,88 @@
+//! `--system-pack`: convention cards loaded by name from a zip bundle, or
+//! from the cards built into the binary.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use zip::write::SimpleFileOptions;
+
+/// A pack with a manifest naming the default card "house" and the GIB card
+/// reachable only by its file stem.
+fn build_pack(label: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("bba-cli-{label}.zip"));
+    let mut zip = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("manifest.json", options).unwrap();
+    zip.write_all(br#"{"house": "cards/21GF-DEFAULT.bbsa"}"#).unwrap();
+    for name in ["21GF-DEFAULT.bbsa", "21GF-GIB.bbsa"] {
+        zip.start_file(format!("cards/{name}"), options).unwrap();
+        zip.write_all(&fs::read(fixture_path(name)).unwrap()).unwrap();
+    }
+    zip.finish().unwrap();
+    path
+}
+
+fn run(label: &str, extra: &[&str]) -> std::process::Output {
+    let output = std::env::temp_dir().join(format!("bba-cli-{label}-out.pbn"));
+    let _ = fs::remove_file(&output);
+    bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--output", output.to_str().unwrap(),
+        ])
+        .args(extra)
+        .output()
+        .expect("failed to spawn bba-cli")
+}
+
+#[test]
+fn loads_systems_by_manifest_name_and_file_stem() {
+    let pack = build_pack("pack");
+    let result = run(
+        "pack",
+        &["--system-pack", pack.to_str().unwrap(), "--ns-system", "house", "--ew-system", "21GF-GIB"],
+    );
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+
+    let out = fs::read_to_string(std::env::temp_dir().join("bba-cli-pack-out.pbn")).unwrap();
+    assert_eq!(out.matches("[Auction ").count(), 8);
+    assert!(out.lines().any(|l| l.starts_with("% CC1 - ") && l.ends_with(":cards/21GF-DEFAULT.bbsa")));
+    assert!(out.lines().any(|l| l.starts_with("% CC2 - ") && l.ends_with(":cards/21GF-GIB.bbsa")));
+}
+
+#[test]
+fn unknown_system_lists_available_names() {
+    let pack = build_pack("pack-missing");
+    let card = fixture_path("21GF-DEFAULT.bbsa");
+    let result = run(
+        "pack-missing",
+        &[
+            "--system-pack", pack.to_str().unwrap(),
+            "--ns-system", "precision",
+            "--ew-conventions", card.to_str().unwrap(),
+        ],
+    );
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("System 'precision' not found"), "{stderr}");
+    assert!(stderr.contains("house, 21GF-DEFAULT, 21GF-GIB"), "{stderr}");
+}
+
+#[test]
+fn built_in_systems_need_no_pack() {
+    let result = run("builtin", &["--ns-system", "sayc", "--ew-system", "2/1"]);
+    assert!(result.status.success(), "{}", String::from_utf8_lossy(&result.stderr));
+
+    let out = fs::read_to_string(std::env::temp_dir().join("bba-cli-builtin-out.pbn")).unwrap();
+    assert_eq!(out.matches("[Auction ").count(), 8);
+    assert!(out.contains("% CC1 - built-in:Sayc.bbsa"), "{out}");
+    assert!(out.contains("% CC2 - built-in:21GF.bbsa"), "{out}");
+
+    let result = run("builtin-unknown", &["--ns-system", "acol"]);
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("sayc, 2/1"));
+}
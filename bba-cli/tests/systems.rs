@@ -0,0 +1,34 @@
+//! `--systems`: every deal bid once per convention file, with a table of
+//! the contracts on stdout and no output file.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+#[test]
+fn tabulates_each_system_per_deal() {
+    let a = fixture_path("21GF-DEFAULT.bbsa");
+    let b = fixture_path("21GF-GIB.bbsa");
+    let systems = format!("{},{}", a.to_str().unwrap(), b.to_str().unwrap());
+
+    let run = bba_cli()
+        .args([
+            "--input", fixture_path("deals.pbn").to_str().unwrap(),
+            "--systems", &systems,
+        ])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(run.status.success(), "bba-cli exited with {}", run.status);
+
+    let stdout = String::from_utf8_lossy(&run.stdout);
+    let tables: Vec<&str> = stdout.split("\n\n").collect();
+    assert_eq!(tables.len(), 8, "{stdout}");
+    for table in tables {
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("Board "), "{table}");
+        assert_eq!(lines.len(), 3, "{table}");
+        assert!(lines[1].trim_start().starts_with("21GF-DEFAULT.bbsa "), "{table}");
+        assert!(lines[2].trim_start().starts_with("21GF-GIB.bbsa "), "{table}");
+        assert!(lines[1..].iter().all(|l| l.contains(" by ") || l.ends_with("passed out")), "{table}");
+    }
+}
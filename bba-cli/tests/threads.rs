@@ -0,0 +1,23 @@
+//! `--threads`: worker processes each bid a share of the deals, and the
+//! joined output matches a single-process run.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+#[test]
+fn workers_match_a_single_process_run() {
+    let input = fixture_path("deals.pbn");
+    let single = run_to_string("threads-1", &input, &["--annotate-index"]);
+    let parallel = run_to_string("threads-3", &input, &["--annotate-index", "--threads", "3"]);
+    assert_eq!(parallel, single);
+    assert_eq!(parallel.matches("[Auction ").count(), 8);
+}
+
+#[test]
+fn more_workers_than_deals_still_bids_each_once() {
+    let input = fixture_path("deals.pbn");
+    let single = run_to_string("threads-1-rotated", &input, &["--rotate-dealers"]);
+    let parallel = run_to_string("threads-16", &input, &["--rotate-dealers", "-j", "16"]);
+    assert_eq!(parallel, single);
+}
@@ -0,0 +1,23 @@
+//! `--timeout` bids on a long-lived worker thread; with a generous limit the
+//! output matches an in-thread run.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+#[test]
+fn worker_output_matches_direct_bidding() {
+    let input = fixture_path("deals.pbn");
+    let direct = run_to_string("timeout-direct", &input, &["--swap-sides"]);
+    let worker = run_to_string("timeout-worker", &input, &["--swap-sides", "--timeout", "60"]);
+    assert_eq!(direct.matches("[Auction ").count(), 16);
+    assert_eq!(direct, worker);
+}
+
+#[test]
+fn deal_timeout_is_an_alias() {
+    let input = fixture_path("deals.pbn");
+    let timeout = run_to_string("timeout-long", &input, &["--timeout", "60"]);
+    let deal_timeout = run_to_string("deal-timeout-long", &input, &["--deal-timeout", "60"]);
+    assert_eq!(timeout, deal_timeout);
+}
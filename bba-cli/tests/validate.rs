@@ -0,0 +1,51 @@
+//! `bba-cli validate`: a file bba-cli bid passes, a tampered one doesn't.
+
+mod common;
+
+use common::{bba_cli, fixture_path, run_to_string, write_input};
+
+fn validate(label: &str, text: &str, extra: &[&str]) -> (bool, String) {
+    let path = write_input(&format!("validate-{label}"), text);
+    let out = bba_cli()
+        .args(["validate", "--input", path.to_str().unwrap()])
+        .args(extra)
+        .output()
+        .expect("failed to spawn bba-cli");
+    (out.status.success(), String::from_utf8(out.stdout).unwrap())
+}
+
+#[test]
+fn accepts_bid_output_and_reports_a_wrong_contract() {
+    let pbn = run_to_string("validate", &fixture_path("deals.pbn"), &[]);
+    let (ok, stdout) = validate("sound", &pbn, &[]);
+    assert!(ok, "{stdout}");
+    assert!(stdout.is_empty(), "{stdout}");
+
+    let contract = pbn.lines().find(|l| l.starts_with("[Contract \"")).unwrap();
+    let tampered = pbn.replacen(contract, "[Contract \"7NTXX\"]", 1);
+    let (ok, stdout) = validate("tampered", &tampered, &[]);
+    assert!(!ok);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains("Contract is 7NTXX"), "{stdout}");
+    let line = pbn.lines().position(|l| l == contract).unwrap() + 1;
+    assert!(stdout.contains(&format!(":{}: game 1 ", line)), "{stdout}");
+}
+
+#[test]
+fn strict_asks_for_every_mandatory_tag() {
+    let pbn = run_to_string("validate-strict", &fixture_path("deals.pbn"), &[]);
+    let (ok, stdout) = validate("strict", &pbn, &["--strict"]);
+    assert!(!ok);
+    assert_eq!(stdout.lines().count(), 8, "{stdout}");
+    assert!(stdout.lines().all(|l| l.ends_with("missing mandatory PBN 2.1 tags: Scoring, Result")), "{stdout}");
+}
+
+#[test]
+fn reports_malformed_tags_with_their_position() {
+    let text = "[Board \"1\"]\n[Dealer \"N\"]\n[Event \"The \\\"Big\\\" Game\"]\n[Site Home]\n\
+                [Deal \"N:8.AQ65.KJ93.K972 KJ62.JT98.T54.63 AQ753.7.A86.A854 T94.K432.Q72.QJT\"]\n";
+    let (ok, stdout) = validate("malformed", text, &[]);
+    assert!(!ok);
+    assert_eq!(stdout.lines().count(), 1, "{stdout}");
+    assert!(stdout.contains(":4: not a tag (column 7): expected '\"' to open the tag value"), "{stdout}");
+}
@@ -0,0 +1,28 @@
+//! `bba-cli validate-conventions`: problems are reported with line numbers.
+
+mod common;
+
+use common::{bba_cli, fixture_path};
+
+#[test]
+fn reports_bad_lines_and_passes_good_cards() {
+    let out = bba_cli()
+        .args(["validate-conventions", fixture_path("21GF-DEFAULT.bbsa").to_str().unwrap()])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stdout));
+
+    let card = std::env::temp_dir().join("bba-cli-bad-card.bbsa");
+    std::fs::write(&card, "System type = 0\nForcing 1NT = 1\nForcing 1NT = 0\nNo such convention = 1\nGarbage\n").unwrap();
+    let out = bba_cli()
+        .args(["validate-conventions", card.to_str().unwrap()])
+        .output()
+        .expect("failed to spawn bba-cli");
+    assert!(!out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "{stdout}");
+    assert!(lines[0].ends_with(":3: Forcing 1NT is already set on line 2"), "{stdout}");
+    assert!(lines[1].ends_with(":4: unknown convention 'No such convention'"), "{stdout}");
+    assert!(lines[2].ends_with(":5: 'Garbage' is not a 'key = value' line"), "{stdout}");
+}
@@ -0,0 +1,22 @@
+//! `--verify-output`: the written file reads back with the intended games
+//! and auctions.
+
+mod common;
+
+use common::{fixture_path, run_to_string};
+
+#[test]
+fn verifies_generated_output() {
+    let out = run_to_string("verify", &fixture_path("deals.pbn"), &["--verify-output"]);
+    assert_eq!(out.matches("[Auction ").count(), 8);
+}
+
+#[test]
+fn verifies_renamed_auction_tag_and_multiple_runs() {
+    let out = run_to_string(
+        "verify-renamed",
+        &fixture_path("deals.pbn"),
+        &["--verify-output", "--auction-tag", "BBAAuction", "--swap-sides"],
+    );
+    assert_eq!(out.matches("[BBAAuction ").count(), 16);
+}
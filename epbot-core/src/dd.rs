@@ -0,0 +1,590 @@
+//! Double-dummy solver.
+//!
+//! Finds how many tricks each declarer takes in each strain when all four
+//! hands are known and everyone plays perfectly. Pure Rust, so no DDS
+//! library has to be shipped next to EPBot.
+//!
+//! The search is a null-window alpha-beta ("can NS take at least n more
+//! tricks?") over whole tricks, narrowed to the exact count by bisection.
+//! Three things keep it fast enough for full deals:
+//! - cards in sequence (no other remaining card between them) are one move;
+//! - winners the side on lead can cash settle a search early;
+//! - results at the start of a trick are cached with the cards whose ranks
+//!   decided them, so later positions that differ only in the smaller cards
+//!   reuse them (a simple form of partition search).
+//!
+//! Results use the layout of `score::par_score_ns`: `tricks[declarer][strain]`
+//! with declarer 0=N, 1=E, 2=S, 3=W and strain C, D, H, S, NT.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hasher};
+
+/// Cards of one hand: suit `s` (0=C, 1=D, 2=H, 3=S) in bits `16*s ..
+/// 16*s+13`, rank 2 at the low bit and the ace at the high one.
+type Hand = u64;
+
+const SUIT_MASK: u64 = 0x1fff;
+
+/// Double-dummy tricks for every declarer and strain of a PBN deal
+/// (`"N:AKQ.xx... ..."`); every hand must hold 13 cards.
+pub fn solve(deal: &str) -> Result<[[u8; 5]; 4], String> {
+    let hands = parse_deal(deal)?;
+    if let Some(seat) = (0..4).find(|&s| hands[s].count_ones() != 13) {
+        return Err(format!("{} does not hold 13 cards", b"NESW"[seat] as char));
+    }
+    let mut tricks = [[0u8; 5]; 4];
+    for strain in 0..5 {
+        let mut solver = Solver::new(hands, (strain < 4).then_some(strain));
+        let mut guess = 7;
+        for (declarer, row) in tricks.iter_mut().enumerate() {
+            let ns = solver.ns_tricks((declarer + 1) % 4, guess);
+            guess = ns;
+            row[strain] = if declarer.is_multiple_of(2) { ns } else { 13 - ns };
+        }
+    }
+    Ok(tricks)
+}
+
+/// Tricks NS take with `leader` on lead, trumps `trump` (None for NT).
+/// Hands may hold any equal number of cards, so endings can be solved too.
+pub fn ns_tricks(deal: &str, trump: Option<usize>, leader: usize) -> Result<u8, String> {
+    let hands = parse_deal(deal)?;
+    let n = hands[0].count_ones();
+    if hands.iter().any(|h| h.count_ones() != n) {
+        return Err("hands hold different numbers of cards".to_string());
+    }
+    if trump.is_some_and(|t| t > 3) || leader > 3 {
+        return Err("trump or leader out of range".to_string());
+    }
+    Ok(Solver::new(hands, trump).ns_tricks(leader, n as u8 / 2))
+}
+
+/// The four hands of a PBN deal, indexed N, E, S, W.
+fn parse_deal(deal: &str) -> Result<[Hand; 4], String> {
+    let deal = deal.trim();
+    let (first, rest) = deal
+        .split_once(':')
+        .ok_or_else(|| format!("'{}' has no first seat", deal))?;
+    let first = match first.trim() {
+        "N" | "n" => 0,
+        "E" | "e" => 1,
+        "S" | "s" => 2,
+        "W" | "w" => 3,
+        other => return Err(format!("unknown seat '{}'", other)),
+    };
+    let parts: Vec<&str> = rest.split_whitespace().collect();
+    if parts.len() != 4 {
+        return Err(format!("expected 4 hands, found {}", parts.len()));
+    }
+    let mut hands = [0; 4];
+    let mut seen = 0u64;
+    for (i, part) in parts.iter().enumerate() {
+        let suits: Vec<&str> = part.split('.').collect();
+        if suits.len() != 4 {
+            return Err(format!("hand '{}' does not have 4 suits", part));
+        }
+        let mut hand = 0;
+        // PBN lists spades first.
+        for (s, cards) in suits.iter().enumerate() {
+            let suit = 3 - s;
+            for c in cards.chars() {
+                let rank = "23456789TJQKA"
+                    .find(c.to_ascii_uppercase())
+                    .ok_or_else(|| format!("unknown card '{}'", c))?;
+                let bit = 1u64 << (16 * suit + rank);
+                if seen & bit != 0 {
+                    return Err(format!("card {}{} dealt twice", "CDHS".as_bytes()[suit] as char, c));
+                }
+                seen |= bit;
+                hand |= bit;
+            }
+        }
+        hands[(first + i) % 4] = hand;
+    }
+    Ok(hands)
+}
+
+/// Search state for one strain.
+struct Solver {
+    hands: [Hand; 4],
+    trump: Option<usize>,
+    /// Bounds found so far, by suit lengths of every hand and leader.
+    cache: HashMap<(u64, u8), Vec<Bound>, BuildHasherDefault<KeyHasher>>,
+}
+
+/// A bound on the tricks NS take from a trick-start position. It holds for
+/// every position with the same suit lengths and leader in which the top
+/// `top[s]` cards of each suit `s` have the same owners: the search that
+/// found it never depended on the smaller cards.
+struct Bound {
+    lower: bool,
+    tricks: u8,
+    top: [u8; 4],
+    /// Owners of the remaining cards of each suit, two bits per card from
+    /// the highest down.
+    owners: [u32; 4],
+}
+
+impl Bound {
+    fn matches(&self, owners: &[u32; 4]) -> bool {
+        (0..4).all(|s| (self.owners[s] ^ owners[s]) & ((1u32 << (2 * self.top[s])) - 1) == 0)
+    }
+}
+
+/// Up to 13 moves, as `Solver::moves` gives them.
+struct Moves {
+    list: [(u64, u64); 13],
+    len: usize,
+}
+
+impl Moves {
+    fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.list[..self.len].iter().copied()
+    }
+}
+
+/// A multiply-rotate hash for the cache keys; SipHash is most of the
+/// search time otherwise.
+#[derive(Default)]
+struct KeyHasher(u64);
+
+impl Hasher for KeyHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Solver {
+    fn new(hands: [Hand; 4], trump: Option<usize>) -> Self {
+        Solver {
+            hands,
+            trump,
+            cache: HashMap::default(),
+        }
+    }
+
+    /// Exact NS tricks with `leader` on lead, searching outward from
+    /// `guess`: each wrong guess costs one more search.
+    fn ns_tricks(&mut self, leader: usize, guess: u8) -> u8 {
+        let left = self.hands[0].count_ones() as u8;
+        let mut tricks = guess.min(left);
+        if self.can_take(leader, tricks).0 {
+            while tricks < left && self.can_take(leader, tricks + 1).0 {
+                tricks += 1;
+            }
+        } else {
+            tricks -= 1;
+            while !self.can_take(leader, tricks).0 {
+                tricks -= 1;
+            }
+        }
+        tricks
+    }
+
+    /// Whether NS can take `target` of the remaining tricks, `leader` to
+    /// lead, and the cards whose ranks decided it.
+    fn can_take(&mut self, leader: usize, target: u8) -> (bool, u64) {
+        let left = self.hands[0].count_ones() as u8;
+        if target == 0 {
+            return (true, 0);
+        }
+        if target > left {
+            return (false, 0);
+        }
+        // Tricks the side on lead can cash straight away.
+        let (quick, winners) = self.quick_tricks(leader);
+        if leader.is_multiple_of(2) && quick >= target {
+            return (true, winners);
+        }
+        if leader % 2 == 1 && left - quick < target {
+            return (false, winners);
+        }
+
+        let key = (self.lengths(), leader as u8);
+        let owners = self.owners();
+        if let Some(bound) = self.cache.get(&key).and_then(|bounds| {
+            bounds.iter().find(|b| {
+                let decides = if b.lower { b.tricks >= target } else { b.tricks < target };
+                decides && b.matches(&owners)
+            })
+        }) {
+            return (bound.lower, self.top_cards(&bound.top));
+        }
+
+        let ns = leader.is_multiple_of(2);
+        let mut result = !ns;
+        let mut relevant = 0;
+        for (card, equals) in self.moves(leader, 0, &[0; 4]).iter() {
+            self.hands[leader] &= !card;
+            let (outcome, cards) = self.play(leader, 1, [card, 0, 0, 0], target);
+            self.hands[leader] |= card;
+            if outcome == ns {
+                result = outcome;
+                relevant = cards;
+                break;
+            }
+            relevant |= widen(cards, card, equals);
+        }
+
+        let bound = Bound {
+            lower: result,
+            tricks: if result { target } else { target - 1 },
+            top: self.top_counts(relevant),
+            owners,
+        };
+        let bounds = self.cache.entry(key).or_default();
+        match bounds.iter_mut().find(|b| b.lower == bound.lower && b.top == bound.top && b.matches(&owners)) {
+            Some(b) if b.lower => b.tricks = b.tricks.max(bound.tricks),
+            Some(b) => b.tricks = b.tricks.min(bound.tricks),
+            None => bounds.push(bound),
+        }
+        (result, relevant)
+    }
+
+    /// Play card `pos` (0 = the lead) of the current trick; `played` holds
+    /// the bits of the cards already on the table, in order. Returns as
+    /// `can_take` does.
+    fn play(&mut self, leader: usize, pos: usize, played: [u64; 4], target: u8) -> (bool, u64) {
+        if pos == 4 {
+            let won = self.trick_winner(&played);
+            let winner = (leader + won) % 4;
+            let (result, mut relevant) = self.can_take(winner, target - (winner.is_multiple_of(2)) as u8);
+            // The winning card's rank mattered if it beat a card of its suit.
+            let suit = suit_of(played[won]);
+            if played.iter().enumerate().any(|(i, &c)| i != won && suit_of(c) == suit) {
+                relevant |= played[won];
+            }
+            return (result, relevant);
+        }
+        let seat = (leader + pos) % 4;
+        let ns = seat.is_multiple_of(2);
+        let mut relevant = 0;
+        for (card, equals) in self.moves(seat, pos, &played).iter() {
+            let mut next = played;
+            next[pos] = card;
+            self.hands[seat] &= !card;
+            let (outcome, cards) = self.play(leader, pos + 1, next, target);
+            self.hands[seat] |= card;
+            if outcome == ns {
+                return (outcome, cards);
+            }
+            relevant |= widen(cards, card, equals);
+        }
+        (!ns, relevant)
+    }
+
+    /// Winners the side on lead can cash, and those cards: `leader`'s
+    /// runs of top cards, then, if `leader` can reach partner in a suit
+    /// partner heads, partner's runs too. While the opponents hold trumps no
+    /// suit yields more side-suit winners than both opponents can follow to.
+    fn quick_tricks(&self, leader: usize) -> (u8, u64) {
+        let partner = (leader + 2) % 4;
+        let (own, own_cards) = self.runs(leader);
+        let (theirs, their_cards) = self.runs(partner);
+        let own_total: u32 = own.iter().sum();
+        let their_total: u32 = theirs.iter().sum();
+        // Partner has to follow to `leader`'s winners without throwing any
+        // of their own.
+        let spare = self.hands[partner].count_ones() - their_total;
+        let entry = (0..4).any(|suit| theirs[suit] > 0 && self.hands[leader] >> (16 * suit) & SUIT_MASK != 0);
+        if entry && spare >= own_total {
+            ((own_total + their_total) as u8, own_cards | their_cards)
+        } else {
+            (own_total as u8, own_cards)
+        }
+    }
+
+    /// How many top cards of each suit `seat` holds in a row, capped while
+    /// the opponents can ruff; and those cards.
+    fn runs(&self, seat: usize) -> ([u32; 4], u64) {
+        let (lho, rho) = ((seat + 1) % 4, (seat + 3) % 4);
+        let length = |seat: usize, suit: usize| (self.hands[seat] >> (16 * suit) & SUIT_MASK).count_ones();
+        let opponents_ruff = self.trump.is_some_and(|t| length(lho, t) + length(rho, t) > 0);
+        let all = self.remaining();
+        let mut runs = [0; 4];
+        let mut cards = 0;
+        for (suit, run) in runs.iter_mut().enumerate() {
+            let limit = if opponents_ruff && Some(suit) != self.trump {
+                length(lho, suit).min(length(rho, suit))
+            } else {
+                13
+            };
+            let mut rest = all & SUIT_MASK << (16 * suit);
+            while *run < limit && rest != 0 {
+                let high = 1 << (63 - rest.leading_zeros());
+                if self.hands[seat] & high == 0 {
+                    break;
+                }
+                *run += 1;
+                cards |= high;
+                rest &= !high;
+            }
+        }
+        (runs, cards)
+    }
+
+    /// Index in `played` of the card winning the trick so far.
+    fn trick_winner(&self, played: &[u64]) -> usize {
+        let mut best = 0;
+        for (i, &card) in played.iter().enumerate().skip(1) {
+            let (suit, best_suit) = (suit_of(card), suit_of(played[best]));
+            // A card off the winning suit wins only by trumping.
+            if (suit == best_suit && card > played[best]) || (suit != best_suit && Some(suit) == self.trump) {
+                best = i;
+            }
+        }
+        best
+    }
+
+    /// The cards `seat` may play, one per sequence, in a rough best-first
+    /// order. Each comes with the lower cards of its sequence, which are not
+    /// tried: playing one of them leaves the same position.
+    fn moves(&self, seat: usize, pos: usize, played: &[u64; 4]) -> Moves {
+        let hand = self.hands[seat];
+        let on_table = played[..pos].iter().fold(0, |a, c| a | c);
+        let present = self.hands.iter().fold(on_table, |a, h| a | h);
+        let follows = pos > 0 && hand >> (16 * suit_of(played[0])) & SUIT_MASK != 0;
+        let mut moves = Moves {
+            list: [(0, 0); 13],
+            len: 0,
+        };
+        for suit in (0..4).rev() {
+            if follows && suit != suit_of(played[0]) {
+                continue;
+            }
+            let mine = hand >> (16 * suit) & SUIT_MASK;
+            let all = present >> (16 * suit) & SUIT_MASK;
+            for rank in (0..13).rev() {
+                if mine & (1 << rank) == 0 {
+                    continue;
+                }
+                let card = 1u64 << (16 * suit + rank);
+                // A card whose next higher remaining card is ours too joins
+                // that card's sequence.
+                let above = all & !((2u64 << rank) - 1);
+                if moves.len > 0 && above != 0 && mine & (above & above.wrapping_neg()) != 0 {
+                    moves.list[moves.len - 1].1 |= card;
+                } else {
+                    moves.list[moves.len] = (card, 0);
+                    moves.len += 1;
+                }
+            }
+        }
+        let list = &mut moves.list[..moves.len];
+        if pos == 0 {
+            // Leading: winners first, then the rest from the bottom up.
+            let winner = |card: u64| {
+                let suit = suit_of(card);
+                present >> (16 * suit) & SUIT_MASK < (card >> (16 * suit)) << 1
+            };
+            let (wins, rest): (Vec<_>, Vec<_>) = list.iter().partition(|&&(card, _)| winner(card));
+            for (slot, m) in list.iter_mut().zip(wins.into_iter().chain(rest.into_iter().rev())) {
+                *slot = m;
+            }
+        } else if pos >= 2 && self.trick_winner(&played[..pos]) == pos - 2 {
+            // Partner is winning: the lowest cards first.
+            list.sort_unstable();
+        } else {
+            // Otherwise the cheapest card that wins the trick so far first,
+            // then the rest from the bottom up.
+            list.sort_unstable_by_key(|&(card, _)| {
+                let mut trick = *played;
+                trick[pos] = card;
+                (self.trick_winner(&trick[..=pos]) != pos, card)
+            });
+        }
+        moves
+    }
+
+    /// Every card still held.
+    fn remaining(&self) -> u64 {
+        self.hands.iter().fold(0, |a, h| a | h)
+    }
+
+    /// Each hand's suit lengths, four bits apiece.
+    fn lengths(&self) -> u64 {
+        let mut key = 0;
+        for (seat, hand) in self.hands.iter().enumerate() {
+            for suit in 0..4 {
+                key |= ((hand >> (16 * suit) & SUIT_MASK).count_ones() as u64) << (16 * seat + 4 * suit);
+            }
+        }
+        key
+    }
+
+    /// Owners of the remaining cards of each suit, as in `Bound::owners`.
+    fn owners(&self) -> [u32; 4] {
+        let mut owners = [0; 4];
+        for (suit, packed) in owners.iter_mut().enumerate() {
+            let mut rest = self.remaining() & SUIT_MASK << (16 * suit);
+            let mut n = 0;
+            while rest != 0 {
+                let high = 1 << (63 - rest.leading_zeros());
+                let seat = (1..4).find(|&s| self.hands[s] & high != 0).unwrap_or(0);
+                *packed |= (seat as u32) << (2 * n);
+                n += 1;
+                rest &= !high;
+            }
+        }
+        owners
+    }
+
+    /// How many of each suit's remaining cards rank at or above the lowest
+    /// of `cards` in that suit.
+    fn top_counts(&self, cards: u64) -> [u8; 4] {
+        let all = self.remaining();
+        let mut top = [0; 4];
+        for (suit, count) in top.iter_mut().enumerate() {
+            let these = cards >> (16 * suit) & SUIT_MASK;
+            if these != 0 {
+                let at_or_above = !((1u64 << these.trailing_zeros()) - 1);
+                *count = (all >> (16 * suit) & SUIT_MASK & at_or_above).count_ones() as u8;
+            }
+        }
+        top
+    }
+
+    /// The top `top[s]` remaining cards of each suit `s`.
+    fn top_cards(&self, top: &[u8; 4]) -> u64 {
+        let mut cards = 0;
+        for (suit, &count) in top.iter().enumerate() {
+            let mut rest = self.remaining() & SUIT_MASK << (16 * suit);
+            for _ in 0..count {
+                let high = 1 << (63 - rest.leading_zeros());
+                cards |= high;
+                rest &= !high;
+            }
+        }
+        cards
+    }
+}
+
+/// The cards a search after playing `card` depended on, widened to cover
+/// the untried `equals` in sequence with it: each of those would have left
+/// `card` behind in its place.
+fn widen(cards: u64, card: u64, equals: u64) -> u64 {
+    let mut wide = cards;
+    if cards & card != 0 {
+        wide |= equals;
+    }
+    if cards & equals != 0 {
+        wide |= card;
+    }
+    wide
+}
+
+/// The suit of a single-card bit.
+fn suit_of(card: u64) -> usize {
+    card.trailing_zeros() as usize / 16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plain minimax over every legal card, for checking the solver.
+    fn brute_force(hands: &mut [Hand; 4], trump: Option<usize>, leader: usize) -> u8 {
+        fn go(hands: &mut [Hand; 4], trump: Option<usize>, leader: usize, pos: usize, played: [u64; 4]) -> u8 {
+            if hands[0] == 0 && pos == 0 {
+                return 0;
+            }
+            let solver = Solver::new(*hands, trump);
+            if pos == 4 {
+                let winner = (leader + solver.trick_winner(&played)) % 4;
+                return (winner.is_multiple_of(2)) as u8 + go(hands, trump, winner, 0, [0; 4]);
+            }
+            let seat = (leader + pos) % 4;
+            let mut legal = hands[seat];
+            if pos > 0 {
+                let led = SUIT_MASK << (16 * suit_of(played[0]));
+                if legal & led != 0 {
+                    legal &= led;
+                }
+            }
+            let mut results = Vec::new();
+            while legal != 0 {
+                let card = legal & legal.wrapping_neg();
+                legal &= !card;
+                let mut next = played;
+                next[pos] = card;
+                hands[seat] &= !card;
+                results.push(go(hands, trump, leader, pos + 1, next));
+                hands[seat] |= card;
+            }
+            let best = if seat.is_multiple_of(2) { results.iter().max() } else { results.iter().min() };
+            *best.unwrap()
+        }
+        go(hands, trump, leader, 0, [0; 4])
+    }
+
+    /// A random deal of `n` cards to each hand (xorshift, fixed seed).
+    fn random_ending(seed: &mut u64, n: usize) -> [Hand; 4] {
+        let mut deck: Vec<u64> = (0..4).flat_map(|s| (0..13).map(move |r| 1u64 << (16 * s + r))).collect();
+        for i in (1..deck.len()).rev() {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            deck.swap(i, (*seed % (i as u64 + 1)) as usize);
+        }
+        let mut hands = [0; 4];
+        for (i, card) in deck.iter().take(4 * n).enumerate() {
+            hands[i % 4] |= card;
+        }
+        hands
+    }
+
+    #[test]
+    fn agrees_with_brute_force_on_endings() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..40 {
+            let mut hands = random_ending(&mut seed, 4);
+            for trump in [None, Some(0), Some(3)] {
+                for leader in 0..4 {
+                    let expected = brute_force(&mut hands, trump, leader);
+                    assert_eq!(Solver::new(hands, trump).ns_tricks(leader, 2), expected, "{:x?} {:?} {}", hands, trump, leader);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solves_a_full_deal() {
+        // Each hand holds one whole suit: N spades, E hearts, S diamonds, W clubs.
+        let tricks = solve("N:AKQJT98765432... .AKQJT98765432.. ..AKQJT98765432. ...AKQJT98765432").unwrap();
+        // In a suit contract declarer's side takes every trick only when it
+        // holds the trumps; in NT the opening leader runs the suit.
+        assert_eq!(tricks[0], [0, 13, 0, 13, 0]);
+        assert_eq!(tricks[2], [0, 13, 0, 13, 0]);
+        assert_eq!(tricks[1][2], 13);
+        assert_eq!(tricks[1][4], 0);
+    }
+
+    #[test]
+    fn solves_small_endings() {
+        // Two-card ending: N holds the AK of spades, everyone else small spades.
+        assert_eq!(ns_tricks("N:AK... 32... 54... 76...", None, 1), Ok(2));
+        // E leads hearts and N can ruff one of them.
+        assert_eq!(ns_tricks("N:2.3.. .AK.. .54.. .76..", Some(3), 1), Ok(1));
+        assert_eq!(ns_tricks("N:2.3.. .AK.. .54.. .76..", None, 1), Ok(0));
+    }
+
+    #[test]
+    fn rejects_bad_deals() {
+        assert!(solve("N:AKQ... ... ... ...").is_err());
+        assert!(solve("AKQJT98765432... .AKQJT98765432.. ..AKQJT98765432. ...AKQJT98765432").is_err());
+        assert!(ns_tricks("N:AK... 3... 54... 76...", None, 0).is_err());
+        assert!(ns_tricks("N:A... A... 5... 7...", None, 0).is_err());
+    }
+}
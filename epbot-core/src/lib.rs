@@ -4,9 +4,11 @@
 //! Rust API for generating bridge auctions. Used by both the CLI and web server.
 
 pub mod bba_hash;
+pub mod dd;
 pub mod ffi;
 pub mod score;
 
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
 use std::os::raw::{c_char, c_void};
 use thiserror::Error;
@@ -15,7 +17,7 @@ use thiserror::Error;
 pub use ffi::{ERR_BUFFER_TOO_SMALL, ERR_EXCEPTION, ERR_NULL_HANDLE, OK};
 
 /// Errors from the EPBot engine.
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum EPBotError {
     #[error("Failed to create EPBot instance")]
     CreateFailed,
@@ -27,6 +29,19 @@ pub enum EPBotError {
     ConventionError(String),
 }
 
+impl EPBotError {
+    /// The numeric code of an `FfiError`: negative codes come straight from
+    /// the EPBot call that failed (`ERR_NULL_HANDLE`, `ERR_EXCEPTION`, ...),
+    /// 0 marks a failure detected on the Rust side of the FFI. None for the
+    /// other variants.
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            EPBotError::FfiError { code, .. } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
 /// A single bid in an auction with optional meaning.
 #[derive(Debug, Clone)]
 pub struct BidInfo {
@@ -63,8 +78,23 @@ pub struct AuctionResult {
     pub bids: Vec<BidInfo>,
     pub success: bool,
     pub error: Option<String>,
+    /// The typed error behind `error`, for callers that want to tell
+    /// failures apart (e.g. skip bad deals but stop on convention errors).
+    /// None on success, and for failures raised outside the engine.
+    pub failure: Option<EPBotError>,
     /// Single-dummy analysis, if requested via `AuctionOptions::single_dummy`.
     pub analysis: Option<SingleDummyAnalysis>,
+    /// True when bidding stopped early because of `AuctionOptions::stop_at_level`.
+    /// A truncated auction is not a legal complete auction: it ends on the
+    /// call that reached the level, without the closing passes.
+    pub truncated: bool,
+}
+
+impl AuctionResult {
+    /// The raw EPBot error code, if the auction failed in an FFI call.
+    pub fn error_code(&self) -> Option<i32> {
+        self.failure.as_ref()?.code()
+    }
 }
 
 /// Scoring mode for the auction.
@@ -80,6 +110,41 @@ impl Default for Scoring {
     }
 }
 
+/// Per-auction settings for `generate_auction_with`.
+///
+/// `Default` gives matchpoint scoring with EPBot's built-in conventions and no
+/// extras, i.e. the same as `generate_auction(pbn, dealer, vul, Scoring::Matchpoints, None, None)`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AuctionOptions<'a> {
+    pub scoring: Scoring,
+    /// Convention card for NS (or None for defaults)
+    pub ns_card: Option<&'a ConventionCard>,
+    /// Convention card for EW (or None for defaults)
+    pub ew_card: Option<&'a ConventionCard>,
+    /// Per-seat cards, indexed N, E, S, W. A seat given a card bids its own
+    /// side with it instead of the partnership card, so its partner may play
+    /// a different system; every other seat still reads that side with the
+    /// partnership card.
+    pub seat_cards: [Option<&'a ConventionCard>; 4],
+    /// Conventions switched on or off after the cards are loaded, in order.
+    /// An auction fails if the engine doesn't know one of the keys.
+    pub conventions: &'a [ConventionOverride],
+    /// Bids forced for the first N positions before EPBot takes over.
+    pub auction_prefix: Option<&'a [String]>,
+    /// Request single-dummy trick estimates once the auction completes.
+    pub single_dummy: bool,
+    /// Stop bidding as soon as a contract bid at this level (1..=7) or higher
+    /// is made. The result is flagged `truncated`.
+    pub stop_at_level: Option<u8>,
+    /// Partnership forced to pass throughout (0 = NS, 1 = EW), so the other
+    /// side bids uncontested. The forced passes are recorded in the auction.
+    pub passing_side: Option<u8>,
+    /// Engine bidding-style setting switched on for both partnerships, on
+    /// top of the convention cards (see `check_style`). An auction fails if
+    /// the engine doesn't recognize the name.
+    pub style: Option<&'a str>,
+}
+
 /// Parsed convention card content (lines from a .bbsa file).
 #[derive(Debug, Clone)]
 pub struct ConventionCard {
@@ -99,9 +164,11 @@ impl ConventionCard {
         Self { lines }
     }
 
-    /// Load conventions into an EPBot instance for the given side (0=NS, 1=EW).
-    /// Mirrors the C# LoadConventions logic from EPBotService.cs.
-    fn apply_to(&self, instance: *mut c_void, side: i32) -> Result<(), EPBotError> {
+    /// The settings this card applies, in file order: how `apply_to`
+    /// reads each `key = value` line. Comments (`#`, `;`), blank lines and
+    /// lines whose value is neither an integer nor true/false are skipped.
+    pub fn settings(&self) -> Vec<ConventionSetting> {
+        let mut settings = Vec::new();
         for line in &self.lines {
             let trimmed = line.trim();
             if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
@@ -117,33 +184,113 @@ impl ConventionCard {
             let value_str = parts[1].trim();
 
             // Try parsing as integer
-            if let Ok(int_value) = value_str.parse::<i32>() {
+            let setting = if let Ok(int_value) = value_str.parse::<i32>() {
                 if key.eq_ignore_ascii_case("System type") {
-                    let rc = unsafe { ffi::epbot_set_system_type(instance, side, int_value) };
-                    if rc < 0 && rc != ffi::ERR_EXCEPTION {
-                        log::warn!("set_system_type({}, {}) returned {}", side, int_value, rc);
-                    }
+                    ConventionSetting::SystemType(int_value)
                 } else if key.eq_ignore_ascii_case("Opponent type") {
-                    let rc = unsafe { ffi::epbot_set_opponent_type(instance, side, int_value) };
-                    if rc < 0 && rc != ffi::ERR_EXCEPTION {
-                        log::warn!("set_opponent_type({}, {}) returned {}", side, int_value, rc);
-                    }
-                } else if let Ok(key_c) = CString::new(key) {
-                    let bool_val = if int_value == 1 { 1 } else { 0 };
-                    unsafe {
-                        ffi::epbot_set_conventions(instance, side, key_c.as_ptr(), bool_val);
+                    ConventionSetting::OpponentType(int_value)
+                } else {
+                    ConventionSetting::Convention {
+                        key: key.to_string(),
+                        enabled: int_value == 1,
                     }
                 }
             } else if value_str.eq_ignore_ascii_case("true") {
-                if let Ok(key_c) = CString::new(key) {
-                    unsafe {
-                        ffi::epbot_set_conventions(instance, side, key_c.as_ptr(), 1);
-                    }
+                ConventionSetting::Convention {
+                    key: key.to_string(),
+                    enabled: true,
                 }
             } else if value_str.eq_ignore_ascii_case("false") {
-                if let Ok(key_c) = CString::new(key) {
-                    unsafe {
-                        ffi::epbot_set_conventions(instance, side, key_c.as_ptr(), 0);
+                ConventionSetting::Convention {
+                    key: key.to_string(),
+                    enabled: false,
+                }
+            } else {
+                continue;
+            };
+            settings.push(setting);
+        }
+        settings
+    }
+
+    /// Every line `settings` would skip or the engine would misread, in file
+    /// order: lines that aren't `key = value`, values that are neither an
+    /// integer nor true/false, convention values other than 0 and 1 (read
+    /// as off), keys the engine doesn't know, and keys set twice. Comments,
+    /// blank lines and BBA's `Not defined` placeholders are fine.
+    pub fn check(&self) -> Result<Vec<CardProblem>, EPBotError> {
+        let inst = unsafe { ffi::epbot_create() };
+        if inst.is_null() {
+            return Err(EPBotError::CreateFailed);
+        }
+        let problems = self.check_with(|key| convention_key(inst, key).is_ok());
+        unsafe { ffi::epbot_destroy(inst) };
+        Ok(problems)
+    }
+
+    /// `check`, with `known` deciding which convention keys exist.
+    fn check_with(&self, known: impl Fn(&str) -> bool) -> Vec<CardProblem> {
+        let mut problems = Vec::new();
+        let mut seen: Vec<(String, usize)> = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            let line_no = i + 1;
+            let mut problem = |message: String| problems.push(CardProblem { line: line_no, message });
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                continue;
+            }
+            let Some((key, value)) = trimmed.split_once('=') else {
+                problem(format!("'{}' is not a 'key = value' line", trimmed));
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            // BBA pads its files with unused `Not defined` slots.
+            if key.eq_ignore_ascii_case("Not defined") {
+                continue;
+            }
+            let typed = key.eq_ignore_ascii_case("System type") || key.eq_ignore_ascii_case("Opponent type");
+            match value.parse::<i32>() {
+                Ok(n) if typed || n == 0 || n == 1 => {}
+                Ok(n) => problem(format!("{} = {}: a convention is on (1) or off (0); {} reads as off", key, n, n)),
+                Err(_) if !typed && (value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false")) => {}
+                Err(_) => {
+                    problem(format!("{} = {}: value is not a number{}", key, value, if typed { "" } else { " or true/false" }));
+                    continue;
+                }
+            }
+            if !typed && !known(key) {
+                problem(format!("unknown convention '{}'", key));
+            }
+            match seen.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)) {
+                Some((_, first)) => problem(format!("{} is already set on line {}", key, first)),
+                None => seen.push((key.to_string(), line_no)),
+            }
+        }
+        problems
+    }
+
+    /// Load conventions into an EPBot instance for the given side (0=NS, 1=EW).
+    /// Mirrors the C# LoadConventions logic from EPBotService.cs.
+    fn apply_to(&self, instance: *mut c_void, side: i32) -> Result<(), EPBotError> {
+        for setting in self.settings() {
+            match setting {
+                ConventionSetting::SystemType(value) => {
+                    let rc = unsafe { ffi::epbot_set_system_type(instance, side, value) };
+                    if rc < 0 && rc != ffi::ERR_EXCEPTION {
+                        log::warn!("set_system_type({}, {}) returned {}", side, value, rc);
+                    }
+                }
+                ConventionSetting::OpponentType(value) => {
+                    let rc = unsafe { ffi::epbot_set_opponent_type(instance, side, value) };
+                    if rc < 0 && rc != ffi::ERR_EXCEPTION {
+                        log::warn!("set_opponent_type({}, {}) returned {}", side, value, rc);
+                    }
+                }
+                ConventionSetting::Convention { key, enabled } => {
+                    if let Ok(key_c) = CString::new(key) {
+                        unsafe {
+                            ffi::epbot_set_conventions(instance, side, key_c.as_ptr(), enabled as i32);
+                        }
                     }
                 }
             }
@@ -152,6 +299,36 @@ impl ConventionCard {
     }
 }
 
+/// A line of a convention card that is wrong or suspicious (see
+/// `ConventionCard::check`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardProblem {
+    /// 1-based line number in the card
+    pub line: usize,
+    pub message: String,
+}
+
+/// One setting from a convention card (see `ConventionCard::settings`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConventionSetting {
+    /// `System type = N`
+    SystemType(i32),
+    /// `Opponent type = N`
+    OpponentType(i32),
+    /// Any other key, switched on (`1`/`true`) or off (anything else).
+    Convention { key: String, enabled: bool },
+}
+
+/// One convention key set for one partnership on top of its convention
+/// card (see `AuctionOptions::conventions`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConventionOverride {
+    /// 0 = NS, 1 = EW
+    pub side: i32,
+    pub key: String,
+    pub enabled: bool,
+}
+
 /// Parse a PBN deal string into per-player hands in EPBot's C.D.H.S order.
 ///
 /// Input format: "N:AKQ.JT9.876.543 ... ... ..."
@@ -190,6 +367,14 @@ fn parse_pbn_deal(pbn: &str) -> Result<(i32, [String; 4]), EPBotError> {
 
     for (i, hand_str) in hand_parts.iter().enumerate() {
         let pos = (first_seat + i as i32) % 4;
+        // Each EPBot seat only ever sees its own cards, so an unknown hand
+        // can't be bid around: that seat has nothing to bid from.
+        if *hand_str == "-" {
+            return Err(EPBotError::InvalidDeal(format!(
+                "Hand {} is unknown ('-'); EPBot needs all four hands",
+                i
+            )));
+        }
         let suits: Vec<&str> = hand_str.split('.').collect();
 
         if suits.len() != 4 {
@@ -303,6 +488,83 @@ pub fn copyright() -> Result<String, EPBotError> {
     Ok(s)
 }
 
+/// Check that the engine has a bidding-style setting called `name`, so an
+/// unknown `AuctionOptions::style` is reported before any deal is bid.
+///
+/// EPBot exposes no dedicated style call; styles are settings looked up by
+/// name like any other convention key, and this EPBot build may have none.
+pub fn check_style(name: &str) -> Result<(), EPBotError> {
+    let inst = unsafe { ffi::epbot_create() };
+    if inst.is_null() {
+        return Err(EPBotError::CreateFailed);
+    }
+    let result = style_index(inst, name).map(|_| ());
+    unsafe { ffi::epbot_destroy(inst) };
+    result
+}
+
+/// Check that the engine has a convention called `key`, so an unknown
+/// `AuctionOptions::conventions` key is reported before any deal is bid.
+pub fn check_convention(key: &str) -> Result<(), EPBotError> {
+    let inst = unsafe { ffi::epbot_create() };
+    if inst.is_null() {
+        return Err(EPBotError::CreateFailed);
+    }
+    let result = convention_key(inst, key).map(|_| ());
+    unsafe { ffi::epbot_destroy(inst) };
+    result
+}
+
+/// `key` as a C string, or an error if the engine doesn't know it.
+fn convention_key(instance: *mut c_void, key: &str) -> Result<CString, EPBotError> {
+    let unknown = || EPBotError::ConventionError(format!("EPBot has no convention '{}'", key));
+    let key_c = CString::new(key).map_err(|_| unknown())?;
+    if unsafe { ffi::epbot_convention_index(instance, key_c.as_ptr()) } < 0 {
+        return Err(unknown());
+    }
+    Ok(key_c)
+}
+
+/// Switch convention `key` on or off for `side` (0=NS, 1=EW) of `instance`.
+fn set_convention(instance: *mut c_void, side: i32, key: &str, enabled: bool) -> Result<(), EPBotError> {
+    let key_c = convention_key(instance, key)?;
+    let rc = unsafe { ffi::epbot_set_conventions(instance, side, key_c.as_ptr(), enabled as i32) };
+    if rc < 0 {
+        return Err(EPBotError::FfiError {
+            code: rc,
+            message: format!("setting convention '{}' failed: {}", key, get_last_error()),
+        });
+    }
+    Ok(())
+}
+
+/// The engine's index for style setting `name`, or an error naming it.
+fn style_index(instance: *mut c_void, name: &str) -> Result<i32, EPBotError> {
+    let unsupported = || EPBotError::ConventionError(format!("EPBot has no bidding style setting '{}'", name));
+    let name_c = CString::new(name).map_err(|_| unsupported())?;
+    let index = unsafe { ffi::epbot_convention_index(instance, name_c.as_ptr()) };
+    if index < 0 {
+        return Err(unsupported());
+    }
+    Ok(index)
+}
+
+/// Switch on style setting `name` for both partnerships of `instance`.
+fn apply_style(instance: *mut c_void, name: &str) -> Result<(), EPBotError> {
+    style_index(instance, name)?;
+    let name_c = CString::new(name).expect("checked by style_index");
+    for side in 0..2 {
+        let rc = unsafe { ffi::epbot_set_conventions(instance, side, name_c.as_ptr(), 1) };
+        if rc < 0 {
+            return Err(EPBotError::FfiError {
+                code: rc,
+                message: format!("setting bidding style '{}' failed: {}", name, get_last_error()),
+            });
+        }
+    }
+    Ok(())
+}
+
 /// Get the last FFI error message.
 fn get_last_error() -> String {
     unsafe {
@@ -389,78 +651,198 @@ pub fn generate_auction_with_options(
     auction_prefix: Option<&[String]>,
     single_dummy: bool,
 ) -> AuctionResult {
-    match generate_auction_inner(pbn, dealer, vulnerability, scoring, ns_card, ew_card, auction_prefix, single_dummy) {
-        Ok((bids, analysis)) => AuctionResult {
+    let options = AuctionOptions {
+        scoring,
+        ns_card,
+        ew_card,
+        auction_prefix,
+        single_dummy,
+        ..Default::default()
+    };
+    generate_auction_with(pbn, dealer, vulnerability, &options)
+}
+
+/// An `Engine` kept for the one-shot `generate_auction*` calls on one
+/// thread, with the convention cards it was set up with.
+struct CachedEngine {
+    engine: Engine,
+    cards: CardsKey,
+}
+
+thread_local! {
+    static CACHED_ENGINE: RefCell<Option<CachedEngine>> = const { RefCell::new(None) };
+}
+
+#[cfg(test)]
+thread_local! {
+    static ENGINES_CREATED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// The convention cards, overrides and style in `options`. Settings
+/// applied to an engine persist across deals, so the cached engine is only
+/// reused for the same cards.
+struct CardsKey {
+    ns: Option<Vec<String>>,
+    ew: Option<Vec<String>>,
+    seats: [Option<Vec<String>>; 4],
+    conventions: Vec<ConventionOverride>,
+    style: Option<String>,
+}
+
+impl CardsKey {
+    fn new(options: &AuctionOptions) -> Self {
+        let lines = |card: Option<&ConventionCard>| card.map(|c| c.lines.clone());
+        CardsKey {
+            ns: lines(options.ns_card),
+            ew: lines(options.ew_card),
+            seats: options.seat_cards.map(lines),
+            conventions: options.conventions.to_vec(),
+            style: options.style.map(str::to_string),
+        }
+    }
+
+    /// Whether `options` holds the same cards, compared in full.
+    fn matches(&self, options: &AuctionOptions) -> bool {
+        let same = |key: &Option<Vec<String>>, card: Option<&ConventionCard>| key.as_ref() == card.map(|c| &c.lines);
+        same(&self.ns, options.ns_card)
+            && same(&self.ew, options.ew_card)
+            && self.seats.iter().zip(options.seat_cards).all(|(key, card)| same(key, card))
+            && self.conventions == options.conventions
+            && self.style.as_deref() == options.style
+    }
+}
+
+/// Generate an auction using an `AuctionOptions` bundle. All other
+/// `generate_auction*` entry points delegate here.
+///
+/// Reuses a per-thread `Engine` while the convention cards stay the same,
+/// so calling this in a loop doesn't create and destroy four EPBot
+/// instances per deal. A new engine is made when the cards change or after
+/// a failed auction.
+pub fn generate_auction_with(
+    pbn: &str,
+    dealer: i32,
+    vulnerability: i32,
+    options: &AuctionOptions,
+) -> AuctionResult {
+    CACHED_ENGINE.with(|cell| {
+        let mut slot = cell.borrow_mut();
+        if slot.as_ref().is_none_or(|cached| !cached.cards.matches(options)) {
+            // Drop the old engine before creating its replacement.
+            *slot = None;
+            match Engine::new() {
+                Ok(engine) => *slot = Some(CachedEngine { engine, cards: CardsKey::new(options) }),
+                Err(e) => return to_auction_result(Err(e)),
+            }
+        }
+        let cached = slot.as_mut().expect("engine was just created");
+        let result = cached.engine.generate(pbn, dealer, vulnerability, options);
+        if !result.success {
+            *slot = None;
+        }
+        result
+    })
+}
+
+type InnerAuction = (Vec<BidInfo>, Option<SingleDummyAnalysis>, bool);
+
+fn to_auction_result(inner: Result<InnerAuction, EPBotError>) -> AuctionResult {
+    match inner {
+        Ok((bids, analysis, truncated)) => AuctionResult {
             bids,
             success: true,
             error: None,
+            failure: None,
             analysis,
+            truncated,
         },
         Err(e) => AuctionResult {
             bids: Vec::new(),
             success: false,
             error: Some(e.to_string()),
+            failure: Some(e),
             analysis: None,
+            truncated: false,
         },
     }
 }
 
-fn generate_auction_inner(
-    pbn: &str,
-    dealer: i32,
-    vulnerability: i32,
-    scoring: Scoring,
-    ns_card: Option<&ConventionCard>,
-    ew_card: Option<&ConventionCard>,
-    auction_prefix: Option<&[String]>,
-    single_dummy: bool,
-) -> Result<(Vec<BidInfo>, Option<SingleDummyAnalysis>), EPBotError> {
-    let (_first_seat, hands) = parse_pbn_deal(pbn)?;
-
-    // Create 4 EPBot instances — one per player
-    let mut players: [*mut c_void; 4] = [std::ptr::null_mut(); 4];
-    let empty_alert = CString::new("").unwrap();
+/// Four EPBot instances (one per seat) that can be reused across deals.
+///
+/// Each deal re-initialises every instance with `epbot_new_hand` and
+/// re-applies the convention cards, so reuse only saves the create/destroy
+/// cost. Pass the same cards for every deal: settings applied for one deal
+/// are not reset for the next. The raw handles keep `Engine` `!Send`.
+pub struct Engine {
+    players: [*mut c_void; 4],
+}
 
-    for i in 0..4 {
-        players[i] = unsafe { ffi::epbot_create() };
-        if players[i].is_null() {
-            // Clean up already-created instances
-            for j in 0..i {
-                unsafe { ffi::epbot_destroy(players[j]) };
+impl Engine {
+    /// Create the four EPBot instances.
+    pub fn new() -> Result<Self, EPBotError> {
+        let mut players: [*mut c_void; 4] = [std::ptr::null_mut(); 4];
+        for i in 0..4 {
+            players[i] = unsafe { ffi::epbot_create() };
+            if players[i].is_null() {
+                // Clean up already-created instances
+                for p in players.iter().take(i) {
+                    unsafe { ffi::epbot_destroy(*p) };
+                }
+                return Err(EPBotError::CreateFailed);
             }
-            return Err(EPBotError::CreateFailed);
         }
+        #[cfg(test)]
+        ENGINES_CREATED.with(|n| n.set(n.get() + 1));
+        Ok(Self { players })
     }
 
-    // Use a closure-like pattern to ensure cleanup on any error
-    let bids_result = run_auction(&players, &hands, dealer, vulnerability, scoring, ns_card, ew_card, &empty_alert, auction_prefix);
+    /// Generate an auction for one deal on these instances.
+    pub fn generate(
+        &mut self,
+        pbn: &str,
+        dealer: i32,
+        vulnerability: i32,
+        options: &AuctionOptions,
+    ) -> AuctionResult {
+        to_auction_result(self.generate_inner(pbn, dealer, vulnerability, options))
+    }
 
-    let final_result = match bids_result {
-        Ok(bids) => {
-            let analysis = if single_dummy {
-                match compute_single_dummy(&players, &hands, &bids) {
-                    Ok(a) => Some(a),
-                    Err(e) => {
-                        log::warn!("single-dummy analysis failed: {}", e);
-                        None
-                    }
+    fn generate_inner(
+        &mut self,
+        pbn: &str,
+        dealer: i32,
+        vulnerability: i32,
+        options: &AuctionOptions,
+    ) -> Result<InnerAuction, EPBotError> {
+        let (_first_seat, hands) = parse_pbn_deal(pbn)?;
+        let empty_alert = CString::new("").unwrap();
+
+        let (bids, truncated) =
+            run_auction(&self.players, &hands, dealer, vulnerability, options, &empty_alert)?;
+
+        let analysis = if options.single_dummy {
+            match compute_single_dummy(&self.players, &hands, &bids) {
+                Ok(a) => Some(a),
+                Err(e) => {
+                    log::warn!("single-dummy analysis failed: {}", e);
+                    None
                 }
-            } else {
-                None
-            };
-            Ok((bids, analysis))
-        }
-        Err(e) => Err(e),
-    };
+            }
+        } else {
+            None
+        };
+        Ok((bids, analysis, truncated))
+    }
+}
 
-    // Always destroy all instances
-    for p in &players {
-        if !p.is_null() {
-            unsafe { ffi::epbot_destroy(*p) };
+impl Drop for Engine {
+    fn drop(&mut self) {
+        for p in &self.players {
+            if !p.is_null() {
+                unsafe { ffi::epbot_destroy(*p) };
+            }
         }
     }
-
-    final_result
 }
 
 /// Estimate single-dummy tricks for the declaring side.
@@ -575,17 +957,21 @@ fn strain_of_bid(bid: &str) -> Option<usize> {
     }
 }
 
+/// Run the bidding loop. Returns the bids plus whether the auction was cut
+/// short by `options.stop_at_level`.
 fn run_auction(
     players: &[*mut c_void; 4],
     hands: &[String; 4],
     dealer: i32,
     vulnerability: i32,
-    scoring: Scoring,
-    ns_card: Option<&ConventionCard>,
-    ew_card: Option<&ConventionCard>,
+    options: &AuctionOptions,
     empty_alert: &CString,
-    auction_prefix: Option<&[String]>,
-) -> Result<Vec<BidInfo>, EPBotError> {
+) -> Result<(Vec<BidInfo>, bool), EPBotError> {
+    let scoring = options.scoring;
+    let ns_card = options.ns_card;
+    let ew_card = options.ew_card;
+    let auction_prefix = options.auction_prefix;
+
     // Initialize each player
     for i in 0..4 {
         let hand_c = CString::new(hands[i].as_str()).map_err(|e| {
@@ -621,6 +1007,15 @@ fn run_auction(
         if let Some(card) = ew_card {
             card.apply_to(players[i], 1)?;
         }
+        if let Some(card) = options.seat_cards[i] {
+            card.apply_to(players[i], (i % 2) as i32)?;
+        }
+        for o in options.conventions {
+            set_convention(players[i], o.side, &o.key, o.enabled)?;
+        }
+        if let Some(style) = options.style {
+            apply_style(players[i], style)?;
+        }
     }
 
     // Run the auction
@@ -639,6 +1034,8 @@ fn run_auction(
                 message: format!("Invalid auctionPrefix at index {}: {}", round, e),
             })?;
             (code, decode_bid(code))
+        } else if options.passing_side == Some((current_pos % 2) as u8) {
+            (0, decode_bid(0))
         } else {
             let code = unsafe { ffi::epbot_get_bid(players[current_pos as usize]) };
             if code < 0 {
@@ -730,6 +1127,13 @@ fn run_auction(
             is_alert,
         });
 
+        // Stop-at-level: cut the auction on the call that reaches the level.
+        if let Some(limit) = options.stop_at_level {
+            if (5..=39).contains(&bid_code) && (bid_code - 5) / 5 + 1 >= limit as i32 {
+                return Ok((bids, true));
+            }
+        }
+
         // Track passes for auction termination
         if bid_str == "Pass" {
             pass_count += 1;
@@ -746,13 +1150,82 @@ fn run_auction(
         current_pos = (current_pos + 1) % 4;
     }
 
-    Ok(bids)
+    Ok((bids, false))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_convention_settings() {
+        let card = ConventionCard::from_content(
+            "System type = 2\n# comment\nStayman = 1\nJacoby 2NT = 0\nOpponent type = 1\nGerber = true\nName = Acol\n",
+        );
+        assert_eq!(
+            card.settings(),
+            vec![
+                ConventionSetting::SystemType(2),
+                ConventionSetting::Convention { key: "Stayman".into(), enabled: true },
+                ConventionSetting::Convention { key: "Jacoby 2NT".into(), enabled: false },
+                ConventionSetting::OpponentType(1),
+                ConventionSetting::Convention { key: "Gerber".into(), enabled: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_card_check() {
+        let card = ConventionCard::from_content(
+            "# comment\nSystem type = 1\nStayman = 1\nGerber = 2\nDrury = yes\nnonsense\n\nBogus = 0\nstayman = 0\nJacoby 2NT = false\n",
+        );
+        let problems: Vec<(usize, String)> = card
+            .check_with(|key| key != "Bogus")
+            .into_iter()
+            .map(|p| (p.line, p.message))
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                (4, "Gerber = 2: a convention is on (1) or off (0); 2 reads as off".to_string()),
+                (5, "Drury = yes: value is not a number or true/false".to_string()),
+                (6, "'nonsense' is not a 'key = value' line".to_string()),
+                (8, "unknown convention 'Bogus'".to_string()),
+                (9, "stayman is already set on line 3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_one_shot_calls_reuse_engine() {
+        let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+        let options = AuctionOptions::default();
+        let before = ENGINES_CREATED.with(|n| n.get());
+
+        let first = generate_auction_with(pbn, 0, 0, &options);
+        assert!(first.success, "{:?}", first.error);
+        for _ in 1..1000 {
+            let result = generate_auction_with(pbn, 0, 0, &options);
+            assert_eq!(
+                result.bids.iter().map(|b| &b.bid).collect::<Vec<_>>(),
+                first.bids.iter().map(|b| &b.bid).collect::<Vec<_>>()
+            );
+        }
+
+        assert_eq!(ENGINES_CREATED.with(|n| n.get()) - before, 1);
+
+        // Different cards get a fresh engine.
+        let card = ConventionCard { lines: vec!["System type = 0".to_string()] };
+        let other = AuctionOptions { ns_card: Some(&card), ..options };
+        generate_auction_with(pbn, 0, 0, &other);
+        assert_eq!(ENGINES_CREATED.with(|n| n.get()) - before, 2);
+
+        // An equal card is the same cards, wherever it is held.
+        let copy = card.clone();
+        generate_auction_with(pbn, 0, 0, &AuctionOptions { ns_card: Some(&copy), ..options });
+        assert_eq!(ENGINES_CREATED.with(|n| n.get()) - before, 2);
+    }
+
     #[test]
     fn test_decode_bid() {
         assert_eq!(decode_bid(0), "Pass");
@@ -32,6 +32,17 @@ impl Strain {
         }
     }
 
+    /// Position in double-dummy trick tables: C, D, H, S, NT = 0..4.
+    pub fn index(self) -> usize {
+        match self {
+            Strain::Clubs => 0,
+            Strain::Diamonds => 1,
+            Strain::Hearts => 2,
+            Strain::Spades => 3,
+            Strain::NoTrump => 4,
+        }
+    }
+
     fn is_minor(self) -> bool {
         matches!(self, Strain::Clubs | Strain::Diamonds)
     }
@@ -202,6 +213,184 @@ pub fn score_for_ns(
     if declarer_is_ns { raw } else { -raw }
 }
 
+/// NS score of `contract` ("4S", "3NTX", "Pass") by `declarer` (0=N, 1=E,
+/// 2=S, 3=W) taking `tricks` tricks. A passed-out board scores 0; None if
+/// the contract can't be read.
+pub fn contract_score_ns(contract: &str, declarer: u8, vul_code: u8, tricks: u8) -> Option<i32> {
+    if contract.trim().eq_ignore_ascii_case("pass") {
+        return Some(0);
+    }
+    let (level, strain, doubled) = parse_contract(contract)?;
+    Some(score_for_ns(level, strain, doubled, declarer, vul_code, tricks))
+}
+
+/// NS score of `contract` by `declarer` played double dummy, taking the
+/// tricks from a table laid out as for `par_score_ns`.
+pub fn dd_score_ns(contract: &str, declarer: u8, vul_code: u8, tricks: &[[u8; 5]; 4]) -> Option<i32> {
+    let taken = match parse_contract(contract) {
+        Some((_, strain, _)) => tricks[declarer as usize % 4][strain.index()],
+        None => 0,
+    };
+    contract_score_ns(contract, declarer, vul_code, taken)
+}
+
+/// Matchpoints for each NS score on one board: one for every other score
+/// it beats and a half for every tie. The EW pair of the same table gets
+/// `scores.len() - 1` less NS's matchpoints.
+pub fn matchpoints(scores: &[i32]) -> Vec<f64> {
+    scores
+        .iter()
+        .map(|&score| {
+            let beaten = scores.iter().filter(|&&other| other < score).count();
+            let tied = scores.iter().filter(|&&other| other == score).count() - 1;
+            beaten as f64 + tied as f64 / 2.0
+        })
+        .collect()
+}
+
+/// Convert a score difference to IMPs using the standard WBF scale.
+/// The sign of `diff` is kept.
+pub fn imps(diff: i32) -> i32 {
+    const SCALE: [i32; 24] = [
+        20, 50, 90, 130, 170, 220, 270, 320, 370, 430, 500, 600, 750, 900, 1100, 1300, 1500,
+        1750, 2000, 2250, 2500, 3000, 3500, 4000,
+    ];
+    let n = SCALE.iter().take_while(|&&t| diff.abs() >= t).count() as i32;
+    if diff < 0 { -n } else { n }
+}
+
+/// Double-dummy par score from the NS pair's perspective.
+///
+/// `tricks[declarer][strain]` holds double-dummy tricks for each declarer
+/// (0=N, 1=E, 2=S, 3=W) and strain (C, D, H, S, NT). The sides compete up
+/// the bidding ladder, each choosing between passing and overbidding the
+/// other; a contract that fails is assumed doubled. Each side declares with
+/// its better hand in the strain, and the dealer's side may open first.
+pub fn par_score_ns(tricks: &[[u8; 5]; 4], vul_code: u8, dealer: u8) -> i32 {
+    par_ns(tricks, vul_code, dealer).score
+}
+
+/// A par contract and the seats that can declare it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParContract {
+    pub level: u8,
+    pub strain: Strain,
+    pub doubled: Doubled,
+    /// Seats (0=N, 1=E, 2=S, 3=W) taking the most tricks in the strain.
+    pub declarers: Vec<u8>,
+}
+
+impl std::fmt::Display for ParContract {
+    /// "NS 4S", "N 3NT", "EW 5HX".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for &seat in &self.declarers {
+            write!(f, "{}", b"NESW"[seat as usize] as char)?;
+        }
+        let strain = match self.strain {
+            Strain::Clubs => "C",
+            Strain::Diamonds => "D",
+            Strain::Hearts => "H",
+            Strain::Spades => "S",
+            Strain::NoTrump => "NT",
+        };
+        let doubled = match self.doubled {
+            Doubled::Undoubled => "",
+            Doubled::Doubled => "X",
+            Doubled::Redoubled => "XX",
+        };
+        write!(f, " {}{}{}", self.level, strain, doubled)
+    }
+}
+
+/// Double-dummy par: the NS score and the contracts that reach it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Par {
+    pub score: i32,
+    /// The lowest contract in each strain, for each side, that scores par
+    /// and that the other side can't profitably overbid. Empty when par is
+    /// a pass-out.
+    pub contracts: Vec<ParContract>,
+}
+
+impl std::fmt::Display for Par {
+    /// The contracts, comma-separated ("NS 6C, NS 6D"), or "Pass".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.contracts.is_empty() {
+            return write!(f, "Pass");
+        }
+        for (i, contract) in self.contracts.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", contract)?;
+        }
+        Ok(())
+    }
+}
+
+/// Double-dummy par score and contracts; see `par_score_ns`.
+pub fn par_ns(tricks: &[[u8; 5]; 4], vul_code: u8, dealer: u8) -> Par {
+    const STRAINS: [Strain; 5] = [
+        Strain::Clubs,
+        Strain::Diamonds,
+        Strain::Hearts,
+        Strain::Spades,
+        Strain::NoTrump,
+    ];
+    // Side 0 = NS (seats 0, 2), side 1 = EW (seats 1, 3). NS maximizes.
+    let prefer = |side: usize, a: i32, b: i32| if side == 0 { a.max(b) } else { a.min(b) };
+    // NS score, and whether it is doubled, when `side` declares contract
+    // `i` (0 = 1C ... 34 = 7NT).
+    let contract_score = |i: usize, side: usize| {
+        let level = (i / 5 + 1) as u8;
+        let strain = i % 5;
+        let declarer = if tricks[side][strain] >= tricks[side + 2][strain] { side } else { side + 2 };
+        let taken = tricks[declarer][strain].min(13);
+        let doubled = if taken >= level + 6 { Doubled::Undoubled } else { Doubled::Doubled };
+        (score_for_ns(level, STRAINS[strain], doubled, declarer as u8, vul_code, taken), doubled)
+    };
+
+    // value[i][side]: final NS score once `side` holds contract i and the
+    // other side decides whether to pass or overbid.
+    let mut value = [[0i32; 2]; 35];
+    for i in (0..35).rev() {
+        for side in 0..2 {
+            let other = 1 - side;
+            value[i][side] = (i + 1..35).fold(contract_score(i, side).0, |v, j| {
+                prefer(other, v, value[j][other])
+            });
+        }
+    }
+
+    let open = |side: usize, pass: i32| (0..35).fold(pass, |v, j| prefer(side, v, value[j][side]));
+    let dealer_side = (dealer % 2) as usize;
+    let other_opens = open(1 - dealer_side, 0);
+    let score = open(dealer_side, other_opens);
+
+    let mut contracts = Vec::new();
+    for side in 0..2 {
+        for (strain, &name) in STRAINS.iter().enumerate() {
+            let stable = (strain..35)
+                .step_by(5)
+                .find(|&i| value[i][side] == score && contract_score(i, side).0 == score);
+            if let Some(i) = stable {
+                let best = tricks[side][strain].max(tricks[side + 2][strain]);
+                contracts.push(ParContract {
+                    level: (i / 5 + 1) as u8,
+                    strain: name,
+                    doubled: contract_score(i, side).1,
+                    declarers: [side, side + 2]
+                        .into_iter()
+                        .filter(|&seat| tricks[seat][strain] == best)
+                        .map(|seat| seat as u8)
+                        .collect(),
+                });
+            }
+        }
+    }
+    Par { score, contracts }
+}
+
 /// Parse a contract string like "3NT", "4S", "5HX", "7DXX", "Pass" into its
 /// component parts. Returns `None` for "Pass" or malformed input.
 pub fn parse_contract(s: &str) -> Option<(u8, Strain, Doubled)> {
@@ -363,6 +552,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn imps_scale() {
+        assert_eq!(imps(0), 0);
+        assert_eq!(imps(10), 0);
+        assert_eq!(imps(20), 1);
+        assert_eq!(imps(-420), -9);
+        assert_eq!(imps(620 - 170), 10);
+        assert_eq!(imps(5000), 24);
+    }
+
+    #[test]
+    fn scores_contract_strings() {
+        assert_eq!(contract_score_ns("4S", 2, 2, 10), Some(620));
+        assert_eq!(contract_score_ns("3NTX", 1, 0, 8), Some(100));
+        assert_eq!(contract_score_ns("Pass", 0, 3, 0), Some(0));
+        assert_eq!(contract_score_ns("8S", 0, 0, 13), None);
+
+        let mut t = [[3u8; 5]; 4];
+        t[0] = [7, 7, 7, 10, 8];
+        assert_eq!(dd_score_ns("4S", 0, 0, &t), Some(420));
+        assert_eq!(dd_score_ns("3NT", 0, 0, &t), Some(-50));
+        assert_eq!(dd_score_ns("2H", 1, 0, &t), Some(250));
+        assert_eq!(dd_score_ns("pass", 0, 0, &t), Some(0));
+    }
+
+    #[test]
+    fn matchpoints_share_ties() {
+        assert_eq!(matchpoints(&[420, 450, 420, -50]), [1.5, 3.0, 1.5, 0.0]);
+        assert_eq!(matchpoints(&[100]), [0.0]);
+        assert!(matchpoints(&[]).is_empty());
+    }
+
+    #[test]
+    fn par_uncontested_game() {
+        // NS make 10 tricks in spades and 8 in NT; EW can make nothing.
+        let mut t = [[3u8; 5]; 4];
+        t[0] = [7, 7, 7, 10, 8];
+        t[2] = t[0];
+        assert_eq!(par_score_ns(&t, 0, 0), 420);
+        assert_eq!(par_score_ns(&t, 2, 1), 620);
+    }
+
+    #[test]
+    fn par_with_profitable_sacrifice() {
+        // NS make 4S (vul, +620); EW, not vul, go down 2 in 5H doubled (-300).
+        let mut t = [[3u8; 5]; 4];
+        t[0] = [6, 6, 3, 10, 7];
+        t[2] = t[0];
+        t[1] = [4, 4, 9, 3, 4];
+        t[3] = t[1];
+        assert_eq!(par_score_ns(&t, 2, 0), 300);
+    }
+
+    #[test]
+    fn par_contracts() {
+        let mut t = [[3u8; 5]; 4];
+        t[0] = [7, 7, 7, 10, 8];
+        t[2] = [7, 7, 7, 10, 9];
+        let par = par_ns(&t, 0, 0);
+        assert_eq!(par.score, 420);
+        assert_eq!(par.to_string(), "NS 4S");
+
+        // The sacrifice above: EW go down two in 5H doubled.
+        let mut t = [[3u8; 5]; 4];
+        t[0] = [6, 6, 3, 10, 7];
+        t[2] = t[0];
+        t[1] = [4, 4, 9, 3, 4];
+        t[3] = [4, 4, 8, 3, 4];
+        assert_eq!(par_ns(&t, 2, 0).to_string(), "E 5HX");
+
+        // Nobody can make anything.
+        assert_eq!(par_ns(&[[6u8; 5]; 4], 0, 0).to_string(), "Pass");
+    }
+
     #[test]
     fn declarer_vulnerable_table() {
         // (vul, declarer) → vulnerable?
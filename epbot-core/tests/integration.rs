@@ -82,3 +82,151 @@ fn test_generate_auction_with_conventions() {
 
     assert!(result.success, "Auction with conventions should succeed: {:?}", result.error);
 }
+
+#[test]
+fn test_stop_at_level_truncates_auction() {
+    let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+    let options = AuctionOptions {
+        stop_at_level: Some(1),
+        ..Default::default()
+    };
+    let result = generate_auction_with(pbn, 0, 0, &options);
+
+    assert!(result.success, "Auction should succeed: {:?}", result.error);
+    assert!(result.truncated, "Auction should be flagged truncated");
+    let last = result.bids.last().expect("at least one bid");
+    assert!(
+        (5..=39).contains(&last.code),
+        "Truncated auction should end on the contract bid, got {}",
+        last.bid
+    );
+}
+
+#[test]
+fn test_passing_side_forces_passes() {
+    let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+    let options = AuctionOptions {
+        passing_side: Some(1),
+        ..Default::default()
+    };
+    let result = generate_auction_with(pbn, 0, 0, &options);
+
+    assert!(result.success, "Auction should succeed: {:?}", result.error);
+    for bid in result.bids.iter().filter(|b| b.position % 2 == 1) {
+        assert_eq!(bid.bid, "Pass", "EW should only pass, got {}", bid.bid);
+    }
+}
+
+#[test]
+fn test_seat_cards_override_the_partnership_card() {
+    let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+    let sayc = ConventionCard::from_content(&std::fs::read_to_string("../conventions/Sayc.bbsa").unwrap());
+    let gf = ConventionCard::from_content(&std::fs::read_to_string("../conventions/21GF.bbsa").unwrap());
+    let calls = |options: &AuctionOptions| -> Vec<String> {
+        let result = generate_auction_with(pbn, 0, 0, options);
+        assert!(result.success, "Auction should succeed: {:?}", result.error);
+        result.bids.into_iter().map(|b| b.bid).collect()
+    };
+
+    // Every seat overridden with the same card bids as the partnership cards.
+    let both = AuctionOptions {
+        ns_card: Some(&gf),
+        ew_card: Some(&gf),
+        ..Default::default()
+    };
+    let seats = AuctionOptions {
+        ns_card: Some(&sayc),
+        ew_card: Some(&sayc),
+        seat_cards: [Some(&gf); 4],
+        ..Default::default()
+    };
+    assert_eq!(calls(&seats), calls(&both));
+
+    let mixed = AuctionOptions {
+        seat_cards: [Some(&sayc), None, Some(&gf), None],
+        ..both
+    };
+    assert!(calls(&mixed).len() >= 4);
+}
+
+#[test]
+fn test_convention_overrides() {
+    let card = ConventionCard::from_content(&std::fs::read_to_string("../conventions/21GF.bbsa").unwrap());
+    let key = match card.settings().into_iter().find(|s| matches!(s, ConventionSetting::Convention { .. })) {
+        Some(ConventionSetting::Convention { key, .. }) => key,
+        _ => panic!("card has no conventions"),
+    };
+    check_convention(&key).expect("a key from the card is known");
+    let err = check_convention("No such convention").expect_err("unknown key should fail");
+    assert!(matches!(err, EPBotError::ConventionError(_)));
+
+    let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+    let overrides = [ConventionOverride { side: 0, key, enabled: true }];
+    let options = AuctionOptions {
+        ns_card: Some(&card),
+        conventions: &overrides,
+        ..Default::default()
+    };
+    let result = generate_auction_with(pbn, 0, 0, &options);
+    assert!(result.success, "Auction should succeed: {:?}", result.error);
+
+    let unknown = [ConventionOverride { side: 1, key: "No such convention".into(), enabled: true }];
+    let result = generate_auction_with(pbn, 0, 0, &AuctionOptions { conventions: &unknown, ..options });
+    assert!(!result.success);
+    assert!(matches!(result.failure, Some(EPBotError::ConventionError(_))));
+}
+
+#[test]
+fn test_card_check_against_the_engine() {
+    let check = |path: &str| -> Vec<(usize, String)> {
+        let card = ConventionCard::from_content(&std::fs::read_to_string(path).unwrap());
+        card.check().expect("engine created").into_iter().map(|p| (p.line, p.message)).collect()
+    };
+    for path in ["../tests/fixtures/21GF-DEFAULT.bbsa", "../tests/fixtures/21GF-GIB.bbsa"] {
+        assert_eq!(check(path), Vec::new(), "{}", path);
+    }
+    // The older cards name two settings this EPBot build has since split up.
+    for path in ["../conventions/21GF.bbsa", "../conventions/Sayc.bbsa"] {
+        assert_eq!(
+            check(path),
+            [
+                (50, "unknown convention 'Direct Jump Cuebid'".to_string()),
+                (120, "unknown convention 'Transfers if RHO passes'".to_string()),
+            ],
+            "{}",
+            path
+        );
+    }
+    let card = ConventionCard::from_content("Forcing 1NT = 1\nNo such convention = 1\n");
+    let problems = card.check().expect("engine created");
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].line, 2);
+}
+
+#[test]
+fn test_unknown_hand_is_rejected() {
+    let pbn = "N:A653.Q97.K64.954 - JT987.65.QT85.K3 -";
+    let result = generate_auction_with(pbn, 0, 0, &AuctionOptions::default());
+
+    assert!(!result.success);
+    assert!(matches!(result.failure, Some(EPBotError::InvalidDeal(_))));
+    assert_eq!(result.error_code(), None);
+    let err = result.error.expect("error message");
+    assert!(err.contains("Hand 1 is unknown"), "unexpected error: {}", err);
+}
+
+#[test]
+fn test_unsupported_style_is_rejected() {
+    let err = check_style("No such style").expect_err("unknown style should fail");
+    assert!(matches!(err, EPBotError::ConventionError(_)));
+    assert!(err.to_string().contains("'No such style'"), "unexpected error: {}", err);
+
+    let pbn = "N:A653.Q97.K64.954 KQ4.AT8432.A72.A JT987.65.QT85.K3 2.KJ.J93.QJT8762";
+    let options = AuctionOptions {
+        style: Some("No such style"),
+        ..Default::default()
+    };
+    let result = generate_auction_with(pbn, 0, 0, &options);
+    assert!(!result.success);
+    assert!(matches!(result.failure, Some(EPBotError::ConventionError(_))));
+}